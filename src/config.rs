@@ -0,0 +1,665 @@
+//! Envlang project configuration
+//!
+//! Project-wide settings are read from an `envlang.toml` file, so that they do not have to be repeated on every invocation of the CLI.
+//!
+//! The CLI looks for the file in the directory of the script being run, and then in each parent directory in turn (see [`find_config_file`]).
+//! Command-line flags always take precedence over the values in the file.
+//!
+//! # File format
+//!
+//! The configuration file is written in a small subset of TOML:
+//! * `key = value` pairs, where a value is a string, an integer, a boolean, or a single-line array of these.
+//! * `[table]` headers.
+//! * Comments starting with `#`.
+//!
+//! ```toml
+//! strict = true
+//! strict-braces = true
+//! deny-warnings = false
+//!
+//! [lints]
+//! unused-binding = "deny"
+//!
+//! [modules]
+//! search-paths = ["lib", "vendor/envl"]
+//!
+//! [limits]
+//! max-file-size = 1048576
+//! max-diagnostics = 50
+//! max-string-length = 65536
+//! max-number-digits = 64
+//!
+//! [files]
+//! extensions = ["envl", "env"]
+//! check-extension = true
+//!
+//! [operators]
+//! aliases = ["and", "or", "=!"]
+//! ```
+//!
+//! Unknown tables and keys, including the names of unknown lints, are rejected to catch typos early.
+
+use crate::io::{ExtensionPolicy, ModuleResolver};
+use crate::lexer::LexerOptions;
+use crate::lint::find_lint;
+use crate::messages::Catalog;
+use crate::symbols::OperatorAliases;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+/// The name of the project configuration file
+pub const CONFIG_FILE_NAME: &str = "envlang.toml";
+
+/// Severity level assigned to a lint
+///
+/// The enum derives the traits `Debug`, `Clone`, `Copy`, `PartialEq`, and `Eq`, and implements [`ToString`](LintLevel::to_string).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    /// The lint is not reported
+    Allow,
+    /// The lint is reported as a warning
+    Warn,
+    /// The lint is reported as an error
+    Deny,
+}
+
+impl LintLevel {
+    /// Parses a lint level from its configuration name (`allow`, `warn`, or `deny`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+impl ToString for LintLevel {
+    fn to_string(&self) -> String {
+        match self {
+            LintLevel::Allow => "allow".to_string(),
+            LintLevel::Warn => "warn".to_string(),
+            LintLevel::Deny => "deny".to_string(),
+        }
+    }
+}
+
+/// Resource limits applied while running a script
+///
+/// A limit of `None` means that the resource is unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum size of a script file in bytes
+    pub max_file_size: Option<u64>,
+    /// Maximum number of diagnostics reported for a script
+    pub max_diagnostics: Option<usize>,
+    /// Maximum length of a string literal in bytes (see [`LexerOptions::max_string_length`])
+    pub max_string_length: Option<usize>,
+    /// Maximum number of digits in a number literal (see [`LexerOptions::max_number_digits`])
+    pub max_number_digits: Option<usize>,
+}
+
+impl Limits {
+    /// Gets the [`LexerOptions`] enforcing the literal size limits
+    pub fn lexer_options(&self) -> LexerOptions {
+        return LexerOptions { max_string_length: self.max_string_length, max_number_digits: self.max_number_digits };
+    }
+}
+
+/// Project configuration
+///
+/// The default configuration is non-strict, leaves the handling of unbalanced braces to the caller, has no lint overrides, no module search paths, and no resource limits, only accepts `.envl` scripts, and enables no operator aliases.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    /// Whether strict mode is enabled
+    pub strict: bool,
+    /// Whether unbalanced braces are parser errors (see [`ParserOptions::strict_braces`](crate::parser::ParserOptions::strict_braces)), or `None` to use the default of the caller: strict in the CLI, and lenient in [`check_source`](crate::check_source)
+    pub strict_braces: Option<bool>,
+    /// Whether warnings are reported as errors
+    pub deny_warnings: bool,
+    /// Lint level overrides, keyed by lint name
+    pub lints: BTreeMap<String, LintLevel>,
+    /// Directories searched for imported modules, in order of priority
+    pub module_paths: Vec<PathBuf>,
+    /// Resource limits
+    pub limits: Limits,
+    /// File extensions accepted for scripts
+    pub extensions: ExtensionPolicy,
+    /// Alternative spellings of operators recognised in scripts
+    pub operator_aliases: OperatorAliases,
+    /// Message templates for diagnostics, set by embedders (not read from the configuration file)
+    pub messages: Catalog,
+}
+
+/// Error type for reading configuration files
+///
+/// Syntax and value errors contain the one-indexed line number of the offending line.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),                // (path, error)
+    Syntax(usize, String),                      // (line, message)
+    UnknownTable(usize, String),                // (line, table)
+    UnknownKey(usize, String),                  // (line, key)
+    DuplicateKey(usize, String),                // (line, key)
+    InvalidValue(usize, String, String),        // (line, key, expected)
+}
+
+impl Error for ConfigError {}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) =>
+                write!(f, "Config error: Could not read '{}': {}", path.display(), err),
+            ConfigError::Syntax(line, message) =>
+                write!(f, "Config error at line {}: {}", line, message),
+            ConfigError::UnknownTable(line, table) =>
+                write!(f, "Config error at line {}: Unknown table '[{}]'", line, table),
+            ConfigError::UnknownKey(line, key) =>
+                write!(f, "Config error at line {}: Unknown key '{}'", line, key),
+            ConfigError::DuplicateKey(line, key) =>
+                write!(f, "Config error at line {}: Key '{}' is defined more than once", line, key),
+            ConfigError::InvalidValue(line, key, expected) =>
+                write!(f, "Config error at line {}: Expected {} for key '{}'", line, expected, key),
+        }
+    }
+}
+
+/// Values recognised by the configuration file parser
+#[derive(Debug, Clone, PartialEq)]
+enum TomlValue {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<TomlValue>),
+}
+
+impl Config {
+    /// Parses a configuration from the contents of an `envlang.toml` file
+    ///
+    /// Relative module search paths are kept as-is; use [`Config::load`] to resolve them against the location of the file.
+    ///
+    /// # Errors
+    /// * [`ConfigError::Syntax`]: A line is not a table header, a key-value pair, a comment, or empty.
+    /// * [`ConfigError::UnknownTable`] and [`ConfigError::UnknownKey`]: The file contains a setting Envlang does not recognise.
+    /// * [`ConfigError::DuplicateKey`]: The same setting is given twice.
+    /// * [`ConfigError::InvalidValue`]: A setting has a value of the wrong type.
+    pub fn parse(source: &str) -> Result<Self, ConfigError> {
+        let mut config = Config::default();
+        let mut table: String = String::new();
+        let mut seen: Vec<String> = Vec::new();
+
+        for (idx, raw_line) in source.lines().enumerate() {
+            let line_no = idx + 1;
+            let line = strip_comment(raw_line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let name = header.strip_suffix(']')
+                    .ok_or_else(|| ConfigError::Syntax(line_no, format!("Unclosed table header '{}'", line)))?
+                    .trim();
+                match name {
+                    "lints" | "modules" | "limits" | "files" | "operators" => table = name.to_string(),
+                    _ => return Err(ConfigError::UnknownTable(line_no, name.to_string())),
+                }
+                continue;
+            }
+
+            let (key, value) = line.split_once('=')
+                .ok_or_else(|| ConfigError::Syntax(line_no, format!("Expected 'key = value', found '{}'", line)))?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(ConfigError::Syntax(line_no, "Missing key before '='".to_string()));
+            }
+            let value = parse_value(value.trim(), line_no)?;
+
+            let qualified = if table.is_empty() { key.to_string() } else { format!("{}.{}", table, key) };
+            if seen.contains(&qualified) {
+                return Err(ConfigError::DuplicateKey(line_no, qualified));
+            }
+            seen.push(qualified.clone());
+
+            config.apply(&table, key, value, line_no, &qualified)?;
+        }
+
+        return Ok(config);
+    }
+
+    /// Reads and parses a configuration file
+    ///
+    /// Relative module search paths are resolved against the directory containing the file.
+    ///
+    /// # Errors
+    /// * [`ConfigError::Io`]: The file could not be read.
+    /// * Any errors bubbled up from [`Config::parse`].
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let source = read_to_string(path).map_err(|err| ConfigError::Io(path.to_path_buf(), err))?;
+        let mut config = Config::parse(&source)?;
+        if let Some(base) = path.parent() {
+            config.module_paths = config.module_paths.into_iter()
+                .map(|p| if p.is_relative() { base.join(p) } else { p })
+                .collect();
+        }
+        return Ok(config);
+    }
+
+    /// Gets a [`ModuleResolver`] searching the configured module paths
+    pub fn module_resolver(&self) -> ModuleResolver {
+        return ModuleResolver::new(self.module_paths.clone());
+    }
+
+    /// Stores a single parsed setting in the configuration
+    fn apply(&mut self, table: &str, key: &str, value: TomlValue, line: usize, qualified: &str) -> Result<(), ConfigError> {
+        let invalid = |expected: &str| ConfigError::InvalidValue(line, qualified.to_string(), expected.to_string());
+        match (table, key) {
+            ("", "strict") => match value {
+                TomlValue::Boolean(b) => self.strict = b,
+                _ => return Err(invalid("a boolean")),
+            },
+            ("", "strict-braces") => match value {
+                TomlValue::Boolean(b) => self.strict_braces = Some(b),
+                _ => return Err(invalid("a boolean")),
+            },
+            ("", "deny-warnings") => match value {
+                TomlValue::Boolean(b) => self.deny_warnings = b,
+                _ => return Err(invalid("a boolean")),
+            },
+            ("lints", _) if find_lint(key).is_none() => return Err(ConfigError::UnknownKey(line, qualified.to_string())),
+            ("lints", _) => match value {
+                TomlValue::String(level) => {
+                    let level = LintLevel::from_name(&level).ok_or_else(|| invalid("\"allow\", \"warn\", or \"deny\""))?;
+                    self.lints.insert(key.to_string(), level);
+                },
+                _ => return Err(invalid("\"allow\", \"warn\", or \"deny\"")),
+            },
+            ("modules", "search-paths") => match value {
+                TomlValue::Array(items) => {
+                    for item in items {
+                        match item {
+                            TomlValue::String(path) => self.module_paths.push(PathBuf::from(path)),
+                            _ => return Err(invalid("an array of strings")),
+                        }
+                    }
+                },
+                _ => return Err(invalid("an array of strings")),
+            },
+            ("limits", "max-file-size") => match value {
+                TomlValue::Integer(n) if n >= 0 => self.limits.max_file_size = Some(n as u64),
+                _ => return Err(invalid("a non-negative integer")),
+            },
+            ("limits", "max-diagnostics") => match value {
+                TomlValue::Integer(n) if n >= 0 => self.limits.max_diagnostics = Some(n as usize),
+                _ => return Err(invalid("a non-negative integer")),
+            },
+            ("limits", "max-string-length") => match value {
+                TomlValue::Integer(n) if n >= 0 => self.limits.max_string_length = Some(n as usize),
+                _ => return Err(invalid("a non-negative integer")),
+            },
+            ("limits", "max-number-digits") => match value {
+                TomlValue::Integer(n) if n >= 0 => self.limits.max_number_digits = Some(n as usize),
+                _ => return Err(invalid("a non-negative integer")),
+            },
+            ("files", "extensions") => match value {
+                TomlValue::Array(items) => {
+                    let mut extensions = ExtensionPolicy::Allow(Vec::new());
+                    for item in items {
+                        match item {
+                            TomlValue::String(ext) => extensions.allow(&ext),
+                            _ => return Err(invalid("an array of strings")),
+                        }
+                    }
+                    // Disabling the check takes precedence regardless of the order of the keys
+                    if self.extensions != ExtensionPolicy::Any {
+                        self.extensions = extensions;
+                    }
+                },
+                _ => return Err(invalid("an array of strings")),
+            },
+            ("files", "check-extension") => match value {
+                TomlValue::Boolean(false) => self.extensions = ExtensionPolicy::Any,
+                TomlValue::Boolean(true) => (),
+                _ => return Err(invalid("a boolean")),
+            },
+            ("operators", "aliases") => match value {
+                TomlValue::Array(items) => {
+                    for item in items {
+                        match item {
+                            TomlValue::String(alias) if self.operator_aliases.enable(&alias) => (),
+                            _ => return Err(invalid("an array of operator aliases (\"=!\", \"and\", \"or\", or \"not\")")),
+                        }
+                    }
+                },
+                _ => return Err(invalid("an array of operator aliases (\"=!\", \"and\", \"or\", or \"not\")")),
+            },
+            _ => return Err(ConfigError::UnknownKey(line, qualified.to_string())),
+        }
+        Ok(())
+    }
+}
+
+/// Finds the nearest `envlang.toml`, starting from `start` and walking up through its ancestors
+///
+/// Returns `None` if no configuration file exists in any of the directories.
+pub fn find_config_file(start: &Path) -> Option<PathBuf> {
+    start.ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Finds the nearest `envlang.toml` like [`find_config_file`], but stops after searching `root`
+///
+/// If `root` is not an ancestor of `start`, all ancestors are searched.
+pub fn find_config_file_within(start: &Path, root: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let candidate = dir.join(CONFIG_FILE_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if dir == root {
+            break;
+        }
+    }
+    return None;
+}
+
+/// Writes a string as a configuration value, escaping it so that [`Config::parse`] reads back the same string
+pub fn quote(text: &str) -> String {
+    let mut result = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            _ => result.push(ch),
+        }
+    }
+    result.push('"');
+    return result;
+}
+
+/// Removes a trailing comment from a line, ignoring `#` symbols inside strings
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, ch) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => (),
+        }
+    }
+    return line;
+}
+
+/// Parses the right-hand side of a key-value pair
+fn parse_value(text: &str, line: usize) -> Result<TomlValue, ConfigError> {
+    if text.is_empty() {
+        return Err(ConfigError::Syntax(line, "Missing value after '='".to_string()));
+    }
+
+    if let Some(inner) = text.strip_prefix('[') {
+        let inner = inner.strip_suffix(']')
+            .ok_or_else(|| ConfigError::Syntax(line, format!("Unclosed array '{}'", text)))?;
+        let mut items: Vec<TomlValue> = Vec::new();
+        for item in split_array_items(inner, line)? {
+            items.push(parse_value(item, line)?);
+        }
+        return Ok(TomlValue::Array(items));
+    }
+
+    if let Some(inner) = text.strip_prefix('"') {
+        let end = closing_quote(inner)
+            .ok_or_else(|| ConfigError::Syntax(line, format!("Unterminated string '{}'", text)))?;
+        let rest = inner[end + 1..].trim();
+        if !rest.is_empty() {
+            return Err(ConfigError::Syntax(line, format!("Unexpected '{}' after string", rest)));
+        }
+        return Ok(TomlValue::String(unescape(&inner[..end], line)?));
+    }
+
+    match text {
+        "true" => return Ok(TomlValue::Boolean(true)),
+        "false" => return Ok(TomlValue::Boolean(false)),
+        _ => (),
+    }
+
+    text.replace('_', "")
+        .parse::<i64>()
+        .map(TomlValue::Integer)
+        .map_err(|_| ConfigError::Syntax(line, format!("Unrecognised value '{}'", text)))
+}
+
+/// Finds the byte index of the first unescaped '"' in the contents of a string
+fn closing_quote(inner: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (idx, ch) in inner.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' => escaped = true,
+            '"' => return Some(idx),
+            _ => (),
+        }
+    }
+    return None;
+}
+
+/// Splits the contents of a single-line array on commas outside of strings
+///
+/// A trailing comma is allowed.
+fn split_array_items(inner: &str, line: usize) -> Result<Vec<&str>, ConfigError> {
+    let mut items: Vec<&str> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (idx, ch) in inner.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            ',' if !in_string => {
+                items.push(inner[start..idx].trim());
+                start = idx + 1;
+            },
+            _ => (),
+        }
+    }
+    if in_string {
+        return Err(ConfigError::Syntax(line, format!("Unterminated string in array '[{}]'", inner)));
+    }
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        items.push(last);
+    }
+    if items.iter().any(|item| item.is_empty()) {
+        return Err(ConfigError::Syntax(line, format!("Empty element in array '[{}]'", inner)));
+    }
+    return Ok(items);
+}
+
+/// Resolves the escape sequences `\\`, `\"`, `\n`, and `\t` in a basic string
+fn unescape(inner: &str, line: usize) -> Result<String, ConfigError> {
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => result.push('\\'),
+            Some('"') => result.push('"'),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(other) => return Err(ConfigError::Syntax(line, format!("Unknown escape sequence '\\{}'", other))),
+            None => return Err(ConfigError::Syntax(line, "Dangling escape at end of string".to_string())),
+        }
+    }
+    return Ok(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_file_is_default() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn parses_all_settings() {
+        let source = "
+            # Project settings
+            strict = true
+            strict-braces = false
+            deny-warnings = true
+
+            [lints]
+            unused-binding = \"deny\"
+            shadowing = \"allow\" # trailing comment
+
+            [modules]
+            search-paths = [\"lib\", \"vendor/#envl\",]
+
+            [limits]
+            max-file-size = 1_048_576
+            max-diagnostics = 20
+            max-string-length = 256
+            max-number-digits = 32
+
+            [files]
+            extensions = [\"envl\", \".env\", \"#envl\",]
+
+            [operators]
+            aliases = [\"=!\", \"and\"]
+        ";
+        let config = Config::parse(source).unwrap();
+        assert!(config.strict);
+        assert_eq!(config.strict_braces, Some(false));
+        assert!(config.deny_warnings);
+        assert_eq!(config.lints.get("unused-binding"), Some(&LintLevel::Deny));
+        assert_eq!(config.lints.get("shadowing"), Some(&LintLevel::Allow));
+        assert_eq!(config.module_paths, vec![PathBuf::from("lib"), PathBuf::from("vendor/#envl")]);
+        assert_eq!(config.limits.max_file_size, Some(1048576));
+        assert_eq!(config.limits.max_diagnostics, Some(20));
+        assert_eq!(config.limits.lexer_options(), LexerOptions { max_string_length: Some(256), max_number_digits: Some(32) });
+        assert_eq!(config.extensions, ExtensionPolicy::Allow(vec!["envl".to_string(), "env".to_string(), "#envl".to_string()]));
+        assert_eq!(config.operator_aliases.spellings(), &["=!", "and"]);
+    }
+
+    #[test]
+    fn extension_check_can_be_disabled() {
+        let config = Config::parse("[files]\ncheck-extension = false\nextensions = [\"env\"]").unwrap();
+        assert_eq!(config.extensions, ExtensionPolicy::Any);
+        let config = Config::parse("[files]\ncheck-extension = true").unwrap();
+        assert_eq!(config.extensions, ExtensionPolicy::default());
+    }
+
+    #[test]
+    fn rejects_unknown_key() {
+        let result = Config::parse("strictt = true");
+        assert!(matches!(result, Err(ConfigError::UnknownKey(1, key)) if key == "strictt"));
+        // Lints are set by name or code
+        let result = Config::parse("[lints]\nW0003 = \"deny\"\nunused-bindings = \"deny\"");
+        assert!(matches!(result, Err(ConfigError::UnknownKey(3, key)) if key == "lints.unused-bindings"));
+    }
+
+    #[test]
+    fn rejects_unknown_table() {
+        let result = Config::parse("\n[lint]");
+        assert!(matches!(result, Err(ConfigError::UnknownTable(2, table)) if table == "lint"));
+    }
+
+    #[test]
+    fn rejects_duplicate_key() {
+        let result = Config::parse("strict = true\nstrict = false");
+        assert!(matches!(result, Err(ConfigError::DuplicateKey(2, key)) if key == "strict"));
+    }
+
+    #[test]
+    fn rejects_invalid_value() {
+        let result = Config::parse("[lints]\nunused-binding = \"loud\"");
+        assert!(matches!(result, Err(ConfigError::InvalidValue(2, key, _)) if key == "lints.unused-binding"));
+
+        let result = Config::parse("[limits]\nmax-file-size = -1");
+        assert!(matches!(result, Err(ConfigError::InvalidValue(2, _, _))));
+
+        let result = Config::parse("[operators]\naliases = [\"xor\"]");
+        assert!(matches!(result, Err(ConfigError::InvalidValue(2, key, _)) if key == "operators.aliases"));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(matches!(Config::parse("strict"), Err(ConfigError::Syntax(1, _))));
+        assert!(matches!(Config::parse("[files"), Err(ConfigError::Syntax(1, _))));
+        assert!(matches!(Config::parse("[modules]\nsearch-paths = [\"lib\""), Err(ConfigError::Syntax(2, _))));
+        assert!(matches!(Config::parse("[files]\nextensions = [\"envl\""), Err(ConfigError::Syntax(2, _))));
+    }
+
+    #[test]
+    fn rejects_text_after_strings() {
+        assert!(matches!(Config::parse("[lints]\nunused-binding = \"warn\" \"deny\""), Err(ConfigError::Syntax(2, _))));
+        assert!(matches!(Config::parse("[files]\nextensions = [\"a\" \"b\"]"), Err(ConfigError::Syntax(2, _))));
+        assert!(matches!(Config::parse("[files]\nextensions = [\"a\"b]"), Err(ConfigError::Syntax(2, _))));
+        assert!(matches!(Config::parse("[files]\nextensions = [\"a\\\"]"), Err(ConfigError::Syntax(2, _))));
+        assert!(Config::parse("[files]\nextensions = [\"a\" , \"b\" ] # comment").is_ok());
+    }
+
+    #[test]
+    fn quoted_strings_are_read_back() {
+        let extension = "en\\v \"l\"\t#1,\n";
+        let config = Config::parse(&format!("[files]\nextensions = [{}]\n", quote(extension))).unwrap();
+        assert_eq!(config.extensions, ExtensionPolicy::Allow(vec![extension.to_string()]));
+    }
+
+    #[test]
+    fn loads_config_file() {
+        let config = Config::load(Path::new("tests/data/config/envlang.toml")).unwrap();
+        assert!(config.strict);
+        assert_eq!(config.lints.get("unused-binding"), Some(&LintLevel::Warn));
+        assert_eq!(config.limits.max_file_size, Some(8));
+    }
+
+    #[test]
+    fn load_resolves_relative_module_paths() {
+        let config = Config::load(Path::new("tests/data/config/envlang.toml")).unwrap();
+        assert_eq!(config.module_paths, vec![Path::new("tests/data/config").join("lib")]);
+    }
+
+    #[test]
+    fn finds_config_in_ancestor_directory() {
+        let found = find_config_file(Path::new("tests/data/config/nested"));
+        assert_eq!(found, Some(PathBuf::from("tests/data/config/envlang.toml")));
+    }
+
+    #[test]
+    fn missing_config_file() {
+        let data = Path::new("tests/data");
+        assert_eq!(find_config_file_within(data, data), None);
+        assert_eq!(find_config_file_within(&data.join("config/nested"), &data.join("config/nested")), None);
+    }
+
+    #[test]
+    fn bounded_search_includes_root() {
+        let found = find_config_file_within(Path::new("tests/data/config/nested"), Path::new("tests/data/config"));
+        assert_eq!(found, Some(PathBuf::from("tests/data/config/envlang.toml")));
+    }
+}
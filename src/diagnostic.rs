@@ -0,0 +1,433 @@
+//! Structured diagnostics for reporting errors and warnings
+//!
+//! A [`Diagnostic`] is the common currency for everything Envlang reports to its users.
+//! Unlike the bare `Display` output of the error types, a diagnostic keeps its parts separate, so that the CLI and other tools can lay them out as they see fit:
+//! * A [`Severity`] and an optional error code (see [`crate::error`]).
+//! * A primary message and an optional primary [`Span`] pointing into the source text.
+//! * Secondary [`Label`]s, which attach a message to another span.
+//! * Free-form notes.
+//! * [`Suggestion`]s for fixing the problem.
+//!
+//! Errors are converted into diagnostics with [`Diagnostic::from_error`], and the call stack of a runtime error into notes with [`call_stack`].
+//!
+//! Before being shown, a list of diagnostics should be passed through [`deduplicate`], which folds repeated and cascaded diagnostics into their root cause, and [`limit`], which caps the number of diagnostics reported.
+
+#[cfg(feature = "std")]
+use crate::error::EnvlangError;
+#[cfg(feature = "std")]
+use crate::io::SourceFile;
+use crate::evaluator::Frame;
+#[cfg(feature = "std")]
+use crate::lexer::LexerError;
+#[cfg(feature = "std")]
+use crate::lexer::Location;
+use crate::messages::Catalog;
+pub use crate::span::Span;
+use core::fmt;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Severity of a diagnostic
+///
+/// The enum derives the traits `Debug`, `Clone`, `Copy`, `PartialEq`, and `Eq`, and implements [`ToString`](Severity::to_string).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The program cannot be run
+    Error,
+    /// The program can be run, but likely contains a mistake
+    Warning,
+    /// Additional information that does not indicate a problem
+    Note,
+}
+
+impl ToString for Severity {
+    fn to_string(&self) -> String {
+        match self {
+            Severity::Error => "error".to_string(),
+            Severity::Warning => "warning".to_string(),
+            Severity::Note => "note".to_string(),
+        }
+    }
+}
+
+/// A secondary span with a message attached to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// A suggested fix, replacing the text of a span with new text
+///
+/// Insertions are expressed with an empty span, and deletions with an empty replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub span: Span,
+    pub replacement: String,
+    pub message: String,
+}
+
+/// A structured error, warning, or note
+///
+/// Diagnostics are created with [`Diagnostic::error`], [`Diagnostic::warning`], or [`Diagnostic::note`], and extended with the `with_*` methods.
+///
+/// # Examples
+/// ```
+/// use envlang::diagnostic::{Diagnostic, Span};
+///
+/// let diagnostic = Diagnostic::error("Unterminated string literal 'abc'")
+///     .with_code("E0002")
+///     .with_span(Span::new(8, 12))
+///     .with_note("String literals must be closed with the same quote that opened them");
+/// assert_eq!(diagnostic.to_string(), "error[E0002]: Unterminated string literal 'abc'\n = note: String literals must be closed with the same quote that opened them");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub span: Option<Span>,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    /// Creates a new diagnostic with the given severity and message
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: message.into(),
+            span: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Creates a new error diagnostic
+    pub fn error(message: impl Into<String>) -> Self { Self::new(Severity::Error, message) }
+
+    /// Creates a new warning diagnostic
+    pub fn warning(message: impl Into<String>) -> Self { Self::new(Severity::Warning, message) }
+
+    /// Creates a new note diagnostic
+    pub fn note(message: impl Into<String>) -> Self { Self::new(Severity::Note, message) }
+
+    /// Sets the error code of the diagnostic
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        return self;
+    }
+
+    /// Sets the primary span of the diagnostic
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        return self;
+    }
+
+    /// Adds a secondary labeled span to the diagnostic
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label { span, message: message.into() });
+        return self;
+    }
+
+    /// Adds a note to the diagnostic
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        return self;
+    }
+
+    /// Adds a suggested fix to the diagnostic
+    pub fn with_suggestion(mut self, span: Span, replacement: impl Into<String>, message: impl Into<String>) -> Self {
+        self.suggestions.push(Suggestion { span, replacement: replacement.into(), message: message.into() });
+        return self;
+    }
+
+    /// Converts an error into an error diagnostic with English messages
+    ///
+    /// See [`Diagnostic::from_error_with`].
+    #[cfg(feature = "std")]
+    pub fn from_error(err: &EnvlangError, source: Option<&SourceFile>) -> Self {
+        return Diagnostic::from_error_with(err, source, &Catalog::default());
+    }
+
+    /// Converts an error into an error diagnostic, rendering its messages from a catalog
+    ///
+    /// The source file is used to turn lexer positions into spans. Without it, lexer diagnostics have no primary span.
+    /// 
+    /// Parser diagnostics use the span of their [`Location`], if the parser was given source locations.
+    /// Runtime diagnostics use the span of the binding or call the error occurred in (see [`EvalError::span`](crate::evaluator::EvalError::span)).
+    #[cfg(feature = "std")]
+    pub fn from_error_with(err: &EnvlangError, source: Option<&SourceFile>, catalog: &Catalog) -> Self {
+        let mut diagnostic = Diagnostic::error(catalog.render(err.message_key(), &err.arguments())).with_code(err.code());
+        match err {
+            EnvlangError::Io(_) => (),
+            EnvlangError::Runtime(eval_err) => {
+                if let Some(span) = eval_err.span() {
+                    diagnostic = diagnostic.with_span(span);
+                }
+            },
+            EnvlangError::Lexer(lexer_err) => {
+                if let Some(source) = source {
+                    let span = grapheme_span(source, lexer_err.position());
+                    if let Some(span) = span {
+                        diagnostic = diagnostic.with_span(span);
+                    }
+                    if let LexerError::UnterminatedString(_, _) = lexer_err {
+                        let end = source.text().len();
+                        if let Some(quote) = span.and_then(|span| source.text().get(span.start..span.end)) {
+                            diagnostic = diagnostic.with_suggestion(Span::new(end, end), quote, catalog.render("help.close-string", &[]));
+                        }
+                    }
+                }
+            },
+            EnvlangError::Parser(parser_err) => {
+                match parser_err.location() {
+                    Some(Location { span: Some(span), .. }) => diagnostic = diagnostic.with_span(*span),
+                    Some(location) => diagnostic = diagnostic.with_note(catalog.render("note.error-line", &[location.line.to_string()])),
+                    None => (),
+                }
+            },
+        }
+        return diagnostic;
+    }
+}
+
+/// Gets the span of the grapheme at a grapheme index, or an empty span at the end of the file
+#[cfg(feature = "std")]
+fn grapheme_span(source: &SourceFile, index: usize) -> Option<Span> {
+    let start = source.grapheme_offset(index)?;
+    let end = source.grapheme_offset(index + 1).unwrap_or(start);
+    return Some(Span::new(start, end));
+}
+
+/// Converts the call stack of a failed evaluation (see [`Evaluator::call_stack`](crate::evaluator::Evaluator::call_stack)) into notes, innermost call first
+///
+/// The span of each note points at the call, and a label points at the declaration of the called function.
+/// Spans the parser did not record, e.g., for calls in a program parsed without source locations, are left out.
+pub fn call_stack(frames: &[Frame], catalog: &Catalog) -> Vec<Diagnostic> {
+    let mut notes = Vec::new();
+    for frame in frames.iter().rev() {
+        let mut note = Diagnostic::note(catalog.render("note.in-call", core::slice::from_ref(&frame.callee)));
+        if let Some(span) = frame.call {
+            note = note.with_span(span);
+        }
+        if let Some(span) = frame.declaration {
+            note = note.with_label(span, catalog.render("label.declared-here", &[]));
+        }
+        notes.push(note);
+    }
+    return notes;
+}
+
+/// Removes repeated and cascaded diagnostics, keeping the first occurrence of each root cause
+///
+/// A diagnostic is dropped if an earlier diagnostic has the same severity, code, message, and span, or if it is an error whose primary span overlaps the span of an earlier error.
+/// The root cause gets a note, rendered from the catalog, stating how many diagnostics were folded into it.
+///
+/// Diagnostics without a primary span are only dropped if they are exact repeats.
+/// Lints (with codes starting with `W`, see [`crate::lint`]) are independent findings, so lints reported as errors are never dropped as cascades.
+pub fn deduplicate(diagnostics: Vec<Diagnostic>, catalog: &Catalog) -> Vec<Diagnostic> {
+    let mut result: Vec<Diagnostic> = Vec::new();
+    let mut folded: Vec<usize> = Vec::new();
+    for diagnostic in diagnostics {
+        let root = result.iter().position(|kept| {
+            let repeat = kept.severity == diagnostic.severity
+                && kept.code == diagnostic.code
+                && kept.message == diagnostic.message
+                && kept.span == diagnostic.span;
+            let cascade = kept.severity == Severity::Error
+                && diagnostic.severity == Severity::Error
+                && !diagnostic.code.is_some_and(|code| code.starts_with('W'))
+                && matches!((kept.span, diagnostic.span), (Some(a), Some(b)) if a.overlaps(&b));
+            repeat || cascade
+        });
+        match root {
+            Some(idx) => folded[idx] += 1,
+            None => {
+                result.push(diagnostic);
+                folded.push(0);
+            },
+        }
+    }
+    for (diagnostic, count) in result.iter_mut().zip(folded) {
+        match count {
+            0 => (),
+            1 => diagnostic.notes.push(catalog.render("note.suppressed-one", &[])),
+            n => diagnostic.notes.push(catalog.render("note.suppressed-many", &[n.to_string()])),
+        }
+    }
+    return result;
+}
+
+/// Caps the number of diagnostics at a maximum
+///
+/// If there are more than `max` diagnostics, only the first `max` are kept, followed by a note (rendered from the catalog) summarizing how many were left out.
+/// A maximum of `None` keeps every diagnostic.
+pub fn limit(mut diagnostics: Vec<Diagnostic>, max: Option<usize>, catalog: &Catalog) -> Vec<Diagnostic> {
+    let Some(max) = max else { return diagnostics; };
+    if diagnostics.len() > max {
+        let total = diagnostics.len();
+        diagnostics.truncate(max);
+        diagnostics.push(Diagnostic::note(catalog.render("note.too-many", &[max.to_string(), total.to_string()])));
+    }
+    return diagnostics;
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "{}[{}]: {}", self.severity.to_string(), code, self.message)?,
+            None => write!(f, "{}: {}", self.severity.to_string(), self.message)?,
+        }
+        for label in &self.labels {
+            write!(f, "\n = label: {}", label.message)?;
+        }
+        for note in &self.notes {
+            write!(f, "\n = note: {}", note)?;
+        }
+        for suggestion in &self.suggestions {
+            write!(f, "\n = help: {}", suggestion.message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluator::EvalError;
+    use crate::io::IoError;
+    use crate::lexer::Lexer;
+    use crate::parser::ParserError;
+    use std::path::PathBuf;
+
+    #[test]
+    fn builds_diagnostics() {
+        let diagnostic = Diagnostic::warning("Unused binding 'x'")
+            .with_span(Span::new(4, 5))
+            .with_label(Span::new(0, 3), "declared here")
+            .with_suggestion(Span::new(0, 10), "", "Remove the binding");
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(diagnostic.code, None);
+        assert_eq!(diagnostic.span, Some(Span::new(4, 5)));
+        assert_eq!(diagnostic.labels.len(), 1);
+        assert_eq!(diagnostic.to_string(), "warning: Unused binding 'x'\n = label: declared here\n = help: Remove the binding");
+    }
+
+    #[test]
+    fn converts_lexer_errors() {
+        let source = SourceFile::new("test.envl", "let x = \"abc");
+        let err = EnvlangError::from(Lexer::from_source(&source).tokenize().unwrap_err());
+        let diagnostic = Diagnostic::from_error(&err, Some(&source));
+        assert_eq!(diagnostic.code, Some("E0002"));
+        assert_eq!(diagnostic.message, "Unterminated string literal 'abc'");
+        assert_eq!(diagnostic.span, Some(Span::new(8, 9)));
+        assert_eq!(diagnostic.suggestions, vec![Suggestion {
+            span: Span::new(12, 12),
+            replacement: "\"".to_string(),
+            message: "Close the string literal".to_string(),
+        }]);
+
+        let diagnostic = Diagnostic::from_error(&err, None);
+        assert_eq!(diagnostic.span, None);
+        assert!(diagnostic.suggestions.is_empty());
+    }
+
+    #[test]
+    fn converts_parser_errors() {
+        let err = EnvlangError::from(ParserError::UnexpectedEOF(Location::new(4, 2)));
+        let diagnostic = Diagnostic::from_error(&err, None);
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.to_string(), "error[E0108]: Unexpected end of file\n = note: The error was found on line 2");
+
+        let location = Location { token: 4, line: 1, column: Some(5), span: Some(Span::new(4, 5)) };
+        let diagnostic = Diagnostic::from_error(&EnvlangError::from(ParserError::UnexpectedEOF(location)), None);
+        assert_eq!(diagnostic.span, Some(Span::new(4, 5)));
+        assert!(diagnostic.notes.is_empty());
+    }
+
+    #[test]
+    fn converts_runtime_errors() {
+        let err = EvalError::UndefinedIdentifier("z".into());
+        assert_eq!(Diagnostic::from_error(&EnvlangError::from(err), None).span, None);
+
+        let err = EvalError::Located(Box::new(EvalError::UndefinedIdentifier("z".into())), Span::new(4, 5));
+        let diagnostic = Diagnostic::from_error(&EnvlangError::from(err), None);
+        assert_eq!(diagnostic.code, Some("E0301"));
+        assert_eq!(diagnostic.span, Some(Span::new(4, 5)));
+    }
+
+    #[test]
+    fn converts_io_errors() {
+        let err = EnvlangError::from(IoError::NotFound(PathBuf::from("missing.envl")));
+        let diagnostic = Diagnostic::from_error(&err, None);
+        assert_eq!(diagnostic.code, Some("E0201"));
+        assert_eq!(diagnostic.message, "File 'missing.envl' does not exist");
+    }
+
+    #[test]
+    fn renders_messages_from_catalogs() {
+        let mut catalog = Catalog::new();
+        catalog.insert("E0002", "Päättämätön merkkijono '{0}'");
+        catalog.insert("help.close-string", "Sulje merkkijono");
+        let source = SourceFile::new("test.envl", "let x = \"abc");
+        let err = EnvlangError::from(Lexer::from_source(&source).tokenize().unwrap_err());
+        let diagnostic = Diagnostic::from_error_with(&err, Some(&source), &catalog);
+        assert_eq!(diagnostic.message, "Päättämätön merkkijono 'abc'");
+        assert_eq!(diagnostic.suggestions[0].message, "Sulje merkkijono");
+    }
+
+    #[test]
+    fn converts_call_stacks() {
+        let frames = [
+            Frame { callee: "env.f".to_string(), call: Some(Span::new(30, 38)), declaration: Some(Span::new(8, 9)) },
+            Frame { callee: "g".to_string(), call: None, declaration: None },
+        ];
+        let notes = call_stack(&frames, &Catalog::default());
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].to_string(), "note: In the call to `g`");
+        assert_eq!((notes[0].span, notes[0].labels.len()), (None, 0));
+        assert_eq!(notes[1].to_string(), "note: In the call to `env.f`\n = label: The function is declared here");
+        assert_eq!(notes[1].span, Some(Span::new(30, 38)));
+        assert_eq!(notes[1].labels[0].span, Span::new(8, 9));
+    }
+
+    #[test]
+    fn deduplicates_repeated_and_cascaded_diagnostics() {
+        let diagnostics = vec![
+            Diagnostic::error("Unexpected end of file").with_span(Span::new(10, 12)),
+            Diagnostic::error("Unclosed environment").with_span(Span::new(11, 11)),
+            Diagnostic::warning("Unused binding 'x'").with_span(Span::new(0, 1)),
+            Diagnostic::warning("Unused binding 'x'").with_span(Span::new(0, 1)),
+            Diagnostic::warning("Unused binding 'y'").with_span(Span::new(0, 1)),
+            Diagnostic::error("Unclosed argument clause").with_span(Span::new(12, 13)),
+            Diagnostic::error("Implicit truthiness of '5'").with_code("W0004").with_span(Span::new(12, 13)),
+        ];
+        let diagnostics = deduplicate(diagnostics, &Catalog::default());
+        assert_eq!(diagnostics.iter().map(|d| d.message.as_str()).collect::<Vec<&str>>(), vec![
+            "Unexpected end of file",
+            "Unused binding 'x'",
+            "Unused binding 'y'",
+            "Unclosed argument clause",
+            "Implicit truthiness of '5'",
+        ]);
+        assert_eq!(diagnostics[0].notes, vec!["1 further diagnostic with the same cause was suppressed".to_string()]);
+        assert_eq!(diagnostics[1].notes.len(), 1);
+        assert!(diagnostics[2].notes.is_empty());
+    }
+
+    #[test]
+    fn limits_diagnostics() {
+        let diagnostics: Vec<Diagnostic> = (0..5).map(|idx| Diagnostic::warning(format!("Warning {}", idx))).collect();
+        assert_eq!(limit(diagnostics.clone(), None, &Catalog::default()).len(), 5);
+        assert_eq!(limit(diagnostics.clone(), Some(5), &Catalog::default()).len(), 5);
+
+        let limited = limit(diagnostics, Some(2), &Catalog::default());
+        assert_eq!(limited.len(), 3);
+        assert_eq!(limited[2].severity, Severity::Note);
+        assert_eq!(limited[2].message, "Too many diagnostics, stopping after 2 of 5");
+    }
+}
@@ -0,0 +1,360 @@
+//! Runtime storage of bindings
+//!
+//! An [`Environment`] holds the bindings declared in an environment of a running program, and a pointer to the environment it was created in.
+//! What an environment sees of its parent is decided by its [`Inherit`] setting, following the inherit clauses of Envlang:
+//! * `inherit (a, b)` only makes the listed bindings of the parent visible.
+//! * `inherit (*)` makes every binding of the parent visible, as do implicit environments (assigned without braces).
+//! * Without an inherit clause, explicit environments and functions see nothing of their parent.
+//!
+//! Parents are shared snapshots: bindings declared in an environment after a child was created are not visible to the child.
+//! Taking a snapshot does not copy the bindings, which are stored in a persistent map whose copies share their nodes.
+//!
+//! # Examples
+//! ```
+//! use envlang::environment::{Environment, Inherit};
+//! use envlang::value::Value;
+//! use std::rc::Rc;
+//!
+//! let mut global = Environment::new();
+//! global.define("a".into(), Value::Integer(1));
+//! global.define("b".into(), Value::Integer(2));
+//! let mut child = Environment::child(Rc::new(global), Inherit::Names(vec!["a".into()]));
+//! child.define("c".into(), Value::Integer(3));
+//! assert_eq!(child.lookup_recursive("a"), Some(&Value::Integer(1)));
+//! assert_eq!(child.lookup_recursive("b"), None);
+//! assert_eq!(child.get("a"), None);
+//! ```
+
+use crate::evaluator::EvalError;
+use crate::parser::AstNode;
+use crate::value::Value;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+/// The bindings of its parent that an environment sees
+///
+/// The enum derives the traits `Debug`, `Clone`, `PartialEq`, and `Eq`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inherit {
+    /// No bindings of the parent, as in explicit environments without an inherit clause
+    Nothing,
+    /// The listed bindings of the parent, as in `inherit (a, b)`
+    Names(Vec<Rc<str>>),
+    /// Every binding of the parent, as in `inherit (*)` and implicit environments
+    All,
+}
+
+impl Inherit {
+    /// Gets the setting of an inherit clause, where `None` stands for a missing clause
+    pub fn from_clause(inherit: Option<&AstNode>) -> Self {
+        match inherit {
+            Some(AstNode::Inherit { names: None }) => return Inherit::All,
+            Some(AstNode::Inherit { names: Some(names) }) => return Inherit::Names(names.clone()),
+            _ => return Inherit::Nothing,
+        }
+    }
+
+    /// Checks whether a binding of the parent is visible
+    pub fn includes(&self, name: &str) -> bool {
+        match self {
+            Inherit::Nothing => return false,
+            Inherit::Names(names) => return names.iter().any(|inherited| inherited.as_ref() == name),
+            Inherit::All => return true,
+        }
+    }
+}
+
+/// The bindings of an environment of a running program
+///
+/// The struct derives the traits `Debug`, `Clone`, and `PartialEq`, and implements `Default` as an empty environment without a parent.
+/// Cloning an environment is cheap, as the bindings are stored in a persistent map whose copies share their nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Environment {
+    bindings: Map,
+    parent: Option<Rc<Environment>>,
+    inherit: Inherit,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        return Environment { bindings: Map::default(), parent: None, inherit: Inherit::Nothing };
+    }
+}
+
+impl Environment {
+    /// Creates an empty environment without a parent, such as the global environment
+    pub fn new() -> Self { Self::default() }
+
+    /// Creates an empty environment that sees the bindings of a parent allowed by an [`Inherit`] setting
+    pub fn child(parent: Rc<Environment>, inherit: Inherit) -> Self {
+        return Environment { bindings: Map::default(), parent: Some(parent), inherit };
+    }
+
+    /// Creates a child environment for an inherit clause, checking that every listed name is visible in the parent
+    ///
+    /// # Errors
+    /// * [`EvalError::UndefinedIdentifier`]: A name listed in the clause is not visible in the parent.
+    pub fn inheriting(parent: Rc<Environment>, inherit: Option<&AstNode>) -> Result<Self, EvalError> {
+        let inherit = Inherit::from_clause(inherit);
+        if let Inherit::Names(names) = &inherit {
+            if let Some(missing) = names.iter().find(|name| parent.lookup_recursive(name).is_none()) {
+                return Err(EvalError::UndefinedIdentifier(missing.to_string()));
+            }
+        }
+        return Ok(Environment::child(parent, inherit));
+    }
+
+    /// Declares a binding in the environment, replacing an earlier binding of the name
+    ///
+    /// Children created before the declaration keep seeing the earlier binding, as they share the bindings as they were at their creation.
+    pub fn define(&mut self, name: Rc<str>, value: Value) {
+        self.bindings.insert(name, value);
+    }
+
+    /// Gets the value of a binding declared in the environment itself, ignoring its parent
+    pub fn get(&self, name: &str) -> Option<&Value> { self.bindings.get(name) }
+
+    /// Replaces the value of a binding declared in the environment itself
+    ///
+    /// # Errors
+    /// * [`EvalError::UndefinedIdentifier`]: The name is not declared in the environment. Bindings of the parent cannot be set, as parents are shared.
+    pub fn set(&mut self, name: &str, value: Value) -> Result<(), EvalError> {
+        match self.bindings.get(name) {
+            Some(_) => {
+                self.bindings.insert(Rc::from(name), value);
+                return Ok(());
+            },
+            None => return Err(EvalError::UndefinedIdentifier(name.to_string())),
+        }
+    }
+
+    /// Looks up a name in the environment, and then in the bindings it inherits from its parents
+    pub fn lookup_recursive(&self, name: &str) -> Option<&Value> {
+        if let Some(value) = self.bindings.get(name) {
+            return Some(value);
+        }
+        let parent = self.parent.as_ref().filter(|_| self.inherit.includes(name))?;
+        return parent.lookup_recursive(name);
+    }
+
+    /// Checks whether a name that is not visible in the environment is declared in a parent that is not inherited
+    ///
+    /// Such names are missing from an inherit clause, as opposed to not being declared at all.
+    pub fn is_hidden(&self, name: &str) -> bool {
+        if self.bindings.get(name).is_some() {
+            return false;
+        }
+        match &self.parent {
+            Some(parent) if self.inherit.includes(name) => return parent.is_hidden(name),
+            Some(parent) => return parent.lookup_recursive(name).is_some() || parent.is_hidden(name),
+            None => return false,
+        }
+    }
+
+    /// Gets every binding visible in the environment, including the inherited ones, ordered by name
+    pub fn visible(&self) -> BTreeMap<Rc<str>, Value> {
+        let mut visible = match &self.parent {
+            Some(parent) => {
+                let mut inherited = parent.visible();
+                inherited.retain(|name, _| self.inherit.includes(name));
+                inherited
+            },
+            None => BTreeMap::new(),
+        };
+        self.bindings.collect(&mut visible);
+        return visible;
+    }
+}
+
+/// A persistent map from names to values, kept balanced as an AVL tree
+///
+/// Inserting into the map copies only the path to the inserted name, and shares every other node with the copies made before.
+#[derive(Clone, Default)]
+struct Map {
+    root: Option<Rc<Node>>,
+}
+
+/// A node of a [`Map`]
+struct Node {
+    name: Rc<str>,
+    value: Value,
+    height: usize,
+    left: Option<Rc<Node>>,
+    right: Option<Rc<Node>>,
+}
+
+impl Map {
+    /// Gets the value of a name
+    fn get(&self, name: &str) -> Option<&Value> {
+        let mut node = self.root.as_ref();
+        while let Some(current) = node {
+            match name.cmp(&current.name) {
+                Ordering::Less => node = current.left.as_ref(),
+                Ordering::Greater => node = current.right.as_ref(),
+                Ordering::Equal => return Some(&current.value),
+            }
+        }
+        return None;
+    }
+
+    /// Sets the value of a name, replacing an earlier value
+    fn insert(&mut self, name: Rc<str>, value: Value) {
+        self.root = Some(insert(self.root.as_ref(), name, value));
+    }
+
+    /// Adds every binding of the map to `into`, replacing the values of names already in it
+    fn collect(&self, into: &mut BTreeMap<Rc<str>, Value>) {
+        let mut stack: Vec<&Rc<Node>> = Vec::new();
+        stack.extend(self.root.as_ref());
+        while let Some(node) = stack.pop() {
+            into.insert(node.name.clone(), node.value.clone());
+            stack.extend(node.left.as_ref());
+            stack.extend(node.right.as_ref());
+        }
+    }
+
+    /// Gets the bindings of the map, ordered by name
+    fn entries(&self) -> BTreeMap<Rc<str>, Value> {
+        let mut entries = BTreeMap::new();
+        self.collect(&mut entries);
+        return entries;
+    }
+}
+
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool { self.entries() == other.entries() }
+}
+
+impl fmt::Debug for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.debug_map().entries(self.entries()).finish() }
+}
+
+/// Gets the height of a subtree, which is zero for an empty one
+fn height(node: Option<&Rc<Node>>) -> usize { node.map_or(0, |node| node.height) }
+
+/// Creates a node above two subtrees
+fn node(name: Rc<str>, value: Value, left: Option<Rc<Node>>, right: Option<Rc<Node>>) -> Rc<Node> {
+    let height = 1 + height(left.as_ref()).max(height(right.as_ref()));
+    return Rc::new(Node { name, value, height, left, right });
+}
+
+/// Creates a node above two subtrees whose heights differ by at most two, rotating it back into balance
+fn balance(name: Rc<str>, value: Value, left: Option<Rc<Node>>, right: Option<Rc<Node>>) -> Rc<Node> {
+    let (left_height, right_height) = (height(left.as_ref()), height(right.as_ref()));
+    match (left, right) {
+        (Some(left), right) if left_height > right_height + 1 => {
+            if height(left.left.as_ref()) >= height(left.right.as_ref()) {
+                let lower = node(name, value, left.right.clone(), right);
+                return node(left.name.clone(), left.value.clone(), left.left.clone(), Some(lower));
+            }
+            match &left.right {
+                Some(inner) => {
+                    let lower_left = node(left.name.clone(), left.value.clone(), left.left.clone(), inner.left.clone());
+                    let lower_right = node(name, value, inner.right.clone(), right);
+                    return node(inner.name.clone(), inner.value.clone(), Some(lower_left), Some(lower_right));
+                },
+                None => return node(name, value, Some(left), right),
+            }
+        },
+        (left, Some(right)) if right_height > left_height + 1 => {
+            if height(right.right.as_ref()) >= height(right.left.as_ref()) {
+                let lower = node(name, value, left, right.left.clone());
+                return node(right.name.clone(), right.value.clone(), Some(lower), right.right.clone());
+            }
+            match &right.left {
+                Some(inner) => {
+                    let lower_left = node(name, value, left, inner.left.clone());
+                    let lower_right = node(right.name.clone(), right.value.clone(), inner.right.clone(), right.right.clone());
+                    return node(inner.name.clone(), inner.value.clone(), Some(lower_left), Some(lower_right));
+                },
+                None => return node(name, value, left, Some(right)),
+            }
+        },
+        (left, right) => return node(name, value, left, right),
+    }
+}
+
+/// Inserts a name into a subtree, returning the new root of the subtree
+fn insert(root: Option<&Rc<Node>>, name: Rc<str>, value: Value) -> Rc<Node> {
+    let Some(root) = root else {
+        return node(name, value, None, None);
+    };
+    match name.cmp(&root.name) {
+        Ordering::Less => {
+            let left = insert(root.left.as_ref(), name, value);
+            return balance(root.name.clone(), root.value.clone(), Some(left), root.right.clone());
+        },
+        Ordering::Greater => {
+            let right = insert(root.right.as_ref(), name, value);
+            return balance(root.name.clone(), root.value.clone(), root.left.clone(), Some(right));
+        },
+        Ordering::Equal => return node(name, value, root.left.clone(), root.right.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn honors_inherit_settings() {
+        let mut global = Environment::new();
+        global.define("a".into(), Value::Integer(1));
+        global.define("b".into(), Value::Integer(2));
+        let global = Rc::new(global);
+
+        let mut all = Environment::child(global.clone(), Inherit::All);
+        all.define("a".into(), Value::Integer(10));
+        assert_eq!(all.lookup_recursive("a"), Some(&Value::Integer(10)));
+        assert_eq!(all.lookup_recursive("b"), Some(&Value::Integer(2)));
+        assert_eq!(all.visible().len(), 2);
+
+        let nothing = Environment::child(global.clone(), Inherit::Nothing);
+        assert_eq!(nothing.lookup_recursive("a"), None);
+        assert!(nothing.visible().is_empty());
+        assert!(nothing.is_hidden("a"));
+        assert!(!nothing.is_hidden("c"));
+
+        // Names are looked up through every parent that inherits them
+        let named = Environment::child(Rc::new(Environment::child(global.clone(), Inherit::All)), Inherit::Names(vec!["b".into()]));
+        assert_eq!(named.lookup_recursive("b"), Some(&Value::Integer(2)));
+        assert_eq!(named.lookup_recursive("a"), None);
+        assert!(named.is_hidden("a"));
+        assert!(!named.is_hidden("b"));
+        assert_eq!(named.visible().keys().cloned().collect::<Vec<_>>(), vec![Rc::from("b")]);
+    }
+
+    #[test]
+    fn defines_and_sets_bindings() {
+        let mut env = Environment::new();
+        assert_eq!(env.set("a", Value::Boolean(true)), Err(EvalError::UndefinedIdentifier("a".into())));
+        env.define("a".into(), Value::Boolean(false));
+        assert_eq!(env.set("a", Value::Boolean(true)), Ok(()));
+        assert_eq!(env.get("a"), Some(&Value::Boolean(true)));
+
+        let clause = AstNode::Inherit { names: Some(vec!["a".into(), "c".into()]) };
+        assert_eq!(Environment::inheriting(Rc::new(env), Some(&clause)), Err(EvalError::UndefinedIdentifier("c".into())));
+    }
+
+    #[test]
+    fn copies_share_their_bindings() {
+        let mut env = Environment::new();
+        for i in 0..1000 {
+            env.define(i.to_string().into(), Value::Integer(i));
+        }
+        let snapshot = env.clone();
+        env.define("0".into(), Value::Boolean(true));
+        env.define("new".into(), Value::Boolean(true));
+        assert_eq!(snapshot.get("0"), Some(&Value::Integer(0)));
+        assert_eq!(snapshot.get("new"), None);
+        assert_eq!(env.get("0"), Some(&Value::Boolean(true)));
+        assert!((1..1000).all(|i| env.get(&i.to_string()) == Some(&Value::Integer(i))));
+        assert_eq!(env.visible().len(), 1001);
+        // The tree stays balanced: an AVL tree of 1001 nodes is at most 14 levels high
+        assert!(height(env.bindings.root.as_ref()) <= 14);
+    }
+}
@@ -0,0 +1,189 @@
+//! Top-level Envlang error type
+//!
+//! Each phase of Envlang reports its own error type: [`IoError`] when reading scripts, [`LexerError`] during lexical analysis, [`ParserError`] during parsing, and [`EvalError`] when running the program.
+//!
+//! [`EnvlangError`] wraps all of these, so that code running several phases in a row can use the `?` operator and return a single error type.
+//!
+//! # Error codes
+//!
+//! Every error variant has a stable code, e.g., `E0002` for an unterminated string literal, which is returned by [`EnvlangError::code`] and printed in front of the message.
+//! Codes never change meaning or get reused once assigned, so they can be used to search the documentation and to refer to specific errors.
+//! The codes are grouped by phase:
+//! * `E0001`-`E0099`: Lexer errors
+//! * `E0101`-`E0199`: Parser errors
+//! * `E0201`-`E0299`: IO errors
+//! * `E0301`-`E0399`: Runtime errors
+
+use crate::evaluator::EvalError;
+use crate::io::IoError;
+use crate::lexer::LexerError;
+use crate::parser::ParserError;
+use std::error::Error;
+use std::fmt;
+
+/// Error type covering every phase of Envlang
+///
+/// The enum implements `From` for each of the wrapped error types, and [`Error::source`] returns the wrapped error.
+#[derive(Debug)]
+pub enum EnvlangError {
+    Io(IoError),                                // (error)
+    Lexer(LexerError),                          // (error)
+    Parser(ParserError),                        // (error)
+    Runtime(EvalError),                         // (error)
+}
+
+impl EnvlangError {
+    /// Gets the human-readable message of the wrapped error
+    pub fn message(&self) -> String {
+        match self {
+            EnvlangError::Io(err) => err.to_string(),
+            EnvlangError::Lexer(err) => err.to_string(),
+            EnvlangError::Parser(err) => err.to_string(),
+            EnvlangError::Runtime(err) => err.to_string(),
+        }
+    }
+
+    /// Gets the key of the message template of the wrapped error (see [`crate::messages`])
+    pub fn message_key(&self) -> &'static str {
+        match self {
+            EnvlangError::Io(err) => err.message_key(),
+            EnvlangError::Lexer(err) => err.code(),
+            EnvlangError::Parser(err) => err.code(),
+            EnvlangError::Runtime(err) => err.code(),
+        }
+    }
+
+    /// Gets the values filled into the message template of the wrapped error
+    pub fn arguments(&self) -> Vec<String> {
+        match self {
+            EnvlangError::Io(err) => err.arguments(),
+            EnvlangError::Lexer(err) => err.arguments(),
+            EnvlangError::Parser(err) => err.arguments(),
+            EnvlangError::Runtime(err) => err.arguments(),
+        }
+    }
+
+    /// Gets the stable error code of the wrapped error
+    pub fn code(&self) -> &'static str {
+        match self {
+            EnvlangError::Io(err) => err.code(),
+            EnvlangError::Lexer(err) => err.code(),
+            EnvlangError::Parser(err) => err.code(),
+            EnvlangError::Runtime(err) => err.code(),
+        }
+    }
+
+    /// Gets the position at which the error was triggered, if the error records one
+    ///
+    /// Lexer positions are grapheme indices into the source, and parser positions are indices into the token vector.
+    /// Runtime errors do not record positions, as the AST has no source locations.
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            EnvlangError::Io(_) | EnvlangError::Runtime(_) => None,
+            EnvlangError::Lexer(err) => Some(err.position()),
+            EnvlangError::Parser(err) => err.position(),
+        }
+    }
+
+    /// Gets the one-indexed source line at which the error was triggered, if the error records one
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            EnvlangError::Io(_) | EnvlangError::Lexer(_) | EnvlangError::Runtime(_) => None,
+            EnvlangError::Parser(err) => err.line(),
+        }
+    }
+}
+
+impl Error for EnvlangError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EnvlangError::Io(err) => Some(err),
+            EnvlangError::Lexer(err) => Some(err),
+            EnvlangError::Parser(err) => Some(err),
+            EnvlangError::Runtime(err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for EnvlangError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code(), self.message())
+    }
+}
+
+impl From<IoError> for EnvlangError {
+    fn from(err: IoError) -> Self {
+        return EnvlangError::Io(err);
+    }
+}
+
+impl From<LexerError> for EnvlangError {
+    fn from(err: LexerError) -> Self {
+        return EnvlangError::Lexer(err);
+    }
+}
+
+impl From<ParserError> for EnvlangError {
+    fn from(err: ParserError) -> Self {
+        return EnvlangError::Parser(err);
+    }
+}
+
+impl From<EvalError> for EnvlangError {
+    fn from(err: EvalError) -> Self {
+        return EnvlangError::Runtime(err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::{Lexer, Location};
+    use crate::parser::{AstNode, Parser};
+    use crate::unicodesegmenters::segment_graphemes;
+
+    fn parse_path(path: &str) -> Result<AstNode, EnvlangError> {
+        let source = crate::io::read_file(path)?;
+        let tokens = Lexer::new(segment_graphemes(&source)).tokenize()?;
+        return Ok(Parser::new(tokens).parse()?);
+    }
+
+    #[test]
+    fn wraps_io_errors() {
+        let err = parse_path("tests/data/doesnotexist.envl").unwrap_err();
+        assert!(matches!(err, EnvlangError::Io(IoError::NotFound(_))));
+        assert_eq!(err.position(), None);
+        assert_eq!(err.code(), "E0201");
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn wraps_lexer_errors() {
+        let err = EnvlangError::from(LexerError::UnterminatedString(3, "abc".to_string()));
+        assert_eq!(err.position(), Some(3));
+        assert_eq!(err.line(), None);
+        assert_eq!(err.code(), "E0002");
+        assert_eq!(err.to_string(), "[E0002] Lexer error at position 3: Unterminated string literal 'abc'");
+    }
+
+    #[test]
+    fn wraps_parser_errors() {
+        let err = EnvlangError::from(ParserError::UnexpectedEOF(Location::new(4, 2)));
+        assert_eq!(err.position(), Some(4));
+        assert_eq!(err.line(), Some(2));
+        assert_eq!(err.code(), "E0108");
+        assert_eq!(err.message(), ParserError::UnexpectedEOF(Location::new(4, 2)).to_string());
+
+        let err = EnvlangError::from(ParserError::NotInheritClause);
+        assert_eq!(err.position(), None);
+        assert_eq!(err.line(), None);
+    }
+
+    #[test]
+    fn wraps_runtime_errors() {
+        let err = EnvlangError::from(EvalError::UndefinedIdentifier("port".to_string()));
+        assert_eq!(err.position(), None);
+        assert_eq!(err.code(), "E0301");
+        assert_eq!(err.to_string(), "[E0301] Runtime error: Identifier 'port' is not defined");
+    }
+}
@@ -0,0 +1,117 @@
+use crate::messages::Catalog;
+use crate::numeric::NumericError;
+use crate::span::Span;
+use core::error::Error;
+use core::fmt;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+/// Error type for the Envlang evaluator
+///
+/// The error types match the failure states of running a program, such as looking up an undefined identifier or calling something that is not a function.
+///
+/// The errors contain the values that caused them. The evaluator wraps them in [`EvalError::Located`] with the span of the innermost binding or call they occurred in, if the parser recorded source locations (see [`AstNode::Let`](crate::parser::AstNode::Let) and [`AstNode::FunctionCall`](crate::parser::AstNode::FunctionCall)).
+///
+/// Usage of the error types is documented in the [`Evaluator`](super::Evaluator).
+#[derive(Debug, PartialEq)]
+pub enum EvalError {
+    UndefinedIdentifier(String),                // (name)
+    InvalidOperands(String, String, String),    // (operator, left type, right type)
+    InvalidOperand(String, String),             // (operator, type)
+    Numeric(String, NumericError),              // (operator, error)
+    NotAnEnvironment(String, String, String),   // (path, member, type)
+    MissingMember(String, String, String),      // (path, member, available members)
+    NotAFunction(String),                       // (type)
+    ArityMismatch(usize, usize),                // (expected, provided)
+    CallDepthExceeded(usize),                   // (limit)
+    UnparsedSource,
+    InvalidExpression(String),                  // (value)
+    NotInherited(String),                       // (name)
+    NestingTooDeep(usize),                      // (limit)
+    UnexpectedType(String, String),             // (expected type, type)
+    Located(Box<EvalError>, Span),              // (error, span)
+}
+
+impl Error for EvalError {}
+
+impl EvalError {
+    /// Gets the stable error code of the error
+    ///
+    /// Runtime errors use the codes `E0301` to `E0399`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EvalError::UndefinedIdentifier(_) => "E0301",
+            EvalError::InvalidOperands(_, _, _) => "E0302",
+            EvalError::InvalidOperand(_, _) => "E0303",
+            EvalError::Numeric(_, _) => "E0304",
+            EvalError::NotAnEnvironment(_, _, _) => "E0305",
+            EvalError::MissingMember(_, _, _) => "E0306",
+            EvalError::NotAFunction(_) => "E0307",
+            EvalError::ArityMismatch(_, _) => "E0308",
+            EvalError::CallDepthExceeded(_) => "E0309",
+            EvalError::UnparsedSource => "E0310",
+            EvalError::InvalidExpression(_) => "E0311",
+            EvalError::NotInherited(_) => "E0312",
+            EvalError::NestingTooDeep(_) => "E0313",
+            EvalError::UnexpectedType(_, _) => "E0314",
+            EvalError::Located(err, _) => err.code(),
+        }
+    }
+
+    /// Gets the values filled into the message template of the error (see [`crate::messages`])
+    pub fn arguments(&self) -> Vec<String> {
+        match self {
+            EvalError::UndefinedIdentifier(valuestr)
+            | EvalError::NotAFunction(valuestr)
+            | EvalError::InvalidExpression(valuestr)
+            | EvalError::NotInherited(valuestr) => vec![valuestr.clone()],
+            EvalError::InvalidOperands(first, second, third)
+            | EvalError::NotAnEnvironment(first, second, third)
+            | EvalError::MissingMember(first, second, third) => vec![first.clone(), second.clone(), third.clone()],
+            EvalError::InvalidOperand(first, second)
+            | EvalError::UnexpectedType(first, second) => vec![first.clone(), second.clone()],
+            EvalError::Numeric(op, err) => vec![op.clone(), err.to_string()],
+            EvalError::ArityMismatch(expected, provided) => vec![expected.to_string(), provided.to_string()],
+            EvalError::CallDepthExceeded(limit)
+            | EvalError::NestingTooDeep(limit) => vec![limit.to_string()],
+            EvalError::UnparsedSource => Vec::new(),
+            EvalError::Located(err, _) => err.arguments(),
+        }
+    }
+
+    /// Gets the byte span of the binding or call the error occurred in, or `None` if the AST has no source locations
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            EvalError::Located(_, span) => Some(*span),
+            _ => None,
+        }
+    }
+
+    /// Gets the error without its span
+    pub fn without_span(&self) -> &EvalError {
+        match self {
+            EvalError::Located(err, _) => err.without_span(),
+            _ => self,
+        }
+    }
+
+    /// Attaches a span to the error, unless the error already has a span as it occurred in a nested binding or call
+    pub(crate) fn at(self, span: Option<Span>) -> Self {
+        match span {
+            Some(span) if !matches!(self, EvalError::Located(_, _)) => EvalError::Located(Box::new(self), span),
+            _ => self,
+        }
+    }
+
+    /// Gets the description of the error, without the phase prefix of its `Display` output
+    ///
+    /// The description is rendered from the English message catalog; see [`crate::messages::Catalog`] for translations.
+    pub fn message(&self) -> String { Catalog::default().render(self.code(), &self.arguments()) }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Runtime error: {}", self.message())
+    }
+}
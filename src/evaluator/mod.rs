@@ -0,0 +1,324 @@
+//! The Envlang evaluator
+//!
+//! The evaluator takes the AST of a program from the [`parser`](crate::parser) and runs it, computing the value of every binding of the global environment.
+//!
+//! # Values
+//!
+//! The evaluator computes with the [`Value`]s of the [`value`](crate::value) module:
+//! * Literals evaluate to integers, floats, booleans, and strings.
+//! * Environments evaluate to [`Value::Environment`]s, whose bindings are in the order of the source.
+//!   Assigning a name twice in an environment replaces the value of the first binding.
+//! * Functions evaluate to [`Value::Function`]s, which capture the bindings the function inherits at its definition.
+//!
+//! An environment whose only element is an anonymous value, such as `{ 5; }`, evaluates to that value.
+//!
+//! # Scoping
+//!
+//! Identifiers are resolved with the same rules as in [`specialize`](crate::specialize), and looked up in the [`Environment`]s of the [`environment`](crate::environment) module:
+//! * Implicit environments (assigned without braces) see the bindings declared before them in the environment they are assigned in.
+//! * Explicit environments only see the bindings they inherit, and the bindings declared before them inside the braces.
+//! * Functions see the bindings they inherit at the point of their definition, their parameters, and the bindings of their body.
+//!
+//! Using a binding of an enclosing environment that is not inherited is reported as [`EvalError::NotInherited`], which suggests adding the name to the inherit clause.
+//!
+//! # Calls
+//!
+//! A call binds its arguments to the parameters of the function by position, failing with [`EvalError::ArityMismatch`] if their numbers differ.
+//! The body and then the return statement are evaluated in a fresh [`Environment`], whose parent holds the bindings the function inherits.
+//! Functions can declare and return other functions, which keep the bindings they inherit from the call, e.g., `make[2][3]`.
+//!
+//! # Operations
+//!
+//! Operators are applied with [`Value::binary`] and [`Value::unary`], which follow the [`numeric`](crate::numeric) semantics for numbers.
+//! Any other combination of operator and operands is an error, as Envlang does not convert values implicitly.
+
+mod error;
+mod tests;
+
+pub use error::EvalError;
+
+use crate::environment::{Environment, Inherit};
+use crate::parser::AstNode;
+use crate::printer::print_expression;
+use crate::span::Span;
+use crate::symbols::{Operators, OtherOperators};
+use crate::value::{Bindings, Closure, Value};
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+/// Default maximum nesting depth of function calls (see [`Evaluator::with_max_call_depth`])
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 64;
+
+/// Default maximum nesting depth of evaluated expressions and environments, across all function calls (see [`Evaluator::with_max_depth`])
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// The `Evaluator` struct runs Envlang programs, and holds the state of the running program
+///
+/// An evaluator can run any number of programs, as [`evaluate`](Evaluator::evaluate) resets the state of the previous run and keeps only the configured limits.
+/// Programs share no state, so embedders evaluating many small scripts can keep a single evaluator.
+///
+/// # Panics
+///
+/// The evaluator does not panic, as it instead converts all invalid states into [`EvalError`] objects.
+///
+/// # Errors
+///
+/// * [`EvalError::UndefinedIdentifier`]: An identifier, or a name in an inherit clause, is not visible in the scope (see the [module documentation](self)).
+/// * [`EvalError::NotInherited`]: An identifier is declared in an enclosing environment, but not listed in the inherit clause of the environment or function using it.
+/// * [`EvalError::InvalidOperands`] and [`EvalError::InvalidOperand`]: An operator is applied to values it is not defined for, e.g., `"a" * 2`.
+/// * [`EvalError::Numeric`]: A numeric operation fails, e.g., by overflowing or dividing by zero.
+/// * [`EvalError::NotAnEnvironment`] and [`EvalError::MissingMember`]: An accession does not find an environment or its member. The errors name the failed segment of an accessor chain, e.g., `config.database` when it has no member `port`.
+/// * [`EvalError::NotAFunction`] and [`EvalError::ArityMismatch`]: A function call does not find a function, or gives it the wrong number of arguments.
+/// * [`EvalError::CallDepthExceeded`]: Function calls are nested deeper than the limit, e.g., in runaway recursion.
+/// * [`EvalError::NestingTooDeep`]: Expressions and environments are nested deeper than the limit, counting the bodies of every function being called.
+/// * [`EvalError::UnparsedSource`]: The AST contains an [`AstNode::Error`] from [`Parser::parse_recovering`](crate::parser::Parser::parse_recovering).
+/// * [`EvalError::InvalidExpression`]: A node that is not a value, such as an inherit clause, is found in the place of a value.
+///
+/// Errors in a binding or call are wrapped in [`EvalError::Located`] with the span of the innermost binding or call, if the parser recorded one.
+/// The return statement of a function has no binding, so its errors point at the call.
+///
+/// # Examples
+/// ```
+/// use envlang::evaluator::Evaluator;
+/// use envlang::parse_source;
+/// use envlang::printer::print_ast;
+///
+/// let ast = parse_source("let fun area[w, h] = { return w * h; }\nlet size = area[3, 4] + 1;\n").unwrap();
+/// let env = Evaluator::new().evaluate(&ast).unwrap();
+/// assert!(print_ast(&env.to_node()).ends_with("let size = 13;\n"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Evaluator {
+    max_call_depth: usize,
+    max_depth: usize,
+    depth: usize,
+    stack: Vec<Frame>,
+}
+
+/// A function call being evaluated, as recorded in the [call stack](Evaluator::call_stack) of the evaluator
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// The callee as written in the call, e.g., `env.twice`
+    pub callee: String,
+    /// The span of the call (see [`AstNode::FunctionCall`])
+    pub call: Option<Span>,
+    /// The span of the name in the declaration of the called function (see [`AstNode::Function`]), which functions keep when they are bound to other names or returned from calls
+    pub declaration: Option<Span>,
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        return Evaluator { max_call_depth: DEFAULT_MAX_CALL_DEPTH, max_depth: DEFAULT_MAX_DEPTH, depth: 0, stack: Vec::new() };
+    }
+}
+
+impl Evaluator {
+    /// Creates an evaluator, limiting the nesting depth of function calls to [`DEFAULT_MAX_CALL_DEPTH`]
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets the maximum nesting depth of function calls, beyond which evaluation fails with [`EvalError::CallDepthExceeded`]
+    ///
+    /// The evaluator is recursive, so the limit keeps runaway recursion from overflowing the stack.
+    pub fn with_max_call_depth(mut self, limit: usize) -> Self {
+        self.max_call_depth = limit;
+        return self;
+    }
+
+    /// Sets the maximum nesting depth of evaluated expressions and environments, beyond which evaluation fails with [`EvalError::NestingTooDeep`]
+    ///
+    /// The depth adds up across function calls, as each call evaluates the body of its function inside the expression making the call.
+    /// The parser limits the depth of each function, but not how deep nested calls of different functions make the evaluator recurse.
+    pub fn with_max_depth(mut self, limit: usize) -> Self {
+        self.max_depth = limit;
+        return self;
+    }
+
+    /// Evaluates a program, returning its global environment as a [`Value::Environment`]
+    ///
+    /// The global environment is never flattened into its only value, unlike the environments inside the program.
+    pub fn evaluate(&mut self, ast: &AstNode) -> Result<Value, EvalError> {
+        self.stack.clear();
+        self.depth = 0;
+        let statements = ast.get_bindings().unwrap_or_else(|| vec![Rc::new(ast.clone())]);
+        let (bindings, _) = self.bindings(&statements, &mut Rc::new(Environment::new()))?;
+        return Ok(Value::Environment { name: ast.get_name(), bindings: Rc::new(bindings) });
+    }
+
+    /// Gets the function calls that were being evaluated when the last evaluation failed, outermost first
+    ///
+    /// The stack is empty after a successful evaluation, and after errors outside of functions.
+    ///
+    /// # Examples
+    /// ```
+    /// use envlang::evaluator::Evaluator;
+    /// use envlang::parse_source;
+    ///
+    /// let ast = parse_source("let fun f[a] = { return a + b; }\nlet c = f[1];\n").unwrap();
+    /// let mut evaluator = Evaluator::new();
+    /// assert!(evaluator.evaluate(&ast).is_err());
+    /// assert_eq!(evaluator.call_stack()[0].callee, "f");
+    /// ```
+    pub fn call_stack(&self) -> &[Frame] { &self.stack }
+
+    /// Evaluates the elements of an environment in a scope, declaring its bindings in the scope
+    ///
+    /// Returns the evaluated bindings, and the value of the only element of the environment if it is not a binding.
+    /// Children of the scope share it instead of copying it, so a declaration only copies the scope if a child still holds it (see [`Environment`]).
+    fn bindings(&mut self, statements: &[Rc<AstNode>], scope: &mut Rc<Environment>) -> Result<(Bindings, Option<Value>), EvalError> {
+        let mut bindings = Bindings::new();
+        let mut only = None;
+        for statement in statements {
+            match statement.as_ref() {
+                AstNode::Let { name, value: Some(value), inherit, span } => {
+                    let value = self.assigned(name, value, inherit.as_deref(), scope).map_err(|err| err.at(*span))?;
+                    Rc::make_mut(scope).define(name.clone(), value.clone());
+                    bindings.insert(name.clone(), value);
+                },
+                // Bindings without a value declare nothing
+                AstNode::Let { value: None, .. } => (),
+                node => {
+                    let value = self.statement(node, scope)?;
+                    if statements.len() == 1 {
+                        only = Some(value);
+                    }
+                },
+            }
+        }
+        return Ok((bindings, only));
+    }
+
+    /// Evaluates the value assigned to a binding
+    fn assigned(&mut self, name: &Rc<str>, value: &AstNode, inherit: Option<&AstNode>, scope: &Rc<Environment>) -> Result<Value, EvalError> {
+        match value {
+            AstNode::Environment { bindings, .. } => {
+                let mut inner = Rc::new(Environment::inheriting(scope.clone(), inherit)?);
+                return self.environment(Some(name.clone()), bindings, &mut inner);
+            },
+            AstNode::Function { .. } => {
+                let captured = Environment::inheriting(scope.clone(), inherit)?;
+                return function(value, Rc::new(captured));
+            },
+            _ => return self.expression(value, scope),
+        }
+    }
+
+    /// Evaluates an environment in a scope, flattening it into its only element if that element is not a binding
+    fn environment(&mut self, name: Option<Rc<str>>, statements: &[Rc<AstNode>], scope: &mut Rc<Environment>) -> Result<Value, EvalError> {
+        let (bindings, only) = self.nested(|evaluator| evaluator.bindings(statements, scope))?;
+        match only {
+            Some(value) => return Ok(value),
+            None => return Ok(Value::Environment { name, bindings: Rc::new(bindings) }),
+        }
+    }
+
+    /// Evaluates an element of an environment that is not a binding
+    fn statement(&mut self, node: &AstNode, scope: &Rc<Environment>) -> Result<Value, EvalError> {
+        match node {
+            // Explicit environments without an inherit clause see none of the enclosing bindings
+            // The enclosing bindings are kept as the parent, so that using them is reported as a missing inherit clause
+            AstNode::Environment { name, bindings, .. } => {
+                let mut inner = Rc::new(Environment::child(scope.clone(), Inherit::Nothing));
+                return self.environment(name.clone(), bindings, &mut inner);
+            },
+            AstNode::Function { .. } => return function(node, Rc::new(Environment::child(scope.clone(), Inherit::Nothing))),
+            _ => return self.expression(node, scope),
+        }
+    }
+
+    /// Runs a step of the evaluation one level deeper, failing if the levels exceed the limit
+    fn nested<T>(&mut self, step: impl FnOnce(&mut Self) -> Result<T, EvalError>) -> Result<T, EvalError> {
+        if self.depth >= self.max_depth {
+            return Err(EvalError::NestingTooDeep(self.max_depth));
+        }
+        self.depth += 1;
+        let result = step(self);
+        self.depth -= 1;
+        return result;
+    }
+
+    /// Evaluates an expression
+    fn expression(&mut self, node: &AstNode, scope: &Rc<Environment>) -> Result<Value, EvalError> {
+        return self.nested(|evaluator| evaluator.operation(node, scope));
+    }
+
+    /// Evaluates an expression at the current depth (see [`expression`](Evaluator::expression))
+    fn operation(&mut self, node: &AstNode, scope: &Rc<Environment>) -> Result<Value, EvalError> {
+        match node {
+            AstNode::Integer(_) | AstNode::Float(_) | AstNode::Boolean(_) | AstNode::String(_) => {
+                return Value::from_literal(node).ok_or_else(|| EvalError::InvalidExpression(node.to_string()));
+            },
+            AstNode::Identifier(name) => {
+                match scope.lookup_recursive(name) {
+                    Some(value) => return Ok(value.clone()),
+                    None if scope.is_hidden(name) => return Err(EvalError::NotInherited(name.to_string())),
+                    None => return Err(EvalError::UndefinedIdentifier(name.to_string())),
+                }
+            },
+            AstNode::Environment { .. } | AstNode::Function { .. } => return self.statement(node, scope),
+            AstNode::UnaryOp { op, operand } => return self.expression(operand, scope)?.unary(op),
+            AstNode::BinaryOp { left, operator: Operators::Other(OtherOperators::ACCESSOR), right } => {
+                let target = self.expression(left, scope)?;
+                let AstNode::Identifier(member) = right.as_ref() else {
+                    return Err(EvalError::InvalidExpression(right.to_string()));
+                };
+                return target.member(&print_expression(left), member);
+            },
+            AstNode::BinaryOp { left, operator, right } => {
+                let left = self.expression(left, scope)?;
+                let right = self.expression(right, scope)?;
+                return left.binary(operator, &right);
+            },
+            AstNode::FunctionCall { id, args, span } => return self.call(id, args, *span, scope).map_err(|err| err.at(*span)),
+            AstNode::Error(_) => return Err(EvalError::UnparsedSource),
+            AstNode::Let { .. } | AstNode::Inherit { .. } | AstNode::FunctionArgs(_) => {
+                return Err(EvalError::InvalidExpression(node.to_string()));
+            },
+        }
+    }
+
+    /// Calls a function with arguments evaluated in the scope of the caller
+    fn call(&mut self, id: &AstNode, args: &AstNode, call: Option<Span>, scope: &Rc<Environment>) -> Result<Value, EvalError> {
+        let function = self.expression(id, scope)?;
+        let Value::Function(closure) = &function else {
+            return Err(EvalError::NotAFunction(function.type_name().to_string()));
+        };
+        let args = args.get_params().unwrap_or_default().iter()
+            .map(|arg| self.expression(arg, scope))
+            .collect::<Result<Vec<Value>, EvalError>>()?;
+        if closure.params.len() != args.len() {
+            return Err(EvalError::ArityMismatch(closure.params.len(), args.len()));
+        }
+        if self.stack.len() >= self.max_call_depth {
+            return Err(EvalError::CallDepthExceeded(self.max_call_depth));
+        }
+
+        let mut inner = Environment::child(closure.captured.clone(), Inherit::All);
+        for (param, arg) in closure.params.iter().zip(args) {
+            inner.define(param.clone(), arg);
+        }
+        let mut inner = Rc::new(inner);
+
+        // The return statement sees the bindings of the function body
+        // The frame is only popped on success, so that the stack of a failed evaluation remains for reporting
+        self.stack.push(Frame { callee: print_expression(id), call, declaration: closure.span });
+        self.bindings(&closure.body.get_bindings().unwrap_or_default(), &mut inner)?;
+        let result = self.environment(None, &closure.r#return.get_bindings().unwrap_or_default(), &mut inner)?;
+        self.stack.pop();
+        return Ok(result);
+    }
+}
+
+/// Creates a function value, capturing the bindings it inherits
+fn function(node: &AstNode, captured: Rc<Environment>) -> Result<Value, EvalError> {
+    let AstNode::Function { params, body, r#return, span } = node else {
+        return Err(EvalError::InvalidExpression(node.to_string()));
+    };
+    let params = params.get_params().unwrap_or_default().iter()
+        .filter_map(|param| match param.as_ref() {
+            AstNode::Identifier(name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect();
+    return Ok(Value::Function(Rc::new(Closure { params, body: body.clone(), r#return: r#return.clone(), captured, span: *span })));
+}
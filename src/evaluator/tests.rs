@@ -0,0 +1,230 @@
+#[cfg(test)]
+mod tests {
+    use crate::evaluator::{EvalError, Evaluator, Frame};
+    use crate::lexer::Lexer;
+    use crate::numeric::NumericError;
+    use crate::parser::{AstNode, Parser};
+    use crate::printer::print_ast;
+    use crate::span::Span;
+    use crate::symbols::{Operators, OtherOperators};
+    use std::rc::Rc;
+    use std::time::Instant;
+
+    fn evaluated(source: &str) -> Result<String, EvalError> {
+        let tokens = Lexer::from_text(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        return Evaluator::new().evaluate(&ast).map(|env| print_ast(&env.to_node()));
+    }
+
+    #[test]
+    fn evaluates_expressions() {
+        let source = "let a = 7 / 2;\nlet b = a * 1.5;\nlet c = -b;\nlet d = a >= 3;\nlet d = d & !false;\nlet e = \"env\" + \"lang\";\nlet f = e == \"envlang\";\nlet g = 1 + 2 * 3 - (4 - 2) ^ 2;\n";
+        assert_eq!(
+            evaluated(source).unwrap(),
+            "let a = 3;\nlet b = 4.5;\nlet c = -4.5;\nlet d = true;\nlet e = \"envlang\";\nlet f = true;\nlet g = 3;\n",
+        );
+    }
+
+    #[test]
+    fn evaluates_environments() {
+        let source = "let x = 2;\nlet env = {\n    let y = 5;\n    let z = y + 2;\n};\nlet single = { 5; };\nlet sum = env.z + x;\nlet x = 3;\n";
+        assert_eq!(
+            evaluated(source).unwrap(),
+            "let x = 3;\nlet env = {\n    let y = 5;\n    let z = 7;\n};\nlet single = 5;\nlet sum = 9;\n",
+        );
+    }
+
+    #[test]
+    fn honors_scopes() {
+        assert_eq!(evaluated("let x = 2;\nlet a = { let y = x; };\n"), Err(EvalError::NotInherited("x".into())));
+        assert_eq!(evaluated("let x = 2;\nlet y = 3;\nlet a inherit (y) = { let z = x + y; };\n"), Err(EvalError::NotInherited("x".into())));
+        assert_eq!(evaluated("let a = { let y = x; };\n"), Err(EvalError::UndefinedIdentifier("x".into())));
+        assert_eq!(
+            evaluated("let x = 2;\nlet b inherit (x) = { let y = x * x; };\nlet c inherit (*) = { let y = b.y + x; };\n").unwrap(),
+            "let x = 2;\nlet b = {\n    let y = 4;\n};\nlet c = {\n    let y = 6;\n};\n",
+        );
+        assert_eq!(evaluated("let b inherit (x) = { 1; };\n"), Err(EvalError::UndefinedIdentifier("x".into())));
+    }
+
+    #[test]
+    fn calls_functions() {
+        let source = "let fun add[a, b] = { let c = a + b; return c; }\nlet seven = add[5, 2];\nlet env = { let fun twice[a] = { return a * 2; } }\nlet ten = env.twice[seven] - add[2, 2];\n";
+        let env = evaluated(source).unwrap();
+        assert!(env.ends_with("let ten = 10;\n"), "{}", env);
+
+        // Functions see what they inherit at their definition, and nothing else
+        let source = "let n = 10;\nlet fun f[] inherit (n) = { return n; }\nlet n = 20;\nlet m = f[];\nlet fun g[] = { return n; }\nlet k = g[];\n";
+        assert_eq!(evaluated(source), Err(EvalError::NotInherited("n".into())));
+        let env = evaluated("let n = 10;\nlet fun f[] inherit (n) = { return n; }\nlet n = 20;\nlet m = f[] + n;\n").unwrap();
+        assert!(env.ends_with("let m = 30;\n"), "{}", env);
+    }
+
+    #[test]
+    fn calls_closures() {
+        // Functions can return functions, which keep the bindings they inherit from the call
+        let source = "let fun make[n] = {\n    let fun add[x] inherit (n) = {\n        return x + n;\n    }\n    return add;\n}\nlet add5 = make[5];\nlet a = add5[1];\nlet b = make[2][3];\nlet n = 100;\nlet c = add5[a];\n";
+        let env = evaluated(source).unwrap();
+        assert!(env.ends_with("let a = 6;\nlet b = 5;\nlet n = 100;\nlet c = 11;\n"), "{}", env);
+        assert_eq!(evaluated("let fun make[n] = {\n    let fun f[a, b] = {\n        return a + b;\n    }\n    return f;\n}\nlet c = make[1][2];\n"), Err(EvalError::ArityMismatch(2, 1)));
+    }
+
+    #[test]
+    fn reports_runtime_errors() {
+        assert_eq!(evaluated("let a = \"a\" * 2;\n"), Err(EvalError::InvalidOperands("*".into(), "a string".into(), "an integer".into())));
+        assert_eq!(evaluated("let a = !5;\n"), Err(EvalError::InvalidOperand("!".into(), "an integer".into())));
+        assert_eq!(evaluated("let a = 1 / 0;\n"), Err(EvalError::Numeric("/".into(), NumericError::DivisionByZero)));
+        assert_eq!(evaluated("let a = 1;\nlet b = a.c;\n"), Err(EvalError::NotAnEnvironment("a".into(), "c".into(), "an integer".into())));
+        assert_eq!(evaluated("let a = { let b = 1; };\nlet c = a.d;\n"), Err(EvalError::MissingMember("a".into(), "d".into(), "'b'".into())));
+        assert_eq!(evaluated("let a = 1;\nlet b = a[2];\n"), Err(EvalError::NotAFunction("an integer".into())));
+        assert_eq!(evaluated("let fun f[a] = { return a; }\nlet b = f[1, 2];\n"), Err(EvalError::ArityMismatch(1, 2)));
+        assert_eq!(EvalError::ArityMismatch(1, 2).to_string(), "Runtime error: Function takes 1 arguments, but 2 were given");
+    }
+
+    #[test]
+    fn reports_failed_accessor_segments() {
+        let config = "let config = { let database = { let port = 5432; }; };\n";
+        let env = evaluated(&format!("{}let port = config.database.port;\n", config)).unwrap();
+        assert!(env.ends_with("let port = 5432;\n"), "{}", env);
+        assert_eq!(
+            evaluated(&format!("{}let host = config.database.host;\n", config)),
+            Err(EvalError::MissingMember("config.database".into(), "host".into(), "'port'".into())),
+        );
+        assert_eq!(
+            evaluated(&format!("{}let port = config.db.port;\n", config)),
+            Err(EvalError::MissingMember("config".into(), "db".into(), "'database'".into())),
+        );
+        assert_eq!(
+            evaluated(&format!("{}let port = config.database.port.value;\n", config)),
+            Err(EvalError::NotAnEnvironment("config.database.port".into(), "value".into(), "an integer".into())),
+        );
+        assert_eq!(
+            EvalError::MissingMember("config.database".into(), "host".into(), "'port'".into()).to_string(),
+            "Runtime error: Environment 'config.database' has no member 'host'; its members are: 'port'",
+        );
+
+        // Only the first members of large environments are listed
+        let names: Vec<String> = (0..10).map(|i| format!("let m{} = {};", i, i)).collect();
+        let result = evaluated(&format!("let e = {{ {} }};\nlet x = e.y;\n", names.join(" ")));
+        assert_eq!(result, Err(EvalError::MissingMember("e".into(), "y".into(), "'m0', 'm1', 'm2', 'm3', 'm4', 'm5', 'm6', 'm7', …".into())));
+    }
+
+    #[test]
+    fn limits_call_depth() {
+        let source = "let fun self_apply[f] = { return f[f]; }\nlet loop = self_apply[self_apply];\n";
+        assert_eq!(evaluated(source), Err(EvalError::CallDepthExceeded(crate::evaluator::DEFAULT_MAX_CALL_DEPTH)));
+
+        let tokens = Lexer::from_text("let fun f[] = { return 1; }\nlet a = f[];\n").tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        assert_eq!(Evaluator::new().with_max_call_depth(0).evaluate(&ast), Err(EvalError::CallDepthExceeded(0)));
+
+        // The depth of the called functions adds up with the depth of the calls
+        let tokens = Lexer::from_text("let fun f[a] = { return (a + 1) * 2; }\nlet fun g[a] inherit (f) = { return f[a] + 1; }\nlet b = g[1];\n").tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        assert_eq!(Evaluator::new().with_max_depth(4).evaluate(&ast), Err(EvalError::NestingTooDeep(4)));
+        assert!(Evaluator::new().with_max_depth(16).evaluate(&ast).is_ok());
+    }
+
+    #[test]
+    fn reuses_evaluators() {
+        let mut evaluator = Evaluator::new().with_max_call_depth(2);
+        let failing = Parser::new(Lexer::from_text("let fun self_apply[f] = { return f[f]; }\nlet loop = self_apply[self_apply];\n").tokenize().unwrap()).parse().unwrap();
+        let passing = Parser::new(Lexer::from_text("let fun f[] = { return 1; }\nlet a = f[];\n").tokenize().unwrap()).parse().unwrap();
+        for _ in 0..100 {
+            assert_eq!(evaluator.evaluate(&failing), Err(EvalError::CallDepthExceeded(2)));
+            assert_eq!(evaluator.evaluate(&passing).map(|env| print_ast(&env.to_node())), evaluated("let fun f[] = { return 1; }\nlet a = f[];\n"));
+            assert!(evaluator.call_stack().is_empty());
+        }
+    }
+
+    #[test]
+    fn locates_errors() {
+        let located = |source: &str| Parser::with_locations(Lexer::from_text(source).tokenize_with_locations().unwrap()).parse().unwrap();
+        let operands = || EvalError::InvalidOperands("+".into(), "an integer".into(), "a string".into());
+
+        // Errors point at the name of the innermost binding
+        let result = Evaluator::new().evaluate(&located("let a = 1;\nlet b = a + \"x\";\n"));
+        assert_eq!(result, Err(EvalError::Located(Box::new(operands()), Span::new(15, 16))));
+        let result = Evaluator::new().evaluate(&located("let env = { let a = 1; let b = a + true; }\n"));
+        assert_eq!(result.map_err(|err| err.span()), Err(Some(Span::new(27, 28))));
+
+        // Errors in a return statement point at the call
+        let result = Evaluator::new().evaluate(&located("let fun f[a] = { return a + \"x\"; }\nlet r = f[1];\n"));
+        assert_eq!(result, Err(EvalError::Located(Box::new(operands()), Span::new(43, 47))));
+        assert_eq!(result.unwrap_err().without_span(), &operands());
+
+        // Errors keep the code and message of the located error
+        let err = EvalError::Located(Box::new(operands()), Span::new(0, 1));
+        assert_eq!((err.code(), err.to_string()), (operands().code(), operands().to_string()));
+
+        // Without source locations, errors are not located
+        assert_eq!(evaluated("let a = 1;\nlet b = a + \"x\";\n"), Err(operands()));
+    }
+
+    #[test]
+    fn records_the_call_stack_of_errors() {
+        let located = |source: &str| Parser::with_locations(Lexer::from_text(source).tokenize_with_locations().unwrap()).parse().unwrap();
+        let source = "let env = { let fun inner[a] = { return a + b; } }\nlet fun outer[f] = { return f[1]; }\nlet c = outer[env.inner];\n";
+        let mut evaluator = Evaluator::new();
+        assert_eq!(evaluator.evaluate(&located(source)), Err(EvalError::Located(Box::new(EvalError::UndefinedIdentifier("b".into())), Span::new(79, 83))));
+        // Functions keep the span of their declaration when bound to parameters
+        assert_eq!(evaluator.call_stack(), &[
+            Frame { callee: "outer".into(), call: Some(Span::new(95, 111)), declaration: Some(Span::new(59, 64)) },
+            Frame { callee: "f".into(), call: Some(Span::new(79, 83)), declaration: Some(Span::new(20, 25)) },
+        ]);
+
+        // Callees are recorded as written in the source
+        let source = "let env = { let fun make[n] = { let fun add[x] = { return x + n; } return add; } }\nlet c = env.make[2][1];\n";
+        assert_eq!(evaluator.evaluate(&located(source)).map_err(|err| err.span()), Err(Some(Span::new(91, 105))));
+        assert_eq!(evaluator.call_stack(), &[Frame { callee: "env.make[2]".into(), call: Some(Span::new(91, 105)), declaration: Some(Span::new(40, 43)) }]);
+
+        // Without source locations, the spans are unknown
+        let source = "let fun f[] = { return g; }\nlet a = f[];\n";
+        assert!(evaluator.evaluate(&Parser::new(Lexer::from_text(source).tokenize().unwrap()).parse().unwrap()).is_err());
+        assert_eq!(evaluator.call_stack(), &[Frame { callee: "f".into(), call: None, declaration: None }]);
+
+        let tokens = Lexer::from_text("let fun f[] = { return 1; }\nlet a = f[];\n").tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        assert!(evaluator.evaluate(&ast).is_ok());
+        assert!(evaluator.call_stack().is_empty());
+    }
+
+    #[test]
+    fn evaluates_large_programs_in_linear_time() {
+        // Each binding assigns an environment, which sees every binding before it, and is then accessed
+        let program = |count: usize| {
+            let mut bindings = Vec::new();
+            for i in 0..count {
+                let name: Rc<str> = format!("a{}", i).into();
+                let inner = AstNode::Let { name: "x".into(), value: Some(Rc::new(AstNode::Integer(i as isize))), inherit: None, span: None };
+                let env = AstNode::Environment { name: None, bindings: vec![Rc::new(inner)], parent: None };
+                bindings.push(Rc::new(AstNode::Let { name: name.clone(), value: Some(Rc::new(env)), inherit: None, span: None }));
+                let access = AstNode::BinaryOp {
+                    left: Rc::new(AstNode::Identifier(name)),
+                    operator: Operators::Other(OtherOperators::ACCESSOR),
+                    right: Rc::new(AstNode::Identifier("x".into())),
+                };
+                bindings.push(Rc::new(AstNode::Let { name: format!("b{}", i).into(), value: Some(Rc::new(access)), inherit: None, span: None }));
+            }
+            return AstNode::Environment { name: None, bindings, parent: None };
+        };
+        let fastest = |ast: &AstNode| (0..3)
+            .map(|_| {
+                let start = Instant::now();
+                assert!(Evaluator::new().evaluate(ast).is_ok());
+                start.elapsed()
+            })
+            .min()
+            .unwrap();
+
+        // Four times the bindings take about four times as long, where copying the scope for every environment took sixteen times as long
+        let small = fastest(&program(2000));
+        let large = fastest(&program(8000));
+        assert!(large < small * 10, "2000 bindings took {:?}, 8000 bindings took {:?}", small, large);
+    }
+
+    #[test]
+    fn rejects_unparsed_source() {
+        let ast = AstNode::Environment { name: None, bindings: vec![Rc::new(AstNode::Error(None))], parent: None };
+        assert_eq!(Evaluator::new().evaluate(&ast), Err(EvalError::UnparsedSource));
+    }
+}
@@ -0,0 +1,278 @@
+//! Inlining of small functions
+//!
+//! [`inline`] replaces calls to small functions with the expression the function returns, with the arguments of the call substituted for the parameters.
+//! The inlined program evaluates to the same global environment as the original one, with fewer function calls, e.g. in scripts that build their environments with small helpers.
+//!
+//! # Inlined functions
+//!
+//! A function is inlined if all of the following hold:
+//! * Its body has no bindings, and it returns a single expression, e.g. `let fun port[n] = { return 8000 + n; }`.
+//! * The returned expression only refers to the parameters of the function, and refers to each of them at least once, so that no argument goes unevaluated.
+//! * The returned expression has at most `max_size` nodes. An argument is evaluated as many times as its parameter is used, so the size bounds the duplicated work.
+//! * The call gives the function as many arguments as it has parameters.
+//!
+//! As the returned expression cannot refer to the function itself, inlined functions are never recursive, and the result of inlining is not inlined again.
+//! Functions that are passed as arguments are left as they are, since their value is not known before evaluation.
+//!
+//! # Scoping
+//!
+//! Functions are only inlined where their binding is visible, with the same rules as in [`specialize`](crate::specialize).
+//! Bindings are never removed, since they are a part of the resulting environment.
+//!
+//! Inlined calls are not a part of the [call stack](crate::evaluator::Evaluator::call_stack) of runtime errors. If several arguments of an inlined call fail to evaluate, the reported error may differ from the original program.
+
+use crate::parser::AstNode;
+use crate::symbols::{Operators, OtherOperators};
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+/// Default maximum number of nodes in the expression of an inlined function (see [`inline`])
+pub const DEFAULT_MAX_INLINE_SIZE: usize = 16;
+
+/// A function that can be inlined: its parameters, and the expression it returns
+#[derive(Debug, Clone)]
+struct Inlinable {
+    params: Vec<Rc<str>>,
+    expression: AstNode,
+}
+
+/// Names visible in an environment, with their function if it can be inlined, or `None` otherwise
+type Scope = BTreeMap<Rc<str>, Option<Rc<Inlinable>>>;
+
+/// Inlines calls to small functions in a program
+///
+/// Functions returning an expression of at most `max_size` nodes are inlined (see the [module documentation](self)).
+///
+/// # Examples
+/// ```
+/// use envlang::inline::{inline, DEFAULT_MAX_INLINE_SIZE};
+/// use envlang::parse_source;
+/// use envlang::printer::print_ast;
+///
+/// let ast = parse_source("let fun port[n] = { return 8000 + n; }\nlet api = port[80];\n").unwrap();
+/// let inlined = print_ast(&inline(&ast, DEFAULT_MAX_INLINE_SIZE));
+/// assert!(inlined.ends_with("let api = 8000 + 80;\n"));
+/// ```
+pub fn inline(ast: &AstNode, max_size: usize) -> AstNode {
+    let mut scope = Scope::new();
+    match ast {
+        AstNode::Environment { .. } => return environment_in(ast, &mut scope, max_size),
+        node => return statement(node, &mut scope, max_size),
+    }
+}
+
+/// Gets a function as an inlinable function, if it meets the conditions of the [module documentation](self)
+fn inlinable(node: &AstNode, max_size: usize) -> Option<Inlinable> {
+    let AstNode::Function { params, body, r#return, .. } = node else { return None; };
+    if !body.get_bindings().unwrap_or_default().is_empty() {
+        return None;
+    }
+    let statements = r#return.get_bindings().unwrap_or_default();
+    let [expression] = &statements[..] else { return None; };
+    if !matches!(expression.as_ref(),
+        AstNode::Integer(_) | AstNode::Float(_) | AstNode::Boolean(_) | AstNode::String(_)
+        | AstNode::Identifier(_) | AstNode::BinaryOp { .. } | AstNode::UnaryOp { .. } | AstNode::FunctionCall { .. })
+    {
+        return None;
+    }
+    let params = params.get_params().unwrap_or_default().iter()
+        .map(|param| match param.as_ref() {
+            AstNode::Identifier(name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect::<Option<Vec<Rc<str>>>>()?;
+
+    let mut names = Vec::new();
+    let size = references(expression, &mut names)?;
+    if size > max_size || !names.iter().all(|name| params.contains(name)) || !params.iter().all(|param| names.contains(param)) {
+        return None;
+    }
+    return Some(Inlinable { params, expression: expression.as_ref().clone() });
+}
+
+/// Collects the identifiers an expression refers to, returning the number of nodes in the expression, or `None` if it contains a node that is not an expression
+///
+/// The members of accessions are not collected, as they are looked up in another environment.
+fn references(node: &AstNode, names: &mut Vec<Rc<str>>) -> Option<usize> {
+    match node {
+        AstNode::Integer(_) | AstNode::Float(_) | AstNode::Boolean(_) | AstNode::String(_) => return Some(1),
+        AstNode::Identifier(name) => {
+            names.push(name.clone());
+            return Some(1);
+        },
+        AstNode::BinaryOp { left, operator: Operators::Other(OtherOperators::ACCESSOR), .. } => return Some(references(left, names)? + 2),
+        AstNode::BinaryOp { left, right, .. } => return Some(references(left, names)? + references(right, names)? + 1),
+        AstNode::UnaryOp { operand, .. } => return Some(references(operand, names)? + 1),
+        AstNode::FunctionCall { id, args, .. } => {
+            let mut size = references(id, names)? + 1;
+            for arg in args.get_params().unwrap_or_default() {
+                size += references(&arg, names)?;
+            }
+            return Some(size);
+        },
+        _ => return None,
+    }
+}
+
+/// Inlines calls in an element of an environment, declaring the bindings it makes in the scope
+fn statement(node: &AstNode, scope: &mut Scope, max_size: usize) -> AstNode {
+    match node {
+        AstNode::Let { name, value, inherit, span } => {
+            let value = value.as_ref().map(|value| match value.as_ref() {
+                AstNode::Environment { .. } => environment_in(value, &mut inherited(inherit.as_deref(), scope), max_size),
+                AstNode::Function { .. } => statement(value, &mut inherited(inherit.as_deref(), scope), max_size),
+                _ => expression(value, scope),
+            });
+            let function = value.as_ref().and_then(|value| inlinable(value, max_size)).map(Rc::new);
+            scope.insert(name.clone(), function);
+            return AstNode::Let { name: name.clone(), value: value.map(Rc::new), inherit: inherit.clone(), span: *span };
+        },
+        // Explicit environments without an inherit clause see none of the enclosing bindings
+        AstNode::Environment { .. } => return environment_in(node, &mut Scope::new(), max_size),
+        AstNode::Function { params, body, r#return, span } => {
+            for param in params.get_params().unwrap_or_default() {
+                if let AstNode::Identifier(param) = param.as_ref() {
+                    scope.insert(param.clone(), None);
+                }
+            }
+            // The return statement sees the bindings of the function body
+            let body = Rc::new(environment_in(body, scope, max_size));
+            let r#return = Rc::new(environment_in(r#return, scope, max_size));
+            return AstNode::Function { params: params.clone(), body, r#return, span: *span };
+        },
+        _ => return expression(node, scope),
+    }
+}
+
+/// Inlines calls in the bindings of an environment in an existing scope
+fn environment_in(node: &AstNode, scope: &mut Scope, max_size: usize) -> AstNode {
+    match node {
+        AstNode::Environment { name, bindings, parent } => {
+            let bindings = bindings.iter().map(|binding| Rc::new(statement(binding, scope, max_size))).collect();
+            return AstNode::Environment { name: name.clone(), bindings, parent: parent.clone() };
+        },
+        _ => return statement(node, scope, max_size),
+    }
+}
+
+/// Gets the scope inherited from an enclosing scope through an inherit clause
+fn inherited(inherit: Option<&AstNode>, scope: &Scope) -> Scope {
+    match inherit {
+        Some(AstNode::Inherit { names: None }) => return scope.clone(),
+        Some(AstNode::Inherit { names: Some(names) }) => {
+            return names.iter()
+                .map(|name| (name.clone(), scope.get(name).cloned().flatten()))
+                .collect();
+        },
+        _ => return Scope::new(),
+    }
+}
+
+/// Inlines calls in an expression
+fn expression(node: &AstNode, scope: &Scope) -> AstNode {
+    match node {
+        AstNode::BinaryOp { left, operator: operator @ Operators::Other(OtherOperators::ACCESSOR), right } => {
+            return AstNode::BinaryOp { left: Rc::new(expression(left, scope)), operator: operator.clone(), right: right.clone() };
+        },
+        AstNode::BinaryOp { left, operator, right } => {
+            let left = Rc::new(expression(left, scope));
+            let right = Rc::new(expression(right, scope));
+            return AstNode::BinaryOp { left, operator: operator.clone(), right };
+        },
+        AstNode::UnaryOp { op, operand } => {
+            return AstNode::UnaryOp { op: op.clone(), operand: Rc::new(expression(operand, scope)) };
+        },
+        AstNode::FunctionCall { id, args, span } => {
+            let id = expression(id, scope);
+            let args = args.get_params().unwrap_or_default().iter()
+                .map(|arg| Rc::new(expression(arg, scope)))
+                .collect::<Vec<Rc<AstNode>>>();
+            if let AstNode::Identifier(name) = &id {
+                if let Some(Some(function)) = scope.get(name) {
+                    if function.params.len() == args.len() {
+                        let arguments = function.params.iter().cloned().zip(args).collect();
+                        return substitute(&function.expression, &arguments);
+                    }
+                }
+            }
+            return AstNode::FunctionCall { id: Rc::new(id), args: Rc::new(AstNode::FunctionArgs(args)), span: *span };
+        },
+        _ => return node.clone(),
+    }
+}
+
+/// Replaces the parameters in the expression of an inlined function with the arguments of the call
+fn substitute(node: &AstNode, arguments: &BTreeMap<Rc<str>, Rc<AstNode>>) -> AstNode {
+    match node {
+        AstNode::Identifier(name) => match arguments.get(name) {
+            Some(arg) => return arg.as_ref().clone(),
+            None => return node.clone(),
+        },
+        AstNode::BinaryOp { left, operator: operator @ Operators::Other(OtherOperators::ACCESSOR), right } => {
+            return AstNode::BinaryOp { left: Rc::new(substitute(left, arguments)), operator: operator.clone(), right: right.clone() };
+        },
+        AstNode::BinaryOp { left, operator, right } => {
+            let left = Rc::new(substitute(left, arguments));
+            let right = Rc::new(substitute(right, arguments));
+            return AstNode::BinaryOp { left, operator: operator.clone(), right };
+        },
+        AstNode::UnaryOp { op, operand } => return AstNode::UnaryOp { op: op.clone(), operand: Rc::new(substitute(operand, arguments)) },
+        AstNode::FunctionCall { id, args, span } => {
+            let args = args.get_params().unwrap_or_default().iter()
+                .map(|arg| Rc::new(substitute(arg, arguments)))
+                .collect::<Vec<Rc<AstNode>>>();
+            return AstNode::FunctionCall { id: Rc::new(substitute(id, arguments)), args: Rc::new(AstNode::FunctionArgs(args)), span: *span };
+        },
+        _ => return node.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluator::Evaluator;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::printer::print_ast;
+
+    fn parse(source: &str) -> AstNode {
+        let tokens = Lexer::from_text(source).tokenize().unwrap();
+        return Parser::new(tokens).parse().unwrap();
+    }
+
+    fn inlined(source: &str) -> alloc::string::String { print_ast(&inline(&parse(source), DEFAULT_MAX_INLINE_SIZE)) }
+
+    #[test]
+    fn inlines_small_functions() {
+        let source = "let fun add[a, b] = { return a + b; }\nlet fun twice[a] inherit (add) = { return add[a, a]; }\nlet x = twice[add[1, 2]] * 2;\nlet env = { let fun neg[n] = { return -n; } }\nlet y = env.neg[x];\n";
+        // Functions are inlined into the functions that inherit them, and accessed functions are left as they are
+        assert_eq!(
+            inlined(source),
+            "let fun add[a, b] = {\n    return a + b;\n}\nlet fun twice[a] inherit (add) = {\n    return a + a;\n}\nlet x = (1 + 2 + (1 + 2)) * 2;\nlet env = {\n    let fun neg[n] = {\n        return -n;\n    }\n};\nlet y = env.neg[x];\n",
+        );
+        // Inlining does not change the computed values, only the bodies of the functions
+        let ast = parse(source);
+        for program in [inline(&ast, DEFAULT_MAX_INLINE_SIZE), ast] {
+            let env = print_ast(&Evaluator::new().evaluate(&program).unwrap().to_node());
+            assert!(env.contains("let x = 12;\n") && env.ends_with("let y = -12;\n"), "{}", env);
+        }
+    }
+
+    #[test]
+    fn leaves_other_functions() {
+        let source = "let n = 1;\nlet fun outer[a] inherit (n) = { return a + n; }\nlet fun unused[a, b] = { return a; }\nlet fun bound[a] = { let b = a; return b; }\nlet fun id[a] = { return a; }\nlet x = outer[1] + unused[1, 2] + bound[1] + id[1, 2];\n";
+        let ast = parse(source);
+        assert_eq!(print_ast(&inline(&ast, DEFAULT_MAX_INLINE_SIZE)), print_ast(&ast));
+
+        // The result of inlining is not inlined again
+        let ast = parse("let fun apply[f] = { return f[f]; }\nlet y = apply[apply];\n");
+        assert!(print_ast(&inline(&ast, DEFAULT_MAX_INLINE_SIZE)).ends_with("let y = apply[apply];\n"));
+
+        // Functions are only inlined up to the size limit, and where they are visible
+        let source = "let fun sum[a, b] = { return a + b; }\nlet x = sum[1, 2];\nlet e = { let y = sum[1, 2]; };\nlet fun sum[a] = { return a; }\nlet z = sum[3];\n";
+        assert!(print_ast(&inline(&parse(source), 2)).contains("let x = sum[1, 2];\n"));
+        let printed = inlined(source);
+        assert!(printed.contains("let x = 1 + 2;\n") && printed.contains("let y = sum[1, 2];\n") && printed.ends_with("let z = 3;\n"), "{}", printed);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::io::IoError;
+use crate::parser::AstNode;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Parses the contents of a `.env` file into an [`AstNode::Environment`]
+///
+/// Each `KEY=value` line becomes a `let` binding with a string value, in the order of the file.
+/// If a key is given more than once, the last value replaces the earlier ones in the position of the first.
+///
+/// The format follows the common conventions of `.env` files:
+/// * Empty lines and lines starting with `#` are ignored, and an optional `export ` prefix is allowed.
+/// * Unquoted values are trimmed, and end at ` #`, which starts a comment.
+/// * Single-quoted values are taken literally.
+/// * Double-quoted values may contain the escapes `\n`, `\t`, `\r`, `\"`, `\\`, and `\$`. Variables are not expanded.
+///
+/// # Errors
+/// * [`IoError::InvalidDotenv`]: A line is not a `KEY=value` pair, a key is not a valid Envlang identifier, or a quoted value is not closed.
+///
+/// # Examples
+/// ```
+/// use envlang::io::parse_dotenv;
+/// use envlang::printer::print_ast;
+///
+/// let env = parse_dotenv("# Database\nDB_HOST=localhost\nexport DB_USER='admin'\n").unwrap();
+/// assert_eq!(print_ast(&env), "let DB_HOST = \"localhost\";\nlet DB_USER = \"admin\";\n");
+/// ```
+pub fn parse_dotenv(text: &str) -> Result<AstNode, IoError> {
+    return parse_assignments(text, None, false);
+}
+
+/// Parses a shell snippet of `export KEY=value` statements into an [`AstNode::Environment`]
+///
+/// The snippet is read like a `.env` file (see [`parse_dotenv`]), except that every assignment must be exported, and statements may also be separated by `;`.
+///
+/// # Errors
+/// * [`IoError::InvalidDotenv`]: A statement is not an `export KEY=value` statement, or any of the errors of [`parse_dotenv`].
+pub fn parse_shell_exports(text: &str) -> Result<AstNode, IoError> {
+    return parse_assignments(text, None, true);
+}
+
+/// Reads and parses a `.env` file into an [`AstNode::Environment`]
+///
+/// Files are read regardless of their extension, since `.env` files are conventionally named `.env` or `.env.<stage>`.
+///
+/// # Errors
+/// * [`IoError::NotFound`], [`IoError::PermissionDenied`], [`IoError::InvalidEncoding`], and [`IoError::Other`]: The file could not be read.
+/// * Any errors from [`parse_dotenv`], which then contain the path of the file.
+pub fn read_dotenv(path: impl AsRef<Path>) -> Result<AstNode, IoError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).map_err(|err| IoError::from_io(Some(path.to_path_buf()), err))?;
+    return parse_assignments(&text, Some(path.to_path_buf()), false);
+}
+
+/// Parses `KEY=value` assignments, one per line, or also separated by `;` for shell snippets
+fn parse_assignments(text: &str, path: Option<PathBuf>, shell: bool) -> Result<AstNode, IoError> {
+    let mut bindings: Vec<Rc<AstNode>> = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let line_no = idx + 1;
+        let invalid = |message: String| IoError::InvalidDotenv(path.clone(), line_no, message);
+        let mut rest = line.trim_start();
+        while !rest.is_empty() && !rest.starts_with('#') {
+            if shell && rest.starts_with(';') {
+                rest = rest[1..].trim_start();
+                continue;
+            }
+
+            let exported = rest.strip_prefix("export ").map(str::trim_start);
+            if shell && exported.is_none() {
+                return Err(invalid(format!("Expected 'export KEY=value', found '{}'", rest)));
+            }
+            let statement = exported.unwrap_or(rest);
+            let Some((key, value)) = statement.split_once('=') else {
+                return Err(invalid(format!("Expected 'KEY=value', found '{}'", statement)));
+            };
+            let key = key.trim_end();
+            if !is_identifier(key) {
+                return Err(invalid(format!("Key '{}' is not a valid Envlang identifier", key)));
+            }
+            let (value, remainder) = parse_value(value.trim_start(), shell).map_err(invalid)?;
+            rest = remainder.trim_start();
+
+            let binding = Rc::new(AstNode::Let {
+                name: Rc::from(key),
+                value: Some(Rc::new(AstNode::String(Rc::from(value)))),
+                inherit: None,
+                span: None,
+            });
+            let existing = bindings.iter().position(|b| matches!(b.as_ref(), AstNode::Let { name, .. } if name.as_ref() == key));
+            match existing {
+                Some(pos) => bindings[pos] = binding,
+                None => bindings.push(binding),
+            }
+            if !shell {
+                break;
+            }
+        }
+    }
+    return Ok(AstNode::Environment { name: None, bindings, parent: None });
+}
+
+/// Parses a value, returning it together with the unparsed rest of the line
+fn parse_value(text: &str, shell: bool) -> Result<(String, &str), String> {
+    if let Some(quoted) = text.strip_prefix('\'') {
+        let end = quoted.find('\'').ok_or_else(|| format!("Unclosed single-quoted value '{}'", text))?;
+        return Ok((quoted[..end].to_string(), &quoted[end + 1..]));
+    }
+
+    if let Some(quoted) = text.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((idx, ch)) = chars.next() {
+            match ch {
+                '"' => return Ok((value, &quoted[idx + 1..])),
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, 'r')) => value.push('\r'),
+                    Some((_, escaped @ ('"' | '\\' | '$'))) => value.push(escaped),
+                    Some((_, other)) => {
+                        value.push('\\');
+                        value.push(other);
+                    },
+                    None => break,
+                },
+                _ => value.push(ch),
+            }
+        }
+        return Err(format!("Unclosed double-quoted value '{}'", text));
+    }
+
+    // Unquoted values end at a comment, or at the end of the statement in shell snippets
+    let mut end = text.len();
+    if let Some(comment) = text.find(" #") {
+        end = comment;
+    }
+    if shell {
+        if let Some(separator) = text[..end].find(';') {
+            end = separator;
+        }
+    }
+    return Ok((text[..end].trim_end().to_string(), &text[end..]));
+}
+
+/// Checks whether a key is a valid Envlang identifier: a letter followed by letters, digits, `-`, or `_`, which is not a keyword or boolean
+fn is_identifier(key: &str) -> bool {
+    if matches!(key, "let" | "inherit" | "fun" | "return" | "true" | "false") {
+        return false;
+    }
+    let mut chars = key.chars();
+    return chars.next().is_some_and(char::is_alphabetic)
+        && chars.all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+}
@@ -0,0 +1,144 @@
+use crate::messages::Catalog;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Error type for Envlang input/output
+///
+/// The error types distinguish the common reasons why a script could not be loaded.
+///
+/// Errors always contain the path of the file involved, if the data was read from a file.
+///
+/// Errors in `.env` files and shell snippets contain the line number and a description of the problem.
+///
+/// Module resolution errors contain the import path and the files that were tried, or the chain of imports forming a cycle.
+#[derive(Debug)]
+pub enum IoError {
+    NotFound(PathBuf),                          // (path)
+    PermissionDenied(PathBuf),                  // (path)
+    InvalidExtension(PathBuf),                  // (path)
+    InvalidEncoding(Option<PathBuf>),           // (path)
+    Other(Option<PathBuf>, std::io::Error),     // (path, error)
+    ModuleNotFound(String, Vec<PathBuf>),       // (import, candidates)
+    ImportCycle(Vec<PathBuf>),                  // (import chain)
+    InvalidDotenv(Option<PathBuf>, usize, String), // (path, line, message)
+}
+
+impl IoError {
+    /// Gets the stable error code of the error
+    /// 
+    /// IO errors use the codes `E0201` to `E0299`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            IoError::NotFound(_) => "E0201",
+            IoError::PermissionDenied(_) => "E0202",
+            IoError::InvalidExtension(_) => "E0203",
+            IoError::InvalidEncoding(_) => "E0204",
+            IoError::Other(_, _) => "E0205",
+            IoError::ModuleNotFound(_, _) => "E0206",
+            IoError::ImportCycle(_) => "E0207",
+            IoError::InvalidDotenv(_, _, _) => "E0208",
+        }
+    }
+
+    /// Gets the key of the message template of the error (see [`crate::messages`])
+    ///
+    /// This is the error code, except for errors about input that was not read from a file, which use the code followed by `.input`.
+    pub fn message_key(&self) -> &'static str {
+        match self {
+            IoError::InvalidEncoding(None) => "E0204.input",
+            IoError::Other(None, _) => "E0205.input",
+            IoError::InvalidDotenv(None, _, _) => "E0208.input",
+            _ => self.code(),
+        }
+    }
+
+    /// Gets the values filled into the message template of the error
+    pub fn arguments(&self) -> Vec<String> {
+        match self {
+            IoError::NotFound(path)
+            | IoError::PermissionDenied(path)
+            | IoError::InvalidExtension(path)
+            | IoError::InvalidEncoding(Some(path)) => vec![path.display().to_string()],
+            IoError::InvalidEncoding(None) => Vec::new(),
+            IoError::Other(Some(path), err) => vec![path.display().to_string(), err.to_string()],
+            IoError::Other(None, err) => vec![err.to_string()],
+            IoError::ModuleNotFound(import, candidates) => vec![import.clone(), {
+                candidates.iter()
+                    .map(|c| format!("'{}'", c.display()))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            }],
+            IoError::ImportCycle(chain) => vec![{
+                chain.iter()
+                    .map(|c| c.display().to_string())
+                    .collect::<Vec<String>>()
+                    .join(" -> ")
+            }],
+            IoError::InvalidDotenv(Some(path), line, message) =>
+                vec![path.display().to_string(), line.to_string(), message.clone()],
+            IoError::InvalidDotenv(None, line, message) => vec![line.to_string(), message.clone()],
+        }
+    }
+
+    /// Gets the description of the error, without the `IO error` prefix of its `Display` output
+    pub fn message(&self) -> String { Catalog::default().render(self.message_key(), &self.arguments()) }
+
+    /// Converts a [`std::io::Error`] into the matching [`IoError`] variant
+    pub fn from_io(path: Option<PathBuf>, err: std::io::Error) -> Self {
+        match (err.kind(), path) {
+            (std::io::ErrorKind::NotFound, Some(path)) => IoError::NotFound(path),
+            (std::io::ErrorKind::PermissionDenied, Some(path)) => IoError::PermissionDenied(path),
+            (std::io::ErrorKind::InvalidData, path) => IoError::InvalidEncoding(path),
+            (_, path) => IoError::Other(path, err),
+        }
+    }
+}
+
+impl Error for IoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IoError::Other(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoError::NotFound(path) =>
+                write!(f, "IO error: File '{}' does not exist", path.display()),
+            IoError::PermissionDenied(path) =>
+                write!(f, "IO error: Permission denied when reading '{}'", path.display()),
+            IoError::InvalidExtension(path) =>
+                write!(f, "IO error: File '{}' must have .envl extension", path.display()),
+            IoError::InvalidEncoding(Some(path)) =>
+                write!(f, "IO error: File '{}' is not valid UTF-8", path.display()),
+            IoError::InvalidEncoding(None) =>
+                write!(f, "IO error: Input is not valid UTF-8"),
+            IoError::Other(Some(path), err) =>
+                write!(f, "IO error: Could not read '{}': {}", path.display(), err),
+            IoError::Other(None, err) =>
+                write!(f, "IO error: Could not read input: {}", err),
+            IoError::ModuleNotFound(import, candidates) =>
+                write!(f, "IO error: Module '{}' not found, tried: {}", import, {
+                    candidates.iter()
+                        .map(|c| format!("'{}'", c.display()))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                }),
+            IoError::ImportCycle(chain) =>
+                write!(f, "IO error: Import cycle detected: {}", {
+                    chain.iter()
+                        .map(|c| c.display().to_string())
+                        .collect::<Vec<String>>()
+                        .join(" -> ")
+                }),
+            IoError::InvalidDotenv(Some(path), line, message) =>
+                write!(f, "IO error: Invalid assignment on line {} of '{}': {}", line, path.display(), message),
+            IoError::InvalidDotenv(None, line, message) =>
+                write!(f, "IO error: Invalid assignment on line {}: {}", line, message),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::path::Path;
+
+/// The file extension of Envlang scripts
+pub const ENVL_EXTENSION: &str = "envl";
+
+/// Policy for the file extensions accepted when reading scripts
+///
+/// The default policy only accepts the `.envl` extension.
+///
+/// Extensions are given without a leading dot, and are compared case-sensitively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtensionPolicy {
+    /// Only files with one of the listed extensions are accepted
+    Allow(Vec<String>),
+    /// Files are accepted regardless of their extension (or lack thereof)
+    Any,
+}
+
+impl Default for ExtensionPolicy {
+    fn default() -> Self {
+        return ExtensionPolicy::Allow(vec![ENVL_EXTENSION.to_string()]);
+    }
+}
+
+impl ExtensionPolicy {
+    /// Checks whether the policy accepts the extension of a path
+    pub fn permits(&self, path: &Path) -> bool {
+        match self {
+            ExtensionPolicy::Allow(extensions) => {
+                return path.extension()
+                    .is_some_and(|ext| extensions.iter().any(|allowed| ext == allowed.as_str()));
+            },
+            ExtensionPolicy::Any => return true,
+        }
+    }
+
+    /// Adds an extension to the allow-list
+    ///
+    /// A leading dot is stripped from the extension. The policy [`ExtensionPolicy::Any`] is left unchanged, as it already accepts every extension.
+    pub fn allow(&mut self, extension: &str) {
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        if let ExtensionPolicy::Allow(extensions) = self {
+            if !extensions.iter().any(|allowed| allowed == extension) {
+                extensions.push(extension.to_string());
+            }
+        }
+    }
+}
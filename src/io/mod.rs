@@ -0,0 +1,129 @@
+//! Envlang internal input/output functions
+//! 
+//! These functions are used by the Envlang interpreter to read and write `.envl` files
+//! 
+//! Scripts can be read either from a path with [`read_file`], or from any [`Read`] implementor with [`read_from`].
+//! 
+//! By default only files with the `.envl` extension are read. Other extensions can be accepted with an [`ExtensionPolicy`] and [`read_file_with`].
+//! 
+//! Generated scripts are written with [`write_file`], which renders an [`AstNode`] through the [`printer`](crate::printer).
+//! 
+//! Source text that is to be lexed, parsed, or reported on is held in a [`SourceFile`], which maps between byte offsets, lexer positions, and line and column numbers.
+//! 
+//! Environment variables from `.env` files and `export KEY=value` shell snippets are imported as environments with [`read_dotenv`], [`parse_dotenv`], and [`parse_shell_exports`].
+//! 
+//! Multi-file programs locate and load their imported modules through a [`ModuleResolver`].
+//! 
+//! # Error handling
+//! 
+//! All functions return an [`IoError`], which distinguishes missing files, missing permissions, wrong file extensions, and invalid encodings.
+
+mod dotenv;
+mod error;
+mod extension;
+mod resolver;
+mod source;
+mod tests;
+
+pub use dotenv::{parse_dotenv, parse_shell_exports, read_dotenv};
+pub use error::IoError;
+pub use extension::{ExtensionPolicy, ENVL_EXTENSION};
+pub use resolver::{HostModules, ModuleResolver, HOST_MODULE_PREFIX};
+pub use source::SourceFile;
+
+use crate::parser::AstNode;
+use crate::printer::print_ast;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Read an `.envl` file
+/// 
+/// Returns a `Result<String, IoError>`
+/// 
+/// This is equivalent to [`read_file_with`] using the default [`ExtensionPolicy`].
+/// 
+/// # Errors
+/// * [`IoError::InvalidExtension`]: The file does not end in the `.envl` file extension.
+/// * [`IoError::NotFound`]: The file does not exist.
+/// * [`IoError::PermissionDenied`]: The file could not be opened due to missing permissions.
+/// * [`IoError::InvalidEncoding`]: The file is not valid UTF-8.
+/// * [`IoError::Other`]: Any other `std::io::Error`.
+pub fn read_file(path: impl AsRef<Path>) -> Result<String, IoError> {
+    return read_file_with(path, &ExtensionPolicy::default());
+}
+
+/// Read a script file whose extension is accepted by an [`ExtensionPolicy`]
+/// 
+/// Returns a `Result<String, IoError>`
+/// 
+/// # Errors
+/// * [`IoError::InvalidExtension`]: The policy does not accept the extension of the file.
+/// * [`IoError::NotFound`]: The file does not exist.
+/// * [`IoError::PermissionDenied`]: The file could not be opened due to missing permissions.
+/// * [`IoError::InvalidEncoding`]: The file is not valid UTF-8.
+/// * [`IoError::Other`]: Any other `std::io::Error`.
+pub fn read_file_with(path: impl AsRef<Path>, policy: &ExtensionPolicy) -> Result<String, IoError> {
+    let path = path.as_ref();
+    if !policy.permits(path) {
+        return Err(IoError::InvalidExtension(path.to_path_buf()));
+    }
+    let file = File::open(path).map_err(|err| IoError::from_io(Some(path.to_path_buf()), err))?;
+    return read_from(file).map_err(|err| match err {
+        IoError::InvalidEncoding(None) => IoError::InvalidEncoding(Some(path.to_path_buf())),
+        IoError::Other(None, err) => IoError::from_io(Some(path.to_path_buf()), err),
+        err => err,
+    });
+}
+
+/// Read Envlang source code from a reader
+/// 
+/// Unlike [`read_file`], this does not check for a file extension, which allows reading from standard input, network streams, or in-memory buffers.
+/// 
+/// # Errors
+/// * [`IoError::InvalidEncoding`]: The input is not valid UTF-8.
+/// * [`IoError::Other`]: Any other `std::io::Error` raised by the reader.
+pub fn read_from(mut reader: impl Read) -> Result<String, IoError> {
+    let mut source = String::new();
+    reader.read_to_string(&mut source).map_err(|err| IoError::from_io(None, err))?;
+    return Ok(source);
+}
+
+/// Write an [`AstNode`] into an `.envl` file
+/// 
+/// The node is rendered with [`print_ast`](crate::printer::print_ast).
+/// 
+/// The write is atomic: the source is first written into a temporary file in the same directory, which then replaces the target file.
+/// Readers will therefore see either the old or the new file, but never a partially written one.
+/// 
+/// # Errors
+/// * [`IoError::InvalidExtension`]: The file does not end in the `.envl` file extension.
+/// * [`IoError::NotFound`]: The directory of the file does not exist.
+/// * [`IoError::PermissionDenied`]: The file or its temporary file could not be written due to missing permissions.
+/// * [`IoError::Other`]: Any other `std::io::Error`.
+pub fn write_file(path: impl AsRef<Path>, node: &AstNode) -> Result<(), IoError> {
+    let path = path.as_ref();
+    if !ExtensionPolicy::default().permits(path) {
+        return Err(IoError::InvalidExtension(path.to_path_buf()));
+    }
+    let Some(file_name) = path.file_name() else {
+        return Err(IoError::InvalidExtension(path.to_path_buf()));
+    };
+
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(print_ast(node).as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp_path, path));
+    if let Err(err) = result {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(IoError::from_io(Some(path.to_path_buf()), err));
+    }
+    return Ok(());
+}
@@ -0,0 +1,178 @@
+use crate::io::{IoError, SourceFile};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// The prefix of the paths given to modules provided by the host
+pub const HOST_MODULE_PREFIX: &str = "host:";
+
+/// A callback providing the source text of virtual modules, given their import path
+///
+/// The callback returns `None` for imports that the host does not provide, which are then looked up on the filesystem.
+pub type HostModules = Box<dyn Fn(&str) -> Option<String>>;
+
+/// Locates and loads `.envl` modules for imports
+/// 
+/// The resolver holds an ordered list of search roots, a cache of already loaded sources sorted by path, and the stack of modules currently being loaded.
+/// 
+/// # Import paths
+/// 
+/// Import paths use `/` as a separator, and the `.envl` extension may be omitted:
+/// * Paths starting with `./` or `../` are resolved relative to the directory of the importing module only.
+/// * Absolute paths are used as-is.
+/// * All other paths are looked up in each search root in turn, and the first existing file is used.
+/// 
+/// Resolved paths are canonicalized, so the same file imported through different paths is only loaded once.
+/// 
+/// # Cycle detection
+/// 
+/// [`load`](ModuleResolver::load) marks a module as being loaded until [`finish`](ModuleResolver::finish) is called for it.
+/// Loading a module that is still being loaded means that the modules import each other, which is reported as [`IoError::ImportCycle`].
+/// 
+/// # Host modules
+/// 
+/// Embedders can provide virtual modules with [`with_host_modules`](ModuleResolver::with_host_modules).
+/// The callback is consulted before the filesystem, and the modules it provides get the path `host:<import>` (see [`HOST_MODULE_PREFIX`]).
+#[derive(Default)]
+pub struct ModuleResolver {
+    roots: Vec<PathBuf>,
+    cache: BTreeMap<PathBuf, Rc<SourceFile>>,
+    loading: Vec<PathBuf>,
+    host: Option<HostModules>,
+}
+
+impl fmt::Debug for ModuleResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModuleResolver")
+            .field("roots", &self.roots)
+            .field("cache", &self.cache)
+            .field("loading", &self.loading)
+            .field("host", &self.host.as_ref().map(|_| "<callback>"))
+            .finish()
+    }
+}
+
+impl ModuleResolver {
+    /// Initializes a new resolver with the given search roots, in order of priority
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self {
+            roots,
+            cache: BTreeMap::new(),
+            loading: Vec::new(),
+            host: None,
+        }
+    }
+
+    /// Sets the callback providing virtual modules, replacing any previous callback
+    /// 
+    /// # Examples
+    /// ```
+    /// use envlang::io::ModuleResolver;
+    /// use std::path::Path;
+    ///
+    /// let mut resolver = ModuleResolver::new(Vec::new()).with_host_modules(|import| match import {
+    ///     "app/version" => Some("let version = 3;\n".to_string()),
+    ///     _ => None,
+    /// });
+    /// let (path, source) = resolver.load("app/version", None).unwrap();
+    /// assert_eq!(path, Path::new("host:app/version"));
+    /// assert_eq!(source.text(), "let version = 3;\n");
+    /// ```
+    pub fn with_host_modules(mut self, host: impl Fn(&str) -> Option<String> + 'static) -> Self {
+        self.host = Some(Box::new(host));
+        return self;
+    }
+
+    /// Gets the search roots of the resolver
+    pub fn roots(&self) -> &[PathBuf] { &self.roots }
+
+    /// Locates the file for an import path and returns its canonical path
+    /// 
+    /// Only the filesystem is searched; host modules are provided by [`load`](ModuleResolver::load).
+    /// 
+    /// # Arguments
+    /// * `import`: The import path as written in the importing module.
+    /// * `importer`: The path of the importing module, or `None` if the import does not come from a file (e.g. the CLI entry point).
+    /// 
+    /// # Errors
+    /// * [`IoError::ModuleNotFound`]: No candidate file exists. The error lists every candidate that was tried.
+    /// * [`IoError::InvalidExtension`]: The import names a file with another extension than `.envl`.
+    /// * Any errors from canonicalizing the path, converted with [`IoError::from_io`].
+    pub fn resolve(&self, import: &str, importer: Option<&Path>) -> Result<PathBuf, IoError> {
+        let relative = Path::new(import);
+        let relative: PathBuf = match relative.extension() {
+            None => relative.with_extension("envl"),
+            Some(ext) if ext == "envl" => relative.to_path_buf(),
+            Some(_) => return Err(IoError::InvalidExtension(relative.to_path_buf())),
+        };
+
+        let candidates: Vec<PathBuf> = if relative.is_absolute() {
+            vec![relative]
+        } else if import.starts_with("./") || import.starts_with("../") {
+            let base = importer.and_then(Path::parent).unwrap_or(Path::new(""));
+            vec![base.join(relative)]
+        } else {
+            self.roots.iter().map(|root| root.join(&relative)).collect()
+        };
+
+        match candidates.iter().find(|candidate| candidate.is_file()) {
+            Some(found) => return std::fs::canonicalize(found).map_err(|err| IoError::from_io(Some(found.clone()), err)),
+            None => return Err(IoError::ModuleNotFound(import.to_string(), candidates)),
+        }
+    }
+
+    /// Resolves an import, reads the module source, and marks the module as being loaded
+    /// 
+    /// Host modules are requested from the host callback first, if one is set. Other imports are resolved on the filesystem.
+    /// 
+    /// Sources are cached by canonical path, so each file is read from disk (or requested from the host) at most once.
+    /// 
+    /// Returns the canonical path together with the source. The caller must call [`finish`](ModuleResolver::finish) with the returned path once the module has been fully processed.
+    /// 
+    /// # Errors
+    /// * Any errors bubbled up from [`resolve`](ModuleResolver::resolve) or [`SourceFile::from_path`].
+    /// * [`IoError::ImportCycle`]: The module is already being loaded. The error contains the import chain, starting and ending with the repeated module.
+    pub fn load(&mut self, import: &str, importer: Option<&Path>) -> Result<(PathBuf, Rc<SourceFile>), IoError> {
+        let host_path = PathBuf::from(format!("{}{}", HOST_MODULE_PREFIX, import));
+        let path = if self.cache.contains_key(&host_path) {
+            host_path
+        } else if let Some(text) = self.host.as_ref().and_then(|host| host(import)) {
+            let source = SourceFile::new(host_path.display().to_string(), text);
+            self.cache.insert(host_path.clone(), Rc::new(source));
+            host_path
+        } else {
+            self.resolve(import, importer)?
+        };
+
+        if let Some(start) = self.loading.iter().position(|loading| *loading == path) {
+            let mut chain: Vec<PathBuf> = self.loading[start..].to_vec();
+            chain.push(path);
+            return Err(IoError::ImportCycle(chain));
+        }
+
+        let source = match self.cache.get(&path) {
+            Some(source) => Rc::clone(source),
+            None => {
+                let source = Rc::new(SourceFile::from_path(&path)?);
+                self.cache.insert(path.clone(), Rc::clone(&source));
+                source
+            },
+        };
+
+        self.loading.push(path.clone());
+        return Ok((path, source));
+    }
+
+    /// Marks a module as fully loaded
+    /// 
+    /// Modules may finish in any order, although imports normally finish in the reverse order of loading.
+    pub fn finish(&mut self, path: &Path) {
+        if let Some(idx) = self.loading.iter().rposition(|loading| loading == path) {
+            self.loading.remove(idx);
+        }
+    }
+
+    /// Checks whether a module with the given canonical path has been read
+    pub fn is_cached(&self, path: &Path) -> bool { self.cache.contains_key(path) }
+}
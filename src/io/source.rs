@@ -0,0 +1,147 @@
+use crate::io::{read_file, IoError};
+use crate::span::{is_line_break, Position};
+use std::path::Path;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// An Envlang source file held in memory
+/// 
+/// The `SourceFile` owns the source text and indexes the starting byte offsets of every line and every grapheme, so that positions can be converted between:
+/// * Byte offsets into the text.
+/// * Grapheme indices, as used by the [`Lexer`](crate::lexer::Lexer).
+/// * One-indexed line and column numbers, as shown to users. Columns are counted in graphemes.
+/// 
+/// Lines are terminated by `\n`, which also covers `\r\n` line endings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    name: String,
+    text: String,
+    line_starts: Vec<usize>,
+    grapheme_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    /// Creates a new source file from a display name and the source text
+    /// 
+    /// The name is only used for reporting and does not have to be a valid path.
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        let text: String = text.into();
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(text.grapheme_indices(true)
+                .filter(|(_, grapheme)| is_line_break(grapheme))
+                .map(|(idx, grapheme)| idx + grapheme.len()))
+            .collect();
+        let grapheme_starts: Vec<usize> = text.grapheme_indices(true).map(|(idx, _)| idx).collect();
+        Self {
+            name: name.into(),
+            text,
+            line_starts,
+            grapheme_starts,
+        }
+    }
+
+    /// Reads a source file from disk
+    /// 
+    /// # Errors
+    /// * Any errors bubbled up from [`read_file`].
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, IoError> {
+        let path = path.as_ref();
+        let text = read_file(path)?;
+        return Ok(Self::new(path.display().to_string(), text));
+    }
+
+    /// Gets the display name of the source file
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Gets the full source text
+    pub fn text(&self) -> &str { &self.text }
+
+    /// Gets the number of lines in the source file
+    /// 
+    /// An empty file, as well as a file ending in a line break, counts the (empty) final line.
+    pub fn line_count(&self) -> usize { self.line_starts.len() }
+
+    /// Gets the number of graphemes in the source file
+    pub fn grapheme_count(&self) -> usize { self.grapheme_starts.len() }
+
+    /// Segments the source text into graphemes for the [`Lexer`](crate::lexer::Lexer)
+    pub fn graphemes(&self) -> Vec<String> {
+        self.text.graphemes(true).map(String::from).collect()
+    }
+
+    /// Gets the text of a one-indexed line, excluding its line terminator
+    /// 
+    /// Returns `None` if the line does not exist.
+    pub fn line_text(&self, line: usize) -> Option<&str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self.line_starts.get(line).copied().unwrap_or(self.text.len());
+        let text = &self.text[start..end];
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        return Some(text.strip_suffix('\r').unwrap_or(text));
+    }
+
+    /// Converts a byte offset into a one-indexed `(line, column)` pair
+    /// 
+    /// The offset may point one past the final byte, which is the position of the end of the file.
+    /// 
+    /// Returns `None` if the offset is beyond the end of the file or does not fall on a grapheme boundary.
+    pub fn line_col(&self, offset: usize) -> Option<(usize, usize)> {
+        if offset > self.text.len() || (offset < self.text.len() && !self.is_grapheme_boundary(offset)) {
+            return None;
+        }
+        // The first line always starts at zero, so the partition point is at least one
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let column = self.text[line_start..offset].graphemes(true).count() + 1;
+        return Some((line, column));
+    }
+
+    /// Converts a byte offset into a [`Position`]
+    /// 
+    /// Returns `None` in the same cases as [`line_col`](SourceFile::line_col).
+    pub fn position(&self, offset: usize) -> Option<Position> {
+        let (line, column) = self.line_col(offset)?;
+        return Some(Position::new(offset, line, column));
+    }
+
+    /// Converts a one-indexed `(line, column)` pair into a byte offset
+    /// 
+    /// The column may point one past the final grapheme of the line.
+    /// 
+    /// Returns `None` if the line does not exist or the column is beyond the end of the line.
+    pub fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        let line_text = self.line_text(line)?;
+        let line_start = self.line_starts[line - 1];
+        let column_idx = column.checked_sub(1)?;
+        let mut graphemes = line_text.grapheme_indices(true).map(|(idx, _)| idx);
+        match graphemes.nth(column_idx) {
+            Some(idx) => return Some(line_start + idx),
+            None if column_idx == line_text.graphemes(true).count() => return Some(line_start + line_text.len()),
+            None => return None,
+        }
+    }
+
+    /// Converts a grapheme index (e.g. a lexer position) into a byte offset
+    /// 
+    /// The index may point one past the final grapheme, which is the position of the end of the file.
+    /// 
+    /// Returns `None` if the index is beyond the end of the file.
+    pub fn grapheme_offset(&self, index: usize) -> Option<usize> {
+        match self.grapheme_starts.get(index) {
+            Some(offset) => return Some(*offset),
+            None if index == self.grapheme_starts.len() => return Some(self.text.len()),
+            None => return None,
+        }
+    }
+
+    /// Converts a grapheme index (e.g. a lexer position) into a one-indexed `(line, column)` pair
+    /// 
+    /// Returns `None` if the index is beyond the end of the file.
+    pub fn grapheme_line_col(&self, index: usize) -> Option<(usize, usize)> {
+        self.line_col(self.grapheme_offset(index)?)
+    }
+
+    /// Checks whether a byte offset is the start of a grapheme
+    fn is_grapheme_boundary(&self, offset: usize) -> bool {
+        self.grapheme_starts.binary_search(&offset).is_ok()
+    }
+}
@@ -0,0 +1,339 @@
+#[cfg(test)]
+mod tests {
+    use crate::io::{parse_dotenv, parse_shell_exports, read_dotenv, read_file, read_file_with, read_from, write_file, ExtensionPolicy, IoError, ModuleResolver, SourceFile};
+    use crate::parser::AstNode;
+    use crate::span::Position;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+
+    #[test]
+    fn invalid_file_extension() {
+        let result = read_file("tests/data/io_invalidextension.txt");
+        assert!(matches!(result, Err(IoError::InvalidExtension(path)) if path == Path::new("tests/data/io_invalidextension.txt")));
+    }
+
+    #[test]
+    fn missing_file_extension() {
+        let result = read_file("tests/data/envl");
+        assert!(matches!(result, Err(IoError::InvalidExtension(_))));
+    }
+
+    #[test]
+    fn allowed_extension_is_read() {
+        let mut policy = ExtensionPolicy::default();
+        policy.allow(".txt");
+        assert!(read_file_with("tests/data/io_invalidextension.txt", &policy).is_ok());
+        assert!(read_file_with("tests/data/io_validextension.envl", &policy).is_ok());
+        assert!(matches!(read_file_with("tests/data/envl", &policy), Err(IoError::InvalidExtension(_))));
+    }
+
+    #[test]
+    fn extension_check_can_be_disabled() {
+        let policy = ExtensionPolicy::Any;
+        assert!(read_file_with("tests/data/io_invalidextension.txt", &policy).is_ok());
+        assert!(matches!(read_file_with("tests/data/envl", &policy), Err(IoError::NotFound(_))));
+    }
+
+    #[test]
+    fn file_does_not_exist() {
+        let result = read_file("tests/data/doesnotexist.envl");
+        assert!(matches!(result, Err(IoError::NotFound(path)) if path == Path::new("tests/data/doesnotexist.envl")));
+    }
+
+    #[test]
+    fn file_is_read() {
+        let result = read_file("tests/data/io_validextension.envl").unwrap();
+        assert_eq!(result, "here is some text\nwith\nwhite\nspace".to_string())
+    }
+
+    #[test]
+    fn file_is_read_from_path() {
+        let path: PathBuf = PathBuf::from("tests/data").join("io_validextension.envl");
+        let result = read_file(&path).unwrap();
+        assert_eq!(result, "here is some text\nwith\nwhite\nspace".to_string())
+    }
+
+    #[test]
+    fn reader_is_read() {
+        let result = read_from("let x = 5;".as_bytes()).unwrap();
+        assert_eq!(result, "let x = 5;".to_string());
+    }
+
+    #[test]
+    fn reader_with_invalid_utf8() {
+        let bytes: &[u8] = &[0x6c, 0x65, 0x74, 0xff];
+        let result = read_from(bytes);
+        assert!(matches!(result, Err(IoError::InvalidEncoding(None))));
+    }
+
+    #[test]
+    fn source_file_line_index() {
+        let source = SourceFile::new("test.envl", "let x = 5;\nlet y = x;\n");
+        assert_eq!(source.line_count(), 3);
+        assert_eq!(source.line_text(1), Some("let x = 5;"));
+        assert_eq!(source.line_text(2), Some("let y = x;"));
+        assert_eq!(source.line_text(3), Some(""));
+        assert_eq!(source.line_text(0), None);
+        assert_eq!(source.line_text(4), None);
+    }
+
+    #[test]
+    fn source_file_offset_to_line_col() {
+        let source = SourceFile::new("test.envl", "let x = 5;\r\nlet y = x;");
+        assert_eq!(source.line_col(0), Some((1, 1)));
+        assert_eq!(source.line_col(4), Some((1, 5)));
+        assert_eq!(source.line_col(12), Some((2, 1)));
+        assert_eq!(source.line_col(22), Some((2, 11)));
+        assert_eq!(source.line_col(23), None);
+        assert_eq!(source.position(12), Some(Position::new(12, 2, 1)));
+        assert_eq!(source.line_text(1), Some("let x = 5;"));
+    }
+
+    #[test]
+    fn source_file_counts_all_line_breaks() {
+        let source = SourceFile::new("test.envl", "let x = \"a\rb\";\r\nlet y = x;\n");
+        assert_eq!(source.line_count(), 4);
+        assert_eq!(source.line_text(2), Some("b\";"));
+        assert_eq!(source.line_col(16), Some((3, 1)));
+    }
+
+    #[test]
+    fn source_file_line_col_to_offset() {
+        let source = SourceFile::new("test.envl", "let x = 5;\nlet y = x;");
+        assert_eq!(source.offset(1, 1), Some(0));
+        assert_eq!(source.offset(2, 5), Some(15));
+        assert_eq!(source.offset(2, 11), Some(21));
+        assert_eq!(source.offset(2, 12), None);
+        assert_eq!(source.offset(3, 1), None);
+        assert_eq!(source.offset(1, 0), None);
+    }
+
+    #[test]
+    fn source_file_columns_count_graphemes() {
+        let source = SourceFile::new("test.envl", "let 😺 = \"e\u{301}\";");
+        let cat = source.text().find('😺').unwrap();
+        assert_eq!(source.line_col(cat), Some((1, 5)));
+        assert_eq!(source.line_col(cat + 1), None);
+        assert_eq!(source.offset(1, 6), Some(cat + '😺'.len_utf8()));
+        assert_eq!(source.line_col(source.text().len()), Some((1, 13)));
+    }
+
+    #[test]
+    fn source_file_grapheme_positions() {
+        let source = SourceFile::new("test.envl", "a\r\nbc");
+        assert_eq!(source.grapheme_count(), 4);
+        assert_eq!(source.graphemes(), vec!["a", "\r\n", "b", "c"]);
+        assert_eq!(source.grapheme_offset(2), Some(3));
+        assert_eq!(source.grapheme_offset(4), Some(5));
+        assert_eq!(source.grapheme_offset(5), None);
+        assert_eq!(source.grapheme_line_col(3), Some((2, 2)));
+    }
+
+    #[test]
+    fn source_file_from_path() {
+        let source = SourceFile::from_path("tests/data/io_validextension.envl").unwrap();
+        assert_eq!(source.name(), "tests/data/io_validextension.envl");
+        assert_eq!(source.line_count(), 4);
+        assert_eq!(source.line_text(4), Some("space"));
+    }
+
+    fn module_resolver() -> ModuleResolver {
+        ModuleResolver::new(vec![
+            PathBuf::from("tests/data/modules/lib"),
+            PathBuf::from("tests/data/modules/vendor"),
+        ])
+    }
+
+    #[test]
+    fn resolver_searches_roots_in_order() {
+        let resolver = module_resolver();
+        let util = resolver.resolve("util", None).unwrap();
+        assert_eq!(util, std::fs::canonicalize("tests/data/modules/lib/util.envl").unwrap());
+        let extra = resolver.resolve("extra.envl", None).unwrap();
+        assert_eq!(extra, std::fs::canonicalize("tests/data/modules/vendor/extra.envl").unwrap());
+        let http = resolver.resolve("net/http", None).unwrap();
+        assert_eq!(http, std::fs::canonicalize("tests/data/modules/lib/net/http.envl").unwrap());
+    }
+
+    #[test]
+    fn resolver_resolves_relative_imports() {
+        let resolver = module_resolver();
+        let importer = Path::new("tests/data/modules/lib/net/http.envl");
+        let util = resolver.resolve("../util", Some(importer)).unwrap();
+        assert_eq!(util, std::fs::canonicalize("tests/data/modules/lib/util.envl").unwrap());
+        // Relative imports do not fall back to the search roots
+        let result = resolver.resolve("./extra", Some(importer));
+        assert!(matches!(result, Err(IoError::ModuleNotFound(import, candidates)) if import == "./extra" && candidates.len() == 1));
+    }
+
+    #[test]
+    fn resolver_reports_missing_module() {
+        let resolver = module_resolver();
+        let result = resolver.resolve("missing", None);
+        assert!(matches!(result, Err(IoError::ModuleNotFound(import, candidates)) if import == "missing" && candidates.len() == 2));
+    }
+
+    #[test]
+    fn resolver_rejects_other_extensions() {
+        let resolver = module_resolver();
+        let result = resolver.resolve("util.txt", None);
+        assert!(matches!(result, Err(IoError::InvalidExtension(_))));
+    }
+
+    #[test]
+    fn resolver_caches_sources() {
+        let mut resolver = module_resolver();
+        let (path, first) = resolver.load("util", None).unwrap();
+        resolver.finish(&path);
+        assert!(resolver.is_cached(&path));
+
+        let importer = Path::new("tests/data/modules/lib/net/http.envl");
+        let (same_path, second) = resolver.load("../util", Some(importer)).unwrap();
+        resolver.finish(&same_path);
+        assert_eq!(path, same_path);
+        assert!(Rc::ptr_eq(&first, &second));
+        assert_eq!(first.text(), "let util = 2;\n");
+    }
+
+    #[test]
+    fn resolver_detects_import_cycles() {
+        let mut resolver = module_resolver();
+        let (util, _) = resolver.load("util", None).unwrap();
+        let (http, _) = resolver.load("net/http", Some(&util)).unwrap();
+        let result = resolver.load("../util", Some(&http));
+        assert!(matches!(result, Err(IoError::ImportCycle(chain)) if chain == vec![util.clone(), http.clone(), util.clone()]));
+
+        // Once the modules have finished loading, importing them again is fine
+        resolver.finish(&http);
+        resolver.finish(&util);
+        assert!(resolver.load("util", None).is_ok());
+    }
+
+    #[test]
+    fn resolver_loads_host_modules() {
+        let calls = Rc::new(std::cell::Cell::new(0));
+        let counter = Rc::clone(&calls);
+        let mut resolver = module_resolver().with_host_modules(move |import| {
+            counter.set(counter.get() + 1);
+            match import {
+                "util" | "virtual" => Some(format!("let {} = 1;\n", import)),
+                _ => None,
+            }
+        });
+
+        // Host modules take precedence over files
+        let (path, source) = resolver.load("util", None).unwrap();
+        resolver.finish(&path);
+        assert_eq!(path, PathBuf::from("host:util"));
+        assert_eq!(source.text(), "let util = 1;\n");
+        assert_eq!(source.name(), "host:util");
+
+        // Cached host modules are not requested again
+        let (path, _) = resolver.load("util", None).unwrap();
+        assert_eq!(calls.get(), 1);
+
+        // Host modules take part in cycle detection
+        assert!(matches!(resolver.load("util", Some(&path)), Err(IoError::ImportCycle(_))));
+        resolver.finish(&path);
+
+        // Imports the host does not provide fall back to the filesystem
+        let (http, _) = resolver.load("net/http", None).unwrap();
+        assert_eq!(http, std::fs::canonicalize("tests/data/modules/lib/net/http.envl").unwrap());
+    }
+
+    fn written_env() -> AstNode {
+        return AstNode::Environment {
+            name: None,
+            bindings: vec![Rc::new(AstNode::Let {
+                name: "x".into(),
+                value: Some(Rc::new(AstNode::Integer(5))),
+                inherit: None,
+                span: None,
+            })],
+            parent: None,
+        };
+    }
+
+    #[test]
+    fn file_is_written() {
+        let dir = std::env::temp_dir().join(format!("envlang_io_write_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("written.envl");
+        std::fs::write(&path, "let old = 1;\n").unwrap();
+
+        write_file(&path, &written_env()).unwrap();
+        assert_eq!(read_file(&path).unwrap(), "let x = 5;\n");
+        // The temporary file has replaced the target
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_rejects_invalid_extension() {
+        let result = write_file("tests/data/written.txt", &written_env());
+        assert!(matches!(result, Err(IoError::InvalidExtension(_))));
+        assert!(!Path::new("tests/data/written.txt").exists());
+    }
+
+    #[test]
+    fn write_into_missing_directory() {
+        let result = write_file("tests/data/doesnotexist/written.envl", &written_env());
+        assert!(matches!(result, Err(IoError::NotFound(path)) if path == Path::new("tests/data/doesnotexist/written.envl")));
+    }
+
+    fn string_bindings(env: &AstNode) -> Vec<(String, String)> {
+        let AstNode::Environment { bindings, .. } = env else { panic!("Not an environment: {:?}", env) };
+        return bindings.iter().map(|binding| match binding.as_ref() {
+            AstNode::Let { name, value: Some(value), inherit: None, .. } => match value.as_ref() {
+                AstNode::String(s) => (name.to_string(), s.to_string()),
+                other => panic!("Not a string value: {:?}", other),
+            },
+            other => panic!("Not a binding: {:?}", other),
+        }).collect();
+    }
+
+    #[test]
+    fn dotenv_file_is_read() {
+        let env = read_dotenv("tests/data/io_dotenv.env").unwrap();
+        assert_eq!(string_bindings(&env), vec![
+            ("DB_HOST".to_string(), "db.internal".to_string()),
+            ("DB_PORT".to_string(), "5432".to_string()),
+            ("DB_USER".to_string(), "admin".to_string()),
+            ("DB_PASSWORD".to_string(), "p#ss \"word\"\n".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn dotenv_quoting() {
+        let env = parse_dotenv("A='$HOME \\n'\nB=\"$HOME\\t\\$\"\nC=a#b\n").unwrap();
+        assert_eq!(string_bindings(&env), vec![
+            ("A".to_string(), "$HOME \\n".to_string()),
+            ("B".to_string(), "$HOME\t$".to_string()),
+            ("C".to_string(), "a#b".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn invalid_dotenv_lines() {
+        assert!(matches!(parse_dotenv("A=1\nnot an assignment\n"), Err(IoError::InvalidDotenv(None, 2, _))));
+        assert!(matches!(parse_dotenv("1A=1\n"), Err(IoError::InvalidDotenv(None, 1, _))));
+        assert!(matches!(parse_dotenv("let=1\n"), Err(IoError::InvalidDotenv(None, 1, _))));
+        assert!(matches!(parse_dotenv("A=\"open\n"), Err(IoError::InvalidDotenv(None, 1, _))));
+        assert!(matches!(read_dotenv("tests/data/doesnotexist.env"), Err(IoError::NotFound(_))));
+
+        let err = parse_dotenv("A B=1\n").unwrap_err();
+        assert_eq!(err.code(), "E0208");
+        assert_eq!(err.message(), "Invalid assignment on line 1: Key 'A B' is not a valid Envlang identifier");
+    }
+
+    #[test]
+    fn shell_exports() {
+        let env = parse_shell_exports("export A=1; export B='two words'\n\n# comment\nexport C=\"3\";\n").unwrap();
+        assert_eq!(string_bindings(&env), vec![
+            ("A".to_string(), "1".to_string()),
+            ("B".to_string(), "two words".to_string()),
+            ("C".to_string(), "3".to_string()),
+        ]);
+        assert!(matches!(parse_shell_exports("export A=1\nB=2\n"), Err(IoError::InvalidDotenv(None, 2, _))));
+    }
+}
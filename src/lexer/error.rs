@@ -1,5 +1,8 @@
-use std::error::Error;
-use std::fmt;
+use crate::messages::Catalog;
+use core::error::Error;
+use core::fmt;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
 
 /// Error type for Envlang lexer
 /// 
@@ -23,10 +26,80 @@ pub enum LexerError {
     IndexOutOfBounds(usize, usize, usize),  // (pos, idx, input_len)
     UnrecognizedInput(usize, String),       // (pos, input)
     InvalidOperator(usize, String),         // (pos, input)
+    StringTooLong(usize, usize),            // (pos, limit)
+    NumberTooLong(usize, usize),            // (pos, limit)
+    InvalidEscapeSequence(usize, String),   // (pos, sequence)
+    GluedIdentifier(usize, String),         // (pos, identifier)
 }
 
 impl Error for LexerError {}
 
+impl LexerError {
+    /// Gets the stable error code of the error
+    /// 
+    /// Lexer errors use the codes `E0001` to `E0099`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexerError::InvalidToken(_, _) => "E0001",
+            LexerError::UnterminatedString(_, _) => "E0002",
+            LexerError::EmptyIdentifier(_) => "E0003",
+            LexerError::BrokenLexer(_, _) => "E0004",
+            LexerError::InvertedSlice(_, _) => "E0005",
+            LexerError::SliceOutOfBounds(_, _, _) => "E0006",
+            LexerError::IndexOutOfBounds(_, _, _) => "E0007",
+            LexerError::UnrecognizedInput(_, _) => "E0008",
+            LexerError::InvalidOperator(_, _) => "E0009",
+            LexerError::StringTooLong(_, _) => "E0010",
+            LexerError::NumberTooLong(_, _) => "E0011",
+            LexerError::InvalidEscapeSequence(_, _) => "E0012",
+            LexerError::GluedIdentifier(_, _) => "E0013",
+        }
+    }
+
+    /// Gets the values filled into the message template of the error (see [`crate::messages`])
+    pub fn arguments(&self) -> Vec<String> {
+        match self {
+            LexerError::InvalidToken(_, value)
+            | LexerError::UnterminatedString(_, value)
+            | LexerError::UnrecognizedInput(_, value)
+            | LexerError::InvalidOperator(_, value)
+            | LexerError::InvalidEscapeSequence(_, value)
+            | LexerError::GluedIdentifier(_, value) => vec![value.clone()],
+            LexerError::EmptyIdentifier(_) => Vec::new(),
+            LexerError::BrokenLexer(first, second)
+            | LexerError::InvertedSlice(first, second)
+            | LexerError::IndexOutOfBounds(_, first, second) => vec![first.to_string(), second.to_string()],
+            LexerError::SliceOutOfBounds(pos, end, len) => vec![pos.to_string(), end.to_string(), len.to_string()],
+            LexerError::StringTooLong(_, limit)
+            | LexerError::NumberTooLong(_, limit) => vec![limit.to_string()],
+        }
+    }
+
+    /// Gets the description of the error, without the phase and position prefix of its `Display` output
+    ///
+    /// The description is rendered from the English message catalog; see [`crate::messages::Catalog`] for translations.
+    pub fn message(&self) -> String { Catalog::default().render(self.code(), &self.arguments()) }
+
+    /// Gets the lexer position (grapheme index) at which the error was triggered
+    pub fn position(&self) -> usize {
+        match self {
+            LexerError::InvalidToken(pos, _)
+            | LexerError::UnterminatedString(pos, _)
+            | LexerError::EmptyIdentifier(pos)
+            | LexerError::BrokenLexer(pos, _)
+            | LexerError::InvertedSlice(pos, _)
+            | LexerError::SliceOutOfBounds(pos, _, _)
+            | LexerError::IndexOutOfBounds(pos, _, _)
+            | LexerError::UnrecognizedInput(pos, _)
+            | LexerError::InvalidOperator(pos, _)
+            | LexerError::StringTooLong(pos, _)
+            | LexerError::NumberTooLong(pos, _)
+            | LexerError::InvalidEscapeSequence(pos, _)
+            | LexerError::GluedIdentifier(pos, _) => *pos,
+        }
+    }
+}
+
 impl fmt::Display for LexerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -48,6 +121,14 @@ impl fmt::Display for LexerError {
                 write!(f, "Lexer error at position {}: Unrecognized input stream '{}'", pos, input),
             LexerError::InvalidOperator(pos, input) =>
                 write!(f, "Lexer error at position {}: Unrecognized first symbol for comparison operator '{}'", pos, input),
+            LexerError::StringTooLong(pos, limit) =>
+                write!(f, "Lexer error at position {}: String literal is longer than the limit of {} bytes", pos, limit),
+            LexerError::NumberTooLong(pos, limit) =>
+                write!(f, "Lexer error at position {}: Number literal has more than the limit of {} digits", pos, limit),
+            LexerError::InvalidEscapeSequence(pos, sequence) =>
+                write!(f, "Lexer error at position {}: Invalid escape sequence '{}'", pos, sequence),
+            LexerError::GluedIdentifier(pos, identifier) =>
+                write!(f, "Lexer error at position {}: Number literal is directly followed by '{}', which is not a unit suffix", pos, identifier),
         }
     }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 //! The Envlang lexer
 //! 
-//! The lexer takes a Unicode-segmented `String` vector from [`segment_graphemes()`] and turns it into a vector of [`Token`]s.
+//! The lexer takes source text, or a Unicode-segmented `String` vector from [`segment_graphemes()`], and turns it into a vector of [`Token`]s.
 //! 
 //! These `Token`s are then intended to be [parsed] into an Abstract Syntax Tree.
 //! 
@@ -12,6 +12,7 @@
 //! * Empty identifiers
 //! * Unrecognized input
 //! * Invalid lexer states
+//! * Literals exceeding the size limits of [`LexerOptions`]
 //! 
 //! All errors include position information for reporting.
 //! 
@@ -21,21 +22,46 @@
 //! [parsed]: ../parser/index.html
 
 mod token;
+mod stream;
 mod error;
 mod tests;
 
-pub use token::Token;
+pub use token::{escape_string, render_tokens, Location, Token};
+pub use stream::TokenStream;
 pub use error::LexerError;
 
-use crate::symbols::{Keywords, Booleans, ArithmeticOperators, ComparisonOperators, LogicalOperators, OtherOperators, Operators};
-use std::rc::Rc;
-use std::borrow::Borrow;
+use crate::span::{is_line_break, Span};
+#[cfg(feature = "std")]
+use crate::io::SourceFile;
+use crate::symbols::{unit_factor, Keywords, Booleans, ArithmeticOperators, ComparisonOperators, LogicalOperators, OtherOperators, Operators, OperatorAliases};
+use alloc::rc::Rc;
+use crate::unicodesegmenters::graphemes_iter;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Options limiting the size of the literals accepted by the [`Lexer`]
+/// 
+/// The limits are checked while a literal is read, so that adversarial input cannot make the lexer allocate huge tokens. A limit of `None` means that the literal size is unlimited, which is the default.
+/// 
+/// The struct derives the traits `Debug`, `Clone`, `Copy`, `PartialEq`, `Eq`, and `Default`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LexerOptions {
+    /// Maximum length of the contents of a string literal in bytes, beyond which the input is rejected with [`LexerError::StringTooLong`]
+    pub max_string_length: Option<usize>,
+    /// Maximum number of digits in a number literal, beyond which the input is rejected with [`LexerError::NumberTooLong`]
+    /// 
+    /// The integer and fractional parts of a float are separate tokens, and are limited separately.
+    pub max_number_digits: Option<usize>,
+}
 
 /// Envlang lexer
 /// 
-/// The `Lexer` struct holds the Unicode-segmented `String` vector from [`segment_graphemes()`].
+/// The `Lexer` struct holds the input text in a single buffer, together with the byte offsets of its graphemes.
+/// The text is segmented with [`graphemes_iter()`] by [`from_text`](Lexer::from_text), or taken from the Unicode-segmented `String` vector of [`segment_graphemes()`] by [`new`](Lexer::new).
+/// 
+/// Note, that the Lexer does not actually check whether the `String`s given to [`new`](Lexer::new) have been properly segmented.
 /// 
-/// Note, that the Lexer does not actually check whether the `String`s have been properly segmented.
+/// Alternative spellings of operators are only recognised once enabled with [`with_aliases`](Lexer::with_aliases), and literal sizes are only limited once set with [`with_options`](Lexer::with_options).
 /// 
 /// # Panics
 /// 
@@ -48,8 +74,13 @@ use std::borrow::Borrow;
 /// * Relevant context (e.g., partial string content for unterminated strings)
 /// 
 /// [`segment_graphemes()`]: ../unicodesegmenters/fn.segment_graphemes.html
+/// [`graphemes_iter()`]: ../unicodesegmenters/fn.graphemes_iter.html
 pub struct Lexer {
-    input: Vec<Rc<str>>,
+    text: Rc<str>,
+    /// Byte offset of the start of each grapheme, followed by the length of the text
+    bounds: Vec<usize>,
+    aliases: OperatorAliases,
+    options: LexerOptions,
     current: usize
 }
 
@@ -59,19 +90,103 @@ impl Lexer {
     /// # Undefined Behaviour
     /// The Lexer will still work with a non-segmented input, but the results will not be accurate for many Unicode characters.
     pub fn new(input: Vec<String>) -> Self {
+        let mut bounds = Vec::with_capacity(input.len() + 1);
+        let mut offset = 0;
+        for grapheme in &input {
+            bounds.push(offset);
+            offset += grapheme.len();
+        }
+        bounds.push(offset);
         Self {
-            input: input.into_iter().map(|s| Rc::from(s.as_str())).collect(),
+            text: Rc::from(input.concat()),
+            bounds,
+            aliases: OperatorAliases::new(),
+            options: LexerOptions::default(),
             current: 0
         }
     }
 
-    /// Iterates over the input
-    fn iterate(&mut self) -> Option<(usize, Rc<str>)> {
-        if self.current < self.input.len() {
+    /// Initializes a new Lexer over the graphemes of a text
+    /// 
+    /// The text is segmented lazily with [`graphemes_iter`], so that no string is allocated per grapheme.
+    /// 
+    /// # Examples
+    /// ```
+    /// use envlang::lexer::{Lexer, Token};
+    /// 
+    /// let tokens = Lexer::from_text("let x = 5;").tokenize().unwrap();
+    /// assert_eq!(tokens.last(), Some(&Token::EOF));
+    /// ```
+    pub fn from_text(text: &str) -> Self {
+        let mut bounds: Vec<usize> = graphemes_iter(text)
+            .scan(0, |offset, grapheme| {
+                let start = *offset;
+                *offset += grapheme.len();
+                Some(start)
+            })
+            .collect();
+        bounds.push(text.len());
+        Self {
+            text: Rc::from(text),
+            bounds,
+            aliases: OperatorAliases::new(),
+            options: LexerOptions::default(),
+            current: 0
+        }
+    }
+
+    /// Initializes a new Lexer over the graphemes of a [`SourceFile`]
+    /// 
+    /// Lexer positions are grapheme indices into the source file, which can be converted to lines and columns with [`SourceFile::grapheme_line_col`].
+    #[cfg(feature = "std")]
+    pub fn from_source(source: &SourceFile) -> Self {
+        Self::from_text(source.text())
+    }
+
+    /// Sets the [`OperatorAliases`] recognised by the lexer
+    /// 
+    /// # Examples
+    /// ```
+    /// use envlang::lexer::{Lexer, Token};
+    /// use envlang::symbols::{LogicalOperators, OperatorAliases, Operators};
+    /// 
+    /// let mut aliases = OperatorAliases::new();
+    /// aliases.enable("and");
+    /// let tokens = Lexer::from_text("a and b").with_aliases(aliases).tokenize().unwrap();
+    /// assert_eq!(tokens[2], Token::Operator(Operators::Logical(LogicalOperators::AND)));
+    /// ```
+    pub fn with_aliases(mut self, aliases: OperatorAliases) -> Self {
+        self.aliases = aliases;
+        return self;
+    }
+
+    /// Sets the [`LexerOptions`] limiting the size of literals
+    /// 
+    /// # Examples
+    /// ```
+    /// use envlang::lexer::{Lexer, LexerError, LexerOptions};
+    /// 
+    /// let options = LexerOptions { max_string_length: Some(4), ..LexerOptions::default() };
+    /// let result = Lexer::from_text("let s = \"too long\";").with_options(options).tokenize();
+    /// assert!(matches!(result, Err(LexerError::StringTooLong(8, 4))));
+    /// ```
+    pub fn with_options(mut self, options: LexerOptions) -> Self {
+        self.options = options;
+        return self;
+    }
+
+    /// Gets the number of graphemes in the input
+    fn len(&self) -> usize { self.bounds.len() - 1 }
+
+    /// Gets the grapheme at an index of the input
+    fn grapheme(&self, idx: usize) -> &str { &self.text[self.bounds[idx]..self.bounds[idx + 1]] }
+
+    /// Iterates over the input, returning the index of the next grapheme
+    fn iterate(&mut self) -> Option<usize> {
+        if self.current < self.len() {
             let pos = self.current;
-            let ch = Rc::clone(&self.input[self.current]);
             self.current += 1;
-            return Some((pos, ch));
+            return Some(pos);
         } else {
             return None;
         }
@@ -84,29 +199,49 @@ impl Lexer {
     /// # Errors
     /// * [`LexerError::BrokenLexer`]: The currently-held position is beyond input length.
     /// * [`LexerError::IndexOutOfBounds`]: The index of the requested element is beyond input length.
-    fn peek_n(&self, n: usize) -> Result<Rc<str>, LexerError> {
+    fn peek_n(&self, n: usize) -> Result<&str, LexerError> {
         // TODO: This should rather return an Option, since an empty symbol stream is a valid state.
         // However, calls outside of symbol stream length should still error...
         // How about Result<Option<Rc<str>>, LexerError>? That is quite verbose...
-        let length: usize = self.input.len();
+        let length: usize = self.len();
         if self.current > length {
             Err(LexerError::BrokenLexer(self.current, length))
         } else if n >= length {
             Err(LexerError::IndexOutOfBounds(self.current, n, length))
         } else {
-            Ok(Rc::clone(&self.input[n]))
+            Ok(self.grapheme(n))
         }
     }
 
     /// Tokenizes the input
     /// 
+    /// Use [`tokenize_with_locations`](Lexer::tokenize_with_locations) to also get the location of each token in the source text.
+    /// 
     /// # Errors
-    /// * Any errors bubbled up from [`tokenize_string`](Lexer::tokenize_string), [`tokenize_operator`](Lexer::tokenize_operator), [`tokenize_number`](Lexer::tokenize_number), or [`tokenize_alphabetics`](Lexer::tokenize_alphabetics).
-    /// * [`LexerError::UnrecognizedInput`]: The input string does not match the syntax of Envlang.
+    /// * Any errors bubbled up from [`tokenize_with_locations`](Lexer::tokenize_with_locations).
     pub fn tokenize(&mut self) -> Result<Vec<Token>, LexerError> {
+        let tokens = self.tokenize_with_locations()?;
+        return Ok(tokens.into_iter().map(|(token, _)| token).collect());
+    }
+
+    /// Tokenizes the input, pairing each token with its [`Location`] in the source text
+    /// 
+    /// Line breaks are `\n`, `\r\n`, and `\r`, including those inside string literals. Columns count graphemes, and spans count bytes, from the start of the input.
+    /// The final EOF token is located at the end of the input.
+    /// 
+    /// # Errors
+    /// * Any errors bubbled up from [`tokenize_string`](Lexer::tokenize_string), [`tokenize_operator`](Lexer::tokenize_operator), [`tokenize_number`](Lexer::tokenize_number), or [`tokenize_alphabetics`](Lexer::tokenize_alphabetics), including the literal size limits of [`LexerOptions`].
+    /// * [`LexerError::UnrecognizedInput`]: The input string does not match the syntax of Envlang.
+    pub fn tokenize_with_locations(&mut self) -> Result<Vec<(Token, Location)>, LexerError> {
         let mut tokens: Vec<Token> = Vec::new();
-        while let Some((pos, unicode_string)) = self.iterate() {
-            match unicode_string.borrow() {
+        let mut starts: Vec<usize> = Vec::new();
+        // The graphemes are borrowed from a handle to the text, so that the tokenizing methods can advance the lexer
+        let text = Rc::clone(&self.text);
+        while let Some(pos) = self.iterate() {
+            // Every iteration produces exactly one token, starting at the current grapheme
+            starts.push(pos);
+            let unicode_string = &text[self.bounds[pos]..self.bounds[pos + 1]];
+            match unicode_string {
                 "{" =>
                     tokens.push(Token::LeftBrace),
                 "}" =>
@@ -124,7 +259,7 @@ impl Lexer {
                 "'" =>
                     tokens.push(self.tokenize_string("'", pos)?),
                 "+" | "-" | "*" | "/" | "%" | "^" | "=" | "<" | ">" | "!" | "&" | "|" =>
-                    tokens.push(self.tokenize_operator(&unicode_string, pos)?),
+                    tokens.push(self.tokenize_operator(unicode_string, pos)?),
                 "." =>
                     tokens.push(Token::Operator(Operators::Other(OtherOperators::ACCESSOR))),
                 "," =>
@@ -141,38 +276,81 @@ impl Lexer {
             }
         }
         tokens.push(Token::EOF);
-        return Ok(tokens);
+        starts.push(self.len());
+
+        // Convert the grapheme ranges of the tokens into locations
+        let offsets = &self.bounds;
+        let mut lines: Vec<(usize, usize)> = Vec::with_capacity(self.len() + 1);
+        let (mut line, mut column) = (1, 1);
+        for idx in 0..self.len() {
+            lines.push((line, column));
+            let grapheme = self.grapheme(idx);
+            if is_line_break(grapheme) {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        lines.push((line, column));
+
+        let ends: Vec<usize> = starts[1..].iter().copied().chain(core::iter::once(self.len())).collect();
+        let located = tokens.into_iter()
+            .zip(starts.iter().zip(ends.iter()))
+            .enumerate()
+            .map(|(idx, (token, (&start, &end)))| {
+                let (line, column) = lines[start];
+                (token, Location {
+                    token: idx,
+                    line,
+                    column: Some(column),
+                    span: Some(Span::new(offsets[start], offsets[end])),
+                })
+            })
+            .collect();
+        return Ok(located);
+    }
+
+    /// Tokenizes the input into a [`TokenStream`], which keeps the source text next to the located tokens
+    /// 
+    /// # Errors
+    /// * Any errors bubbled up from [`tokenize_with_locations`](Lexer::tokenize_with_locations).
+    pub fn tokenize_stream(&mut self) -> Result<TokenStream, LexerError> {
+        let tokens = self.tokenize_with_locations()?;
+        return Ok(TokenStream::new(Rc::clone(&self.text), tokens));
     }
 
     /// Distinguishes single-symbol operators from dual-symbol operators.
     /// 
+    /// Dual-symbol aliases (e.g. `=!`) take precedence over the standard operators once enabled.
+    /// 
     /// # Errors
     /// * [`LexerError::InvalidOperator`]: Either the first or the second symbol did not match the set of valid comparison operator symbols.
     /// * [`LexerError::IndexOutOfBounds`]: There is no next symbol in the symbol queue, meaning there cannot be a right-hand-side to the operator.
     fn tokenize_comparison(&mut self, first_op: &str, pos: usize) -> Result<Token, LexerError> {
         if let Ok(next_symbol) = self.peek_n(self.current) {
             // We have a next symbol
+            // Check whether the symbols form an enabled alias, such as "=!"
+            let spelling = [first_op, next_symbol].concat();
+            if let Some(op) = self.aliases.get(&spelling) {
+                self.current += 1;
+                return Ok(Token::Operator(op));
+            }
             // Check whether it is "="
-            match next_symbol.borrow() {
-                "=" => {
-                    // Increment `current` to avoid re-parsing the next symbol once the method is done.
-                    self.current += 1;
-                    match first_op {
-                        ">" => return Ok(Token::Operator(Operators::Comparison(ComparisonOperators::GEQ))),
-                        "<" => return Ok(Token::Operator(Operators::Comparison(ComparisonOperators::LEQ))),
-                        "=" => return Ok(Token::Operator(Operators::Comparison(ComparisonOperators::EQ))),
-                        "!" => return Ok(Token::Operator(Operators::Comparison(ComparisonOperators::NEQ))),
-                        _ => return Err(LexerError::InvalidOperator(pos, first_op.to_string())),
-                    }
-                },
-                _ => match first_op {
-                    "!" => return Ok(Token::Operator(Operators::Logical(LogicalOperators::NOT))),
-                    _ => return Err(LexerError::InvalidOperator(pos, next_symbol.to_string())),
-                },
+            if next_symbol == "=" {
+                // Increment `current` to avoid re-parsing the next symbol once the method is done.
+                self.current += 1;
+                match first_op {
+                    ">" => return Ok(Token::Operator(Operators::Comparison(ComparisonOperators::GEQ))),
+                    "<" => return Ok(Token::Operator(Operators::Comparison(ComparisonOperators::LEQ))),
+                    "=" => return Ok(Token::Operator(Operators::Comparison(ComparisonOperators::EQ))),
+                    "!" => return Ok(Token::Operator(Operators::Comparison(ComparisonOperators::NEQ))),
+                    _ => return Err(LexerError::InvalidOperator(pos, first_op.to_string())),
+                }
             }
         }
 
-        // We should have a single symbol (or peek_n returned some other error...)
+        // We should have a single symbol (followed by something else, or peek_n returned some other error...)
         match first_op {
             ">" => return Ok(Token::Operator(Operators::Comparison(ComparisonOperators::GT))),
             "<" => return Ok(Token::Operator(Operators::Comparison(ComparisonOperators::LT))),
@@ -186,6 +364,8 @@ impl Lexer {
     /// 
     /// # Errors
     /// * [`LexerError::InvalidToken`]: `unicode_string` is not an ASCII digit.
+    /// * [`LexerError::NumberTooLong`]: The number has more digits than [`LexerOptions::max_number_digits`].
+    /// * [`LexerError::GluedIdentifier`]: The number is directly followed by an identifier that is not a unit suffix, e.g., `10xs`.
     fn tokenize_number(&mut self, unicode_string: &str, pos: usize) -> Result<Token, LexerError> {
         if unicode_string.is_empty() {
             return Err(LexerError::InvalidToken(pos, String::new()));
@@ -196,6 +376,11 @@ impl Lexer {
             return Err(LexerError::InvalidToken(pos, unicode_string.to_string()));
         }
 
+        let limit = self.options.max_number_digits;
+        let too_long = |digits: usize| limit.filter(|&max| digits > max);
+        if let Some(max) = too_long(unicode_string.len()) {
+            return Err(LexerError::NumberTooLong(pos, max));
+        }
         let mut number = unicode_string.to_string();
 
         while let Ok(next_unicode_string) = self.peek_n(self.current) {
@@ -203,23 +388,71 @@ impl Lexer {
                 // Valid termination state, exit out.
                 break;
             }
+            // Graphemes of ASCII digits have a byte per digit
+            if let Some(max) = too_long(number.len() + next_unicode_string.len()) {
+                return Err(LexerError::NumberTooLong(pos, max));
+            }
 
-            let (_, digit) = self.iterate().unwrap();
-            number.push_str(&digit);
+            let Some(idx) = self.iterate() else { break; };
+            number.push_str(self.grapheme(idx));
+        }
+
+        // A unit suffix directly after the digits is part of the literal, e.g., `10s`, and any other identifier is a typo of one
+        if let Some(suffix) = self.unit_suffix() {
+            number.push_str(&suffix);
+        }
+        let glued = self.identifier_at(self.current);
+        if !glued.is_empty() {
+            return Err(LexerError::GluedIdentifier(self.current, glued));
         }
 
         return Ok(Token::Number(Rc::from(number)));
     }
 
+    /// Consumes a unit suffix of [`UNIT_SUFFIXES`](crate::symbols::UNIT_SUFFIXES) at the current position, if there is one
+    /// 
+    /// The suffix must not continue into an identifier, so that `10sec` is rejected as a whole rather than read as `10s` followed by `ec`.
+    fn unit_suffix(&mut self) -> Option<String> {
+        let mut suffix = String::new();
+        let mut end = self.current;
+        while let Ok(next) = self.peek_n(end) {
+            if !next.chars().all(|c| c.is_ascii_alphabetic()) {
+                break;
+            }
+            suffix.push_str(next);
+            end += 1;
+        }
+        if !self.identifier_at(end).is_empty() {
+            return None;
+        }
+        unit_factor(&suffix)?;
+        self.current = end;
+        return Some(suffix);
+    }
+
+    /// Gets the identifier characters (alphanumerics and `_`) starting at a position, without consuming them
+    fn identifier_at(&self, start: usize) -> String {
+        let mut identifier = String::new();
+        let mut end = start;
+        while let Ok(next) = self.peek_n(end) {
+            if !next.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                break;
+            }
+            identifier.push_str(next);
+            end += 1;
+        }
+        return identifier;
+    }
+
     /// Matches potential non-string-delimited character sequences
     /// 
-    /// Matches can be boolean values, reserved [keywords](crate::symbols::Keywords), or identifiers.
+    /// Matches can be boolean values, reserved [keywords](crate::symbols::Keywords), enabled word aliases of operators, or identifiers.
     /// 
     /// # Errors
     /// * [`LexerError::InvalidToken`]: The input stream did not start with an alphabetic character.
     /// * [`LexerError::EmptyIdentifier`]: The input stream is empty.
     fn tokenize_alphabetics(&mut self, unicode_string: &str, pos: usize) -> Result<Token, LexerError> {
-        if unicode_string == "" {
+        if unicode_string.is_empty() {
             return Err(LexerError::EmptyIdentifier(pos));
         }
 
@@ -229,15 +462,14 @@ impl Lexer {
         
         let mut temp = unicode_string.to_string();
         while let Ok(following_unicode_string) = self.peek_n(self.current) {
-            match following_unicode_string.as_ref() {
+            match following_unicode_string {
                 s if s.chars().all(
                     |c| c.is_alphanumeric()) ||
                     s == "-" ||
                     s == "_" =>
                     {
-                        // TODO: Convert to if-let-some to handle error state more gracefully.
-                        let (_, ch) = self.iterate().unwrap();
-                        temp.push_str(&ch);
+                        let Some(idx) = self.iterate() else { break; };
+                        temp.push_str(self.grapheme(idx));
                     },
                     _ => break,
             }
@@ -250,12 +482,17 @@ impl Lexer {
             "true" => Ok(Token::Boolean(Booleans::TRUE)),
             "false" => Ok(Token::Boolean(Booleans::FALSE)),
             "return" => Ok(Token::Keyword(Keywords::RETURN)),
-            _ => Ok(Token::Identifier(Rc::from(temp))),
+            word => match self.aliases.get(word) {
+                Some(op) => Ok(Token::Operator(op)),
+                None => Ok(Token::Identifier(Rc::from(temp))),
+            },
         }
     }
     
     /// Tokenize a string
     /// 
+    /// Escape sequences are replaced by the characters they stand for (see [`tokenize_escape`](Lexer::tokenize_escape)), and every other grapheme is copied verbatim.
+    /// 
     /// # Safety
     /// The calling context must supply an appropriate string delimiter.
     /// 
@@ -263,8 +500,9 @@ impl Lexer {
     /// * `matched` - The delimiter used.
     /// 
     /// # Errors
-    /// * Any errors bubbled up from [`peek_n`](Lexer::peek_n).
+    /// * Any errors bubbled up from [`peek_n`](Lexer::peek_n) or [`tokenize_escape`](Lexer::tokenize_escape).
     /// * [`LexerError::UnterminatedString`]: Input ends before a closing delimiter is found, or the lexer is broken.
+    /// * [`LexerError::StringTooLong`]: The contents of the string are longer than [`LexerOptions::max_string_length`].
     fn tokenize_string(&mut self, matched: &str, start_pos: usize) -> Result<Token, LexerError> {
         let mut value: String = String::new();
         loop {
@@ -272,9 +510,18 @@ impl Lexer {
             // TODO: Split up errors.
             match self.peek_n(self.current) {
                 Ok(ch) => {
-                    if ch.as_ref() != matched {
-                        let (_, s) = self.iterate().unwrap();
-                        value.push_str(&s);
+                    if ch != matched {
+                        let Some(idx) = self.iterate() else {
+                            return Err(LexerError::UnterminatedString(start_pos, value));
+                        };
+                        let unescaped = match self.grapheme(idx) {
+                            "\\" => self.tokenize_escape(idx, start_pos, &value)?.to_string(),
+                            grapheme => grapheme.to_string(),
+                        };
+                        if let Some(max) = self.options.max_string_length.filter(|&max| value.len() + unescaped.len() > max) {
+                            return Err(LexerError::StringTooLong(start_pos, max));
+                        }
+                        value.push_str(&unescaped);
                     } else {
                         self.iterate(); // Skip over the closing brace
                         break;
@@ -288,6 +535,58 @@ impl Lexer {
         Ok(Token::StringLiteral(Rc::from(value)))
     }
 
+    /// Tokenize the escape sequence after a backslash in a string literal
+    /// 
+    /// The escape sequences are `\n` (line feed), `\r` (carriage return), `\t` (tab), `\0` (null), `\\`, `\"`, and `\'`,
+    /// and unicode escapes of one to six hexadecimal digits in braces, such as `\u{1F600}`.
+    /// 
+    /// # Arguments
+    /// * `pos` - The position of the backslash.
+    /// * `start_pos` - The position of the opening delimiter of the string.
+    /// * `value` - The contents of the string so far, for reporting unterminated strings.
+    /// 
+    /// # Errors
+    /// * [`LexerError::UnterminatedString`]: Input ends inside the escape sequence.
+    /// * [`LexerError::InvalidEscapeSequence`]: The backslash is followed by an unknown escape, or a unicode escape is malformed or not a valid character.
+    fn tokenize_escape(&mut self, pos: usize, start_pos: usize, value: &str) -> Result<char, LexerError> {
+        let mut sequence = String::from("\\");
+        let Some(idx) = self.iterate() else {
+            return Err(LexerError::UnterminatedString(start_pos, value.to_string()));
+        };
+        sequence.push_str(self.grapheme(idx));
+        match self.grapheme(idx) {
+            "n" => return Ok('\n'),
+            "r" => return Ok('\r'),
+            "t" => return Ok('\t'),
+            "0" => return Ok('\0'),
+            "\\" => return Ok('\\'),
+            "\"" => return Ok('"'),
+            "'" => return Ok('\''),
+            "u" => (),
+            _ => return Err(LexerError::InvalidEscapeSequence(pos, sequence)),
+        }
+
+        // Unicode escapes end at the closing brace, or at the first grapheme that cannot belong to them
+        let mut digits = String::new();
+        let mut opened = false;
+        loop {
+            let Some(idx) = self.iterate() else {
+                return Err(LexerError::UnterminatedString(start_pos, value.to_string()));
+            };
+            let grapheme = self.grapheme(idx);
+            sequence.push_str(grapheme);
+            match grapheme {
+                "{" if !opened => opened = true,
+                "}" if opened => break,
+                digit if opened && digits.len() < 6 && digit.chars().all(|c| c.is_ascii_hexdigit()) => digits.push_str(digit),
+                _ => return Err(LexerError::InvalidEscapeSequence(pos, sequence)),
+            }
+        }
+        return u32::from_str_radix(&digits, 16).ok()
+            .and_then(char::from_u32)
+            .ok_or(LexerError::InvalidEscapeSequence(pos, sequence));
+    }
+
     /// Tokenize an operator
     /// 
     /// # Errors
@@ -301,7 +600,7 @@ impl Lexer {
             "/" => Ok(Operators::Arithmetic(ArithmeticOperators::DIVIDE)),
             "%" => Ok(Operators::Arithmetic(ArithmeticOperators::MODULUS)),
             "^" => Ok(Operators::Arithmetic(ArithmeticOperators::EXPONENTIATION)),
-            ">" | "<" | "!" | "=" => return Ok(self.tokenize_comparison(&unicode_string, pos)?),
+            ">" | "<" | "!" | "=" => return self.tokenize_comparison(unicode_string, pos),
             "&" => Ok(Operators::Logical(LogicalOperators::AND)),
             "|" => Ok(Operators::Logical(LogicalOperators::OR)),
             _ => Err(LexerError::UnrecognizedInput(pos, unicode_string.to_string())),
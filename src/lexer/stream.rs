@@ -0,0 +1,62 @@
+use super::{Location, Token};
+#[cfg(feature = "std")]
+use crate::io::SourceFile;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+/// Tokens together with the source text they were lexed from
+/// 
+/// Returned by [`Lexer::tokenize_stream`](super::Lexer::tokenize_stream). Every token is paired with its [`Location`], and its exact text can be quoted from the source with [`text`](TokenStream::text) instead of being reconstructed from the token.
+/// 
+/// The source is shared with the lexer, so creating a stream does not copy it.
+/// 
+/// # Examples
+/// ```
+/// use envlang::lexer::{Lexer, Token};
+/// 
+/// let stream = Lexer::from_text("let x = 'abc';").tokenize_stream().unwrap();
+/// assert_eq!(stream.source(), "let x = 'abc';");
+/// assert_eq!(stream.tokens()[6].0, Token::StringLiteral("abc".into()));
+/// assert_eq!(stream.text(6), Some("'abc'"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenStream {
+    source: Rc<str>,
+    tokens: Vec<(Token, Location)>,
+}
+
+impl TokenStream {
+    /// Creates a new token stream from a source text and the located tokens lexed from it
+    pub fn new(source: Rc<str>, tokens: Vec<(Token, Location)>) -> Self {
+        Self { source, tokens }
+    }
+
+    /// Gets the source text
+    pub fn source(&self) -> &str { &self.source }
+
+    /// Gets the tokens and their locations
+    pub fn tokens(&self) -> &[(Token, Location)] { &self.tokens }
+
+    /// Gets the number of tokens, including the final EOF token
+    pub fn len(&self) -> usize { self.tokens.len() }
+
+    /// Checks whether the stream has no tokens
+    pub fn is_empty(&self) -> bool { self.tokens.is_empty() }
+
+    /// Gets the exact source text of the token at an index
+    /// 
+    /// Returns `None` if there is no token at the index, or if its location has no span.
+    pub fn text(&self, idx: usize) -> Option<&str> {
+        let span = self.tokens.get(idx)?.1.span?;
+        return self.source.get(span.start..span.end);
+    }
+
+    /// Creates a [`SourceFile`] of the source text for rendering diagnostics
+    #[cfg(feature = "std")]
+    pub fn source_file(&self, name: &str) -> SourceFile {
+        return SourceFile::new(name, self.source.as_ref());
+    }
+
+    /// Splits the stream into its source text and tokens
+    pub fn into_parts(self) -> (Rc<str>, Vec<(Token, Location)>) { (self.source, self.tokens) }
+}
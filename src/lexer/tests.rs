@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use crate::symbols::{ArithmeticOperators, ComparisonOperators, LogicalOperators, OtherOperators, Operators, Booleans, Keywords};
-    use crate::lexer::{Lexer, LexerError, Token};
+    use crate::symbols::{ArithmeticOperators, ComparisonOperators, LogicalOperators, OtherOperators, Operators, OperatorAliases, Booleans, Keywords};
+    use crate::lexer::{render_tokens, Lexer, LexerError, LexerOptions, Token};
+    use crate::io::SourceFile;
+    use crate::span::{Position, Span};
     use std::rc::Rc;
 
     // Error condition tests
@@ -180,6 +182,19 @@ mod tests {
         assert_eq!(tokens, vec![Token::Operator(Operators::Other(OtherOperators::ASSIGNMENT)), Token::EOF]);
     }
 
+    #[test]
+    fn matches_operator_followed_by_whitespace() {
+        let input = vec!["=".to_string(), " ".to_string(), "<".to_string(), " ".to_string()];
+        let tokens = Lexer::new(input).tokenize().unwrap();
+        assert_eq!(tokens, vec![
+            Token::Operator(Operators::Other(OtherOperators::ASSIGNMENT)),
+            Token::Whitespace(Rc::from(" ")),
+            Token::Operator(Operators::Comparison(ComparisonOperators::LT)),
+            Token::Whitespace(Rc::from(" ")),
+            Token::EOF
+        ]);
+    }
+
     #[test]
     fn matches_digits() {
         let input = vec!["12345".to_string()];
@@ -384,4 +399,172 @@ mod tests {
             Err(LexerError::BrokenLexer(pos, len)) if pos == 2 && len == 1
         ));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn lexes_source_file() {
+        let source = SourceFile::new("test.envl", "x\n@");
+        let result = Lexer::from_source(&source).tokenize();
+        let Err(LexerError::UnrecognizedInput(pos, _)) = result else {
+            panic!("Expected an unrecognized input error");
+        };
+        assert_eq!(source.grapheme_line_col(pos), Some((2, 1)));
+    }
+
+    #[test]
+    fn tokens_have_locations() {
+        let source = SourceFile::new("test.envl", "\"ä\"\r\nab");
+        let tokens = Lexer::from_source(&source).tokenize_with_locations().unwrap();
+        let locations: Vec<(usize, usize, Option<usize>, Option<Span>)> = tokens.iter()
+            .map(|(_, location)| (location.token, location.line, location.column, location.span))
+            .collect();
+        assert_eq!(locations, vec![
+            (0, 1, Some(1), Some(Span::new(0, 4))),     // "ä" (two-byte grapheme)
+            (1, 1, Some(4), Some(Span::new(4, 6))),     // \r\n
+            (2, 2, Some(1), Some(Span::new(6, 8))),     // ab
+            (3, 2, Some(3), Some(Span::new(8, 8))),     // EOF
+        ]);
+        assert_eq!(tokens[2].1.start(), Some(Position::new(6, 2, 1)));
+    }
+
+    #[test]
+    fn lexes_text_like_segmented_input() {
+        let text = "let x = \"ä\";\r\nlet fun f[a] = { return a; }\n";
+        let from_text = Lexer::from_text(text).tokenize_with_locations().unwrap();
+        let from_segments = Lexer::new(crate::unicodesegmenters::segment_graphemes(text)).tokenize_with_locations().unwrap();
+        assert_eq!(from_text, from_segments);
+    }
+
+    #[test]
+    fn streams_keep_the_source_text() {
+        let source = "let x = \"a\nb\";\r\nlet y = 12;";
+        let stream = Lexer::from_text(source).tokenize_stream().unwrap();
+        assert_eq!(stream.source(), source);
+        assert_eq!(stream.len(), stream.tokens().len());
+        let texts: String = (0..stream.len()).filter_map(|idx| stream.text(idx)).collect();
+        assert_eq!(texts, source);
+        assert_eq!(stream.text(6), Some("\"a\nb\""));
+        assert_eq!(stream.text(stream.len()), None);
+    }
+
+    #[test]
+    fn rendered_tokens_round_trip_over_the_corpus() {
+        let mut paths = Vec::new();
+        for dir in ["tests/cases", "tests/data", "tests/data/lints", "tests/data/modules"] {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.extension().is_some_and(|extension| extension == "envl") {
+                    paths.push(path);
+                }
+            }
+        }
+        assert!(!paths.is_empty());
+        for path in paths {
+            let source = std::fs::read_to_string(&path).unwrap();
+            let Ok(tokens) = Lexer::from_text(&source).tokenize() else { continue; };
+            assert_eq!(render_tokens(&tokens), source, "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn rendered_tokens_lex_to_the_same_tokens() {
+        let source = "let a = 'say \"hi\"';\r\nlet b = 'plain';\tlet c = x.y >= 10;";
+        let tokens = Lexer::from_text(source).tokenize().unwrap();
+        let rendered = render_tokens(&tokens);
+        assert_eq!(rendered, "let a = 'say \"hi\"';\r\nlet b = \"plain\";\tlet c = x.y >= 10;");
+        assert_eq!(Lexer::from_text(&rendered).tokenize().unwrap(), tokens);
+    }
+
+    #[test]
+    fn aliases_are_lexed_once_enabled() {
+        let source = "let x = a =! b and not c or d;";
+        let tokens = Lexer::from_text(source).tokenize().unwrap();
+        assert!(tokens.contains(&Token::Identifier("and".into())));
+        assert!(tokens.contains(&Token::Operator(Operators::Other(OtherOperators::ASSIGNMENT))));
+
+        let tokens = Lexer::from_text(source).with_aliases(OperatorAliases::all()).tokenize().unwrap();
+        let operators: Vec<&Token> = tokens.iter().filter(|token| matches!(token, Token::Operator(_))).collect();
+        assert_eq!(operators, vec![
+            &Token::Operator(Operators::Other(OtherOperators::ASSIGNMENT)),
+            &Token::Operator(Operators::Comparison(ComparisonOperators::NEQ)),
+            &Token::Operator(Operators::Logical(LogicalOperators::AND)),
+            &Token::Operator(Operators::Logical(LogicalOperators::NOT)),
+            &Token::Operator(Operators::Logical(LogicalOperators::OR)),
+        ]);
+        // Words merely starting with an alias are still identifiers
+        let tokens = Lexer::from_text("android").with_aliases(OperatorAliases::all()).tokenize().unwrap();
+        assert_eq!(tokens[0], Token::Identifier("android".into()));
+    }
+
+    #[test]
+    fn literal_sizes_are_limited_once_set() {
+        let source = "let s = 'Grüße';\nlet n = 12345.678;";
+        assert!(Lexer::from_text(source).tokenize().is_ok());
+
+        // Strings are limited in bytes, without their delimiters
+        let options = LexerOptions { max_string_length: Some(6), ..LexerOptions::default() };
+        let err = Lexer::from_text(source).with_options(options).tokenize().unwrap_err();
+        assert!(matches!(err, LexerError::StringTooLong(8, 6)), "{:?}", err);
+        assert_eq!(err.code(), "E0010");
+        assert_eq!(err.to_string(), "Lexer error at position 8: String literal is longer than the limit of 6 bytes");
+        let options = LexerOptions { max_string_length: Some(7), ..LexerOptions::default() };
+        assert!(Lexer::from_text(source).with_options(options).tokenize().is_ok());
+
+        // The integer and fractional parts of a float are limited separately
+        let options = LexerOptions { max_number_digits: Some(4), ..LexerOptions::default() };
+        let err = Lexer::from_text(source).with_options(options).tokenize().unwrap_err();
+        assert!(matches!(err, LexerError::NumberTooLong(25, 4)), "{:?}", err);
+        assert_eq!(err.message(), "Number literal has more than the limit of 4 digits");
+        let options = LexerOptions { max_number_digits: Some(5), ..LexerOptions::default() };
+        assert!(Lexer::from_text(source).with_options(options).tokenize().is_ok());
+    }
+
+    #[test]
+    fn escape_sequences_are_replaced() {
+        let tokens = Lexer::from_text(r#"'tab\t\"quote\" \'single\' back\\slash\n\r\0 \u{1F600}\u{e9}'"#).tokenize().unwrap();
+        assert_eq!(tokens[0], Token::StringLiteral("tab\t\"quote\" 'single' back\\slash\n\r\0 \u{1F600}\u{e9}".into()));
+
+        // Escaped characters count towards the length limit as they are, not as they were written
+        let options = LexerOptions { max_string_length: Some(4), ..LexerOptions::default() };
+        assert!(Lexer::from_text(r#""\u{1F600}""#).with_options(options).tokenize().is_ok());
+    }
+
+    #[test]
+    fn invalid_escape_sequences_are_rejected() {
+        let err = Lexer::from_text(r#"let s = "a\qb";"#).tokenize().unwrap_err();
+        assert!(matches!(&err, LexerError::InvalidEscapeSequence(10, sequence) if sequence == "\\q"), "{:?}", err);
+        assert_eq!(err.code(), "E0012");
+        assert_eq!(err.to_string(), "Lexer error at position 10: Invalid escape sequence '\\q'");
+
+        for (source, sequence) in [(r#""\u41""#, r"\u4"), (r#""\u{}""#, r"\u{}"), (r#""\u{1234567}""#, r"\u{1234567"), (r#""\u{D800}""#, r"\u{D800}"), (r#""\u{41""#, r#"\u{41""#)] {
+            let err = Lexer::from_text(source).tokenize().unwrap_err();
+            assert!(matches!(&err, LexerError::InvalidEscapeSequence(1, found) if found == sequence), "{}: {:?}", source, err);
+        }
+        assert!(matches!(Lexer::from_text(r#""abc\"#).tokenize(), Err(LexerError::UnterminatedString(0, _))));
+    }
+
+    #[test]
+    fn unit_suffixes_are_part_of_numbers() {
+        let tokens = Lexer::from_text("10s 512kb 1.5h 3 min 2s-1").tokenize().unwrap();
+        let tokens: Vec<Token> = tokens.into_iter().filter(|token| !matches!(token, Token::Whitespace(_))).collect();
+        assert_eq!(tokens, vec![
+            Token::Number("10s".into()),
+            Token::Number("512kb".into()),
+            Token::Number("1".into()),
+            Token::Operator(Operators::Other(OtherOperators::ACCESSOR)),
+            Token::Number("5h".into()),
+            Token::Number("3".into()),
+            Token::Identifier("min".into()),
+            Token::Number("2s".into()),
+            Token::Operator(Operators::Arithmetic(ArithmeticOperators::SUBTRACT)),
+            Token::Number("1".into()),
+            Token::EOF,
+        ]);
+
+        // Identifiers directly after a number are typos of suffixes rather than separate tokens
+        for (source, pos, identifier) in [("10xs", 2, "xs"), ("3min", 1, "min"), ("10sec", 2, "sec"), ("1s_2", 1, "s_2"), ("1.5hours", 3, "hours")] {
+            let err = Lexer::from_text(source).tokenize().unwrap_err();
+            assert!(matches!(&err, LexerError::GluedIdentifier(found, glued) if *found == pos && glued == identifier), "{}: {:?}", source, err);
+            assert_eq!(err.code(), "E0013");
+        }
+    }
+}
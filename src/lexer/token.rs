@@ -1,5 +1,8 @@
+use crate::span::{Position, Span};
 use crate::symbols::{Booleans, Keywords, Operators};
-use std::rc::Rc;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::format;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Token {
@@ -25,7 +28,7 @@ impl ToString for Token {
     fn to_string(&self) -> String {
         match self {
             Token::Number(n) => n.to_string(),
-            Token::StringLiteral(s) => format!("\"{}\"", s),
+            Token::StringLiteral(s) => format!("\"{}\"", escape_string(s)),
             Token::Boolean(b) => b.to_string(),
             Token::Identifier(i) => i.to_string(),
             Token::Keyword(k) => k.to_string(),
@@ -43,3 +46,99 @@ impl ToString for Token {
         }
     }
 }
+
+/// Renders tokens back into source text
+/// 
+/// Whitespace tokens are kept by the lexer, so rendering the tokens of a text gives back the text itself, with one exception:
+/// the lexer does not record string delimiters or escape sequences, so string literals are rendered with double quotes, or with single quotes if they contain a double quote but no single quote.
+/// Their contents are escaped as with [`escape_string`], except that single-quoted literals escape single quotes instead of double quotes.
+/// Either way, lexing the rendered text gives the same tokens again.
+/// 
+/// The final EOF token renders as nothing.
+/// 
+/// # Examples
+/// ```
+/// use envlang::lexer::{render_tokens, Lexer};
+/// 
+/// let source = "let x = {\n\tlet y = \"z\";\n};\n";
+/// let tokens = Lexer::from_text(source).tokenize().unwrap();
+/// assert_eq!(render_tokens(&tokens), source);
+/// ```
+pub fn render_tokens(tokens: &[Token]) -> String {
+    let mut output = String::new();
+    for token in tokens {
+        match token {
+            Token::EOF => (),
+            Token::StringLiteral(s) if s.contains('"') && !s.contains('\'') => output.push_str(&format!("'{}'", escape(s, '\''))),
+            token => output.push_str(&token.to_string()),
+        }
+    }
+    return output;
+}
+
+/// Escapes the value of a string literal, so that it can be written between double quotes
+/// 
+/// Backslashes and double quotes are escaped, as are control characters other than line breaks and tabs, which string literals may contain as they are.
+/// Lexing the escaped value between double quotes gives back the original value.
+/// 
+/// # Examples
+/// ```
+/// use envlang::lexer::escape_string;
+/// 
+/// assert_eq!(escape_string(r#"a "quoted" \ path"#), r#"a \"quoted\" \\ path"#);
+/// assert_eq!(escape_string("bell\u{7}"), "bell\\u{7}");
+/// ```
+pub fn escape_string(value: &str) -> String { escape(value, '"') }
+
+/// Escapes the value of a string literal for writing between a quote character
+fn escape(value: &str, quote: char) -> String {
+    let mut output = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => output.push_str("\\\\"),
+            c if c == quote => {
+                output.push('\\');
+                output.push(c);
+            },
+            '\0' => output.push_str("\\0"),
+            '\n' | '\r' | '\t' => output.push(c),
+            c if c.is_control() => output.push_str(&format!("\\u{{{:X}}}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    return output;
+}
+
+/// Location of a token in the source text
+/// 
+/// Locations produced by [`Lexer::tokenize_with_locations`](super::Lexer::tokenize_with_locations) always contain:
+/// * `token`: The index of the token in the token vector.
+/// * `line`: The one-indexed line of the first character of the token.
+/// * `column`: The one-indexed column (in graphemes) of the first character of the token.
+/// * `span`: The byte range of the token in the source text.
+/// 
+/// Tokens that were not lexed from source text (e.g., built by hand for the parser) only have a token index and a line, with the column and span set to `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub token: usize,
+    pub line: usize,
+    pub column: Option<usize>,
+    pub span: Option<Span>,
+}
+
+impl Location {
+    /// Creates a location that only knows the token index and line
+    pub fn new(token: usize, line: usize) -> Self {
+        Self {
+            token,
+            line,
+            column: None,
+            span: None,
+        }
+    }
+
+    /// Gets the position of the first character of the token, if the location was lexed from source text
+    pub fn start(&self) -> Option<Position> {
+        return Some(Position::new(self.span?.start, self.line, self.column?));
+    }
+}
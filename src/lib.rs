@@ -1,9 +1,50 @@
 //! Envlang is an interpreted programming language focused on environment manipulation
 //! 
 //! This crate provides both a library for working with Envlang files and a CLI tool for running Envlang scripts.
+//!
+//! # Features
+//! * `std` (default): Reading files ([`io`]), configuration ([`config`]), linting ([`lint`]), the single-call [`pipeline`], and the CLI.
+//! * `testing`: Generators of random programs for property tests (`testing`).
+//!
+//! Without the `std` feature, the crate is `no_std` and only requires `alloc`.
+//! The front end and the [`evaluator`] with its [`value`]s and [`environment`]s remain available: segmentation, the [`lexer`], the [`parser`] and its AST, the [`printer`], [`numeric`] semantics, partial evaluation ([`specialize`]), function inlining ([`inline`]), [`diagnostic`]s, and [`messages`].
 
+// Explicit `return`s, `ToString` implementations, and `tests.rs` files wrapping a `tests` module are the house style of this crate.
+#![allow(clippy::needless_return, clippy::to_string_trait_impl, clippy::module_inception)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod config;
+pub mod diagnostic;
+pub mod environment;
+#[cfg(feature = "std")]
+pub mod error;
+pub mod evaluator;
+pub mod inline;
+#[cfg(feature = "std")]
 pub mod io;
 pub mod unicodesegmenters;
 pub mod lexer;
+#[cfg(feature = "std")]
+pub mod lint;
+pub mod messages;
+pub mod numeric;
 pub mod parser;
-pub mod symbols;
\ No newline at end of file
+#[cfg(feature = "std")]
+pub mod pipeline;
+pub mod printer;
+#[cfg(feature = "std")]
+pub mod snippet;
+pub mod span;
+pub mod specialize;
+pub mod symbols;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod value;
+
+#[cfg(feature = "std")]
+pub use pipeline::{check_ast, check_source, parse_source, parse_source_with, parse_source_with_config, parser_with_config, run_source};
+#[cfg(feature = "std")]
+pub use printer::format_source;
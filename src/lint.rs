@@ -0,0 +1,668 @@
+//! Warnings for code that is valid but likely a mistake
+//!
+//! The lints run on the parsed [`AstNode`] of a program and report their findings as [`Diagnostic`]s, separately from hard errors.
+//!
+//! Each lint has a name and a stable code, either of which can be used to set its [`LintLevel`] in the configuration:
+//! * `unused-binding` (`W0001`): A binding inside a function body is never used by the function.
+//! * `unused-parameter` (`W0002`): A function parameter is never used by the function.
+//! * `shadowing` (`W0003`): A binding has the same name as a binding or parameter of an enclosing environment.
+//! * `implicit-truthiness` (`W0004`): A logical operator is applied to a number or a string literal.
+//! * `mixed-scripts` (`W0005`): An identifier mixes Latin, Greek, or Cyrillic letters.
+//! * `confusable-identifier` (`W0006`): A Greek or Cyrillic identifier is made up of letters that look like Latin ones.
+//! * `bidi-control` (`W0007`): A string literal contains a bidirectional control character, which can make code appear different from what it is.
+//! * `anonymous-value` (`W0008`): A value without a name can never be accessed, as it is not the only element of an assigned or returned environment.
+//!
+//! All lints are reported as warnings by default.
+//! In strict mode ([`Config::strict`]), all lints except `unused-binding` and `unused-parameter` are reported as errors instead, unless their level is set explicitly.
+//! Lints set to [`LintLevel::Deny`] are reported as errors, as are all warnings if [`Config::deny_warnings`] is set.
+//!
+//! Bindings outside of function bodies are never considered unused, as they are a part of the resulting environment.
+//! Anonymous values, on the other hand, are only a part of the resulting environment when they are all there is to it (e.g. `let a = { 5; };` or `return a;`).
+//!
+//! If the parser recorded source locations, each diagnostic points at the name of the innermost binding or function declaring the code it was found in.
+
+use crate::config::{Config, LintLevel};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::lexer::escape_string;
+use crate::parser::AstNode;
+use crate::printer::print_expression;
+use crate::span::Span;
+use crate::symbols::{LogicalOperators, Operators, OtherOperators};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/// A lint, identified by its name and code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lint {
+    pub name: &'static str,
+    pub code: &'static str,
+    /// Whether the lint is denied by default in strict mode
+    pub strict: bool,
+}
+
+/// A binding inside a function body is never used
+pub const UNUSED_BINDING: Lint = Lint { name: "unused-binding", code: "W0001", strict: false };
+
+/// A function parameter is never used
+pub const UNUSED_PARAMETER: Lint = Lint { name: "unused-parameter", code: "W0002", strict: false };
+
+/// A binding shadows a binding or parameter of an enclosing environment
+pub const SHADOWING: Lint = Lint { name: "shadowing", code: "W0003", strict: true };
+
+/// A logical operator is applied to a value that is not a boolean
+pub const IMPLICIT_TRUTHINESS: Lint = Lint { name: "implicit-truthiness", code: "W0004", strict: true };
+
+/// An identifier mixes Latin, Greek, or Cyrillic letters
+pub const MIXED_SCRIPTS: Lint = Lint { name: "mixed-scripts", code: "W0005", strict: true };
+
+/// A Greek or Cyrillic identifier can be mistaken for a Latin one
+pub const CONFUSABLE_IDENTIFIER: Lint = Lint { name: "confusable-identifier", code: "W0006", strict: true };
+
+/// A string literal contains a bidirectional control character
+pub const BIDI_CONTROL: Lint = Lint { name: "bidi-control", code: "W0007", strict: true };
+
+/// A value without a name can never be accessed
+pub const ANONYMOUS_VALUE: Lint = Lint { name: "anonymous-value", code: "W0008", strict: true };
+
+/// All lints known to Envlang
+pub const LINTS: [Lint; 8] = [
+    UNUSED_BINDING, UNUSED_PARAMETER, SHADOWING, IMPLICIT_TRUTHINESS, MIXED_SCRIPTS, CONFUSABLE_IDENTIFIER, BIDI_CONTROL,
+    ANONYMOUS_VALUE,
+];
+
+/// A finding of a lint, with the arguments of its message and the span of the declaration it was found in
+struct Finding {
+    lint: Lint,
+    arguments: Vec<String>,
+    span: Option<Span>,
+}
+
+impl Finding {
+    fn new(lint: Lint, arguments: Vec<String>, span: Option<Span>) -> Self {
+        return Finding { lint, arguments, span };
+    }
+}
+
+/// Bidirectional control characters, which reorder the displayed text
+const BIDI_CONTROLS: [char; 12] = [
+    '\u{061C}', '\u{200E}', '\u{200F}', '\u{202A}', '\u{202B}', '\u{202C}',
+    '\u{202D}', '\u{202E}', '\u{2066}', '\u{2067}', '\u{2068}', '\u{2069}',
+];
+
+/// Greek and Cyrillic letters that are indistinguishable from Latin letters in most fonts, with the Latin letter they look like
+const CONFUSABLES: [(char, char); 40] = [
+    ('А', 'A'), ('В', 'B'), ('Е', 'E'), ('К', 'K'), ('М', 'M'), ('Н', 'H'), ('О', 'O'), ('Р', 'P'),
+    ('С', 'C'), ('Т', 'T'), ('Х', 'X'), ('а', 'a'), ('е', 'e'), ('о', 'o'), ('р', 'p'), ('с', 'c'),
+    ('у', 'y'), ('х', 'x'), ('і', 'i'), ('ј', 'j'), ('ѕ', 's'), ('ԁ', 'd'), ('һ', 'h'), ('ԛ', 'q'),
+    ('ԝ', 'w'), ('Α', 'A'), ('Β', 'B'), ('Ε', 'E'), ('Ζ', 'Z'), ('Η', 'H'), ('Ι', 'I'), ('Κ', 'K'),
+    ('Μ', 'M'), ('Ν', 'N'), ('Ο', 'O'), ('Ρ', 'P'), ('Τ', 'T'), ('Υ', 'Y'), ('Χ', 'X'), ('ο', 'o'),
+];
+
+impl Lint {
+    /// Gets the level of the lint in a configuration
+    ///
+    /// A level set for the name of the lint takes precedence over a level set for its code.
+    /// Without either, strict lints are denied in strict mode, and all other lints are warnings.
+    pub fn level(&self, config: &Config) -> LintLevel {
+        let default = if self.strict && config.strict { LintLevel::Deny } else { LintLevel::Warn };
+        return config.lints.get(self.name)
+            .or_else(|| config.lints.get(self.code))
+            .copied()
+            .unwrap_or(default);
+    }
+}
+
+/// Finds a lint by its name or code
+pub fn find_lint(name: &str) -> Option<Lint> {
+    return LINTS.into_iter().find(|lint| lint.name == name || lint.code == name);
+}
+
+/// Runs all lints on a program
+///
+/// Returns the diagnostics of all lints that are not allowed, in the order they were found.
+/// The messages are rendered from [`Config::messages`].
+pub fn lint(ast: &AstNode, config: &Config) -> Vec<Diagnostic> {
+    let mut findings: Vec<Finding> = Vec::new();
+    find_unused(ast, &mut findings);
+    find_shadowing(ast, &mut Vec::new(), &mut findings);
+    find_implicit_truthiness(ast, None, &mut findings);
+    find_suspicious_unicode(ast, &mut findings);
+    find_anonymous_values(ast, false, None, &mut findings);
+
+    let mut diagnostics = Vec::new();
+    for Finding { lint, arguments, span } in findings {
+        let severity = match lint.level(config) {
+            LintLevel::Allow => continue,
+            LintLevel::Deny => Severity::Error,
+            LintLevel::Warn if config.deny_warnings => Severity::Error,
+            LintLevel::Warn => Severity::Warning,
+        };
+        let level = lint.level(config).to_string();
+        let mut diagnostic = Diagnostic::new(severity, config.messages.render(lint.code, &arguments))
+            .with_code(lint.code)
+            .with_note(config.messages.render("note.lint-level", &[lint.name.to_string(), level]));
+        if let Some(span) = span {
+            diagnostic = diagnostic.with_span(span);
+        }
+        diagnostics.push(diagnostic);
+    }
+    return diagnostics;
+}
+
+/// Finds unused bindings and parameters in every function of a subtree
+fn find_unused(node: &AstNode, findings: &mut Vec<Finding>) {
+    match node {
+        AstNode::Environment { bindings, .. } => {
+            for binding in bindings {
+                find_unused(binding, findings);
+            }
+        },
+        AstNode::Let { name, value: Some(value), .. } => {
+            if let AstNode::Function { params, body, r#return, span } = value.as_ref() {
+                check_function(name, *span, params, body, r#return, findings);
+            }
+            find_unused(value, findings);
+        },
+        AstNode::Function { body, r#return, .. } => {
+            find_unused(body, findings);
+            find_unused(r#return, findings);
+        },
+        _ => (),
+    }
+}
+
+/// Checks the parameters and body bindings of a single function
+///
+/// The references of each statement are collected once, counting the statements referring to each name.
+/// Unused parameters are reported at the name of the function, as the parser records no spans for parameters.
+fn check_function(name: &str, span: Option<Span>, params: &AstNode, body: &AstNode, r#return: &AstNode, findings: &mut Vec<Finding>) {
+    let statements = body.get_bindings().unwrap_or_default();
+
+    let mut uses: HashMap<Rc<str>, usize> = HashMap::new();
+    let mut refers_to_itself: Vec<bool> = Vec::new();
+    for statement in statements.iter().map(|statement| statement.as_ref()).chain([r#return]) {
+        let mut used: HashSet<Rc<str>> = HashSet::new();
+        collect_references(statement, &mut used);
+        refers_to_itself.push(matches!(statement, AstNode::Let { name: binding, .. } if used.contains(binding)));
+        for name in used {
+            *uses.entry(name).or_default() += 1;
+        }
+    }
+
+    for param in params.get_params().unwrap_or_default() {
+        if let AstNode::Identifier(param) = param.as_ref() {
+            if !uses.contains_key(param) {
+                findings.push(Finding::new(UNUSED_PARAMETER, vec![param.to_string(), name.to_string()], span));
+            }
+        }
+    }
+
+    for (statement, refers_to_itself) in statements.iter().zip(refers_to_itself) {
+        if let AstNode::Let { name: binding, span: binding_span, .. } = statement.as_ref() {
+            // A binding referring to itself does not count as a use
+            let others = uses.get(binding).copied().unwrap_or(0) - usize::from(refers_to_itself);
+            if others == 0 {
+                findings.push(Finding::new(UNUSED_BINDING, vec![binding.to_string(), name.to_string()], *binding_span));
+            }
+        }
+    }
+}
+
+/// Finds bindings shadowing a name declared earlier in an enclosing environment
+///
+/// Each entry of `scopes` holds the names declared so far in one enclosing environment, with the innermost environment last.
+/// Function parameters form a scope of their own, enclosing the function body.
+fn find_shadowing(node: &AstNode, scopes: &mut Vec<HashSet<Rc<str>>>, findings: &mut Vec<Finding>) {
+    match node {
+        AstNode::Environment { bindings, .. } => {
+            scopes.push(HashSet::new());
+            for binding in bindings {
+                find_shadowing(binding, scopes, findings);
+            }
+            scopes.pop();
+        },
+        AstNode::Let { name, value, span, .. } => {
+            let (current, enclosing) = match scopes.split_last_mut() {
+                Some(split) => split,
+                None => return,
+            };
+            if enclosing.iter().any(|scope| scope.contains(name)) {
+                findings.push(Finding::new(SHADOWING, vec![name.to_string()], *span));
+            }
+            current.insert(name.clone());
+            if let Some(value) = value {
+                find_shadowing(value, scopes, findings);
+            }
+        },
+        AstNode::Function { params, body, r#return, .. } => {
+            let names = params.get_params().unwrap_or_default().iter()
+                .filter_map(|param| match param.as_ref() {
+                    AstNode::Identifier(name) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect();
+            scopes.push(names);
+            find_shadowing(body, scopes, findings);
+            find_shadowing(r#return, scopes, findings);
+            scopes.pop();
+        },
+        _ => (),
+    }
+}
+
+/// Finds logical operations with a number or a string literal as an operand
+///
+/// `span` is the span of the innermost binding enclosing the node.
+fn find_implicit_truthiness(node: &AstNode, span: Option<Span>, findings: &mut Vec<Finding>) {
+    let is_logical = |op: &Operators| matches!(op, Operators::Logical(_));
+    let mut check_operand = |operand: &AstNode, op: &Operators| {
+        let literal = match operand {
+            AstNode::Integer(num) => num.to_string(),
+            AstNode::Float(num) => format!("{:?}", num),
+            AstNode::String(s) => format!("\"{}\"", escape_string(s)),
+            _ => return,
+        };
+        findings.push(Finding::new(IMPLICIT_TRUTHINESS, vec![literal, op.to_string()], span));
+    };
+    match node {
+        AstNode::UnaryOp { op, operand } => {
+            if *op == Operators::Logical(LogicalOperators::NOT) {
+                check_operand(operand, op);
+            }
+            find_implicit_truthiness(operand, span, findings);
+        },
+        AstNode::BinaryOp { left, operator, right } => {
+            if is_logical(operator) {
+                check_operand(left, operator);
+                check_operand(right, operator);
+            }
+            find_implicit_truthiness(left, span, findings);
+            find_implicit_truthiness(right, span, findings);
+        },
+        AstNode::Environment { bindings, .. } => {
+            for binding in bindings {
+                find_implicit_truthiness(binding, span, findings);
+            }
+        },
+        AstNode::Let { value: Some(value), span: binding, .. } => find_implicit_truthiness(value, binding.or(span), findings),
+        AstNode::Function { body, r#return, .. } => {
+            find_implicit_truthiness(body, span, findings);
+            find_implicit_truthiness(r#return, span, findings);
+        },
+        _ => (),
+    }
+}
+
+/// Finds anonymous values that can never be accessed
+///
+/// An anonymous value is the value of an environment if it is the only element of an environment in a value position, i.e. assigned with `let` or returned from a function (`value` is `true`).
+/// In all other environments, including the global environment and function bodies, anonymous values are unreachable.
+/// `span` is the span of the innermost binding enclosing the node.
+fn find_anonymous_values(node: &AstNode, value: bool, span: Option<Span>, findings: &mut Vec<Finding>) {
+    match node {
+        AstNode::Environment { bindings, .. } => {
+            let is_value = value && bindings.len() == 1;
+            for binding in bindings {
+                if binding.is_anonymous_value() && !is_value {
+                    findings.push(Finding::new(ANONYMOUS_VALUE, vec![print_expression(binding)], span));
+                }
+                find_anonymous_values(binding, false, span, findings);
+            }
+        },
+        AstNode::Let { value: Some(value), span: binding, .. } => find_anonymous_values(value, true, binding.or(span), findings),
+        AstNode::Function { body, r#return, .. } => {
+            find_anonymous_values(body, false, span, findings);
+            find_anonymous_values(r#return, true, span, findings);
+        },
+        _ => (),
+    }
+}
+
+/// Scripts whose letters can be confused with each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+}
+
+impl ToString for Script {
+    fn to_string(&self) -> String {
+        match self {
+            Script::Latin => "Latin".to_string(),
+            Script::Greek => "Greek".to_string(),
+            Script::Cyrillic => "Cyrillic".to_string(),
+        }
+    }
+}
+
+/// Gets the script of a letter, if it is Latin, Greek, or Cyrillic
+fn script(c: char) -> Option<Script> {
+    match c {
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}' if c.is_alphabetic() => Some(Script::Latin),
+        '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' if c.is_alphabetic() => Some(Script::Greek),
+        '\u{0400}'..='\u{052F}' if c.is_alphabetic() => Some(Script::Cyrillic),
+        _ => None,
+    }
+}
+
+/// Finds identifiers mixing scripts or made up of confusable letters, and string literals with bidirectional control characters
+///
+/// Each identifier is checked once, at its first occurrence.
+fn find_suspicious_unicode(ast: &AstNode, findings: &mut Vec<Finding>) {
+    let mut names: Vec<(Rc<str>, Option<Span>)> = Vec::new();
+    let mut strings: Vec<(Rc<str>, Option<Span>)> = Vec::new();
+    collect_text(ast, None, &mut names, &mut strings);
+
+    let mut seen: HashSet<Rc<str>> = HashSet::new();
+    for (name, span) in names {
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        let mut scripts: Vec<Script> = Vec::new();
+        for s in name.chars().filter_map(script) {
+            if !scripts.contains(&s) {
+                scripts.push(s);
+            }
+        }
+        match scripts.as_slice() {
+            [first, second, ..] =>
+                findings.push(Finding::new(MIXED_SCRIPTS, vec![name.to_string(), first.to_string(), second.to_string()], span)),
+            [Script::Greek] | [Script::Cyrillic] => {
+                let lookalike: Option<String> = name.chars()
+                    .map(|c| match script(c) {
+                        Some(_) => CONFUSABLES.iter().find(|(confusable, _)| *confusable == c).map(|(_, latin)| *latin),
+                        None => Some(c),
+                    })
+                    .collect();
+                if let Some(lookalike) = lookalike {
+                    findings.push(Finding::new(CONFUSABLE_IDENTIFIER, vec![name.to_string(), lookalike], span));
+                }
+            },
+            _ => (),
+        }
+    }
+
+    for (string, span) in strings {
+        if let Some(control) = string.chars().find(|c| BIDI_CONTROLS.contains(c)) {
+            findings.push(Finding::new(BIDI_CONTROL, vec![format!("U+{:04X}", control as u32)], span));
+        }
+    }
+}
+
+/// Collects the identifiers and string literals of a subtree, in source order, with the span of the innermost binding enclosing them
+fn collect_text(node: &AstNode, span: Option<Span>, names: &mut Vec<(Rc<str>, Option<Span>)>, strings: &mut Vec<(Rc<str>, Option<Span>)>) {
+    match node {
+        AstNode::Identifier(name) => names.push((name.clone(), span)),
+        AstNode::String(s) => strings.push((s.clone(), span)),
+        AstNode::Environment { name, bindings, .. } => {
+            if let Some(name) = name {
+                names.push((name.clone(), span));
+            }
+            for binding in bindings {
+                collect_text(binding, span, names, strings);
+            }
+        },
+        AstNode::UnaryOp { operand, .. } => collect_text(operand, span, names, strings),
+        AstNode::BinaryOp { left, right, .. } => {
+            collect_text(left, span, names, strings);
+            collect_text(right, span, names, strings);
+        },
+        AstNode::Let { name, value, inherit, span: binding } => {
+            let span = binding.or(span);
+            names.push((name.clone(), span));
+            if let Some(inherit) = inherit {
+                collect_text(inherit, span, names, strings);
+            }
+            if let Some(value) = value {
+                collect_text(value, span, names, strings);
+            }
+        },
+        AstNode::Inherit { names: Some(inherited) } => names.extend(inherited.iter().map(|name| (name.clone(), span))),
+        AstNode::Function { params, body, r#return, .. } => {
+            collect_text(params, span, names, strings);
+            collect_text(body, span, names, strings);
+            collect_text(r#return, span, names, strings);
+        },
+        AstNode::FunctionArgs(args) => {
+            for arg in args {
+                collect_text(arg, span, names, strings);
+            }
+        },
+        AstNode::FunctionCall { id, args, .. } => {
+            collect_text(id, span, names, strings);
+            collect_text(args, span, names, strings);
+        },
+        AstNode::Integer(_)
+        | AstNode::Float(_)
+        | AstNode::Boolean(_)
+        | AstNode::Inherit { names: None }
+        | AstNode::Error(_) => (),
+    }
+}
+
+/// Collects the names referred to in a subtree
+///
+/// The right-hand side of an accession names an element of the left-hand side, and is therefore not a reference.
+fn collect_references(node: &AstNode, used: &mut HashSet<Rc<str>>) {
+    match node {
+        AstNode::Identifier(name) => {
+            used.insert(name.clone());
+        },
+        AstNode::Environment { bindings, .. } => {
+            for binding in bindings {
+                collect_references(binding, used);
+            }
+        },
+        AstNode::UnaryOp { operand, .. } => collect_references(operand, used),
+        AstNode::BinaryOp { left, operator: Operators::Other(OtherOperators::ACCESSOR), .. } => collect_references(left, used),
+        AstNode::BinaryOp { left, right, .. } => {
+            collect_references(left, used);
+            collect_references(right, used);
+        },
+        AstNode::Let { value, inherit, .. } => {
+            if let Some(value) = value {
+                collect_references(value, used);
+            }
+            if let Some(inherit) = inherit {
+                collect_references(inherit, used);
+            }
+        },
+        AstNode::Inherit { names: Some(names) } => {
+            for name in names {
+                used.insert(name.clone());
+            }
+        },
+        // Parameters are declarations, not references
+        AstNode::Function { body, r#return, .. } => {
+            collect_references(body, used);
+            collect_references(r#return, used);
+        },
+        AstNode::FunctionArgs(args) => {
+            for arg in args {
+                collect_references(arg, used);
+            }
+        },
+        AstNode::FunctionCall { id, args, .. } => {
+            collect_references(id, used);
+            collect_references(args, used);
+        },
+        AstNode::Integer(_)
+        | AstNode::Float(_)
+        | AstNode::Boolean(_)
+        | AstNode::String(_)
+        | AstNode::Inherit { names: None }
+        | AstNode::Error(_) => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::unicodesegmenters::segment_graphemes;
+
+    fn parse(source: &str) -> AstNode {
+        let tokens = Lexer::new(segment_graphemes(source)).tokenize().unwrap();
+        return Parser::new(tokens).parse().unwrap();
+    }
+
+    #[test]
+    fn reports_unused_parameters_and_bindings() {
+        let ast = parse("let fun f[a, b] = { let c = a; let d = b; return c; }\n");
+        let diagnostics = lint(&ast, &Config::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].code, Some("W0001"));
+        assert_eq!(diagnostics[0].message, "Unused binding 'd' in function 'f'");
+
+        let ast = parse("let fun f[a, b] = { return a; }\n");
+        let diagnostics = lint(&ast, &Config::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some("W0002"));
+        assert_eq!(diagnostics[0].message, "Unused parameter 'b' in function 'f'");
+    }
+
+    #[test]
+    fn points_at_declarations() {
+        let located = |source: &str| Parser::with_locations(Lexer::from_text(source).tokenize_with_locations().unwrap()).parse().unwrap();
+        let spans = |source: &str| lint(&located(source), &Config::default()).iter().map(|d| (d.code.unwrap(), d.span)).collect::<Vec<_>>();
+
+        // Unused parameters point at the function, and everything else at the innermost binding
+        assert_eq!(spans("let fun f[a, b] = { let c = a; let d = c; return c; }\n"), vec![
+            ("W0002", Some(Span::new(8, 9))),
+            ("W0001", Some(Span::new(35, 36))),
+        ]);
+        assert_eq!(spans("let x = 1;\nlet y = { let x = !5; };\n"), vec![
+            ("W0003", Some(Span::new(25, 26))),
+            ("W0004", Some(Span::new(25, 26))),
+        ]);
+        assert_eq!(spans("let vаlue = \"\u{202E}\";\nlet e = { 5; 6; };\n"), vec![
+            ("W0005", Some(Span::new(4, 10))),
+            ("W0007", Some(Span::new(4, 10))),
+            ("W0008", Some(Span::new(24, 25))),
+            ("W0008", Some(Span::new(24, 25))),
+        ]);
+
+        // Without source locations, the spans are unknown
+        assert_eq!(lint(&parse("let fun f[a] = { return 1; }\n"), &Config::default())[0].span, None);
+    }
+
+    #[test]
+    fn self_references_are_not_uses() {
+        let ast = parse("let fun f[a] = { let b = b + a; let c = a; return c; }\n");
+        let diagnostics = lint(&ast, &Config::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Unused binding 'b' in function 'f'");
+    }
+
+    #[test]
+    fn accessions_and_inheritance_are_uses() {
+        let ast = parse("let fun f[a, b] = { let c inherit (b) = a.d; return c; }\n");
+        assert!(lint(&ast, &Config::default()).is_empty());
+    }
+
+    #[test]
+    fn top_level_bindings_are_never_unused() {
+        let ast = parse("let x = 1;\nlet y = { let z = 2; };\n");
+        assert!(lint(&ast, &Config::default()).is_empty());
+    }
+
+    #[test]
+    fn lint_levels_are_honored() {
+        let ast = parse("let fun f[a] = { return 1; }\n");
+
+        let mut config = Config::default();
+        config.lints.insert("unused-parameter".to_string(), LintLevel::Allow);
+        assert!(lint(&ast, &config).is_empty());
+
+        let mut config = Config::default();
+        config.lints.insert("W0002".to_string(), LintLevel::Deny);
+        assert_eq!(lint(&ast, &config)[0].severity, Severity::Error);
+
+        let config = Config { deny_warnings: true, ..Config::default() };
+        assert_eq!(lint(&ast, &config)[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn reports_shadowing() {
+        let ast = parse("let x = 1;\nlet y = { let x = 2; };\n");
+        let diagnostics = lint(&ast, &Config::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some("W0003"));
+        assert_eq!(diagnostics[0].message, "Binding 'x' shadows a binding of an enclosing environment");
+
+        let ast = parse("let fun f[a] = { let a = 1; return a; }\n");
+        assert_eq!(lint(&ast, &Config::default())[0].code, Some("W0003"));
+
+        let ast = parse("let y = { let x = 2; };\nlet x = 1;\n");
+        assert!(lint(&ast, &Config::default()).is_empty());
+    }
+
+    #[test]
+    fn reports_implicit_truthiness() {
+        let ast = parse("let x = !5;\nlet y = !true;\n");
+        let diagnostics = lint(&ast, &Config::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some("W0004"));
+        assert_eq!(diagnostics[0].message, "Implicit truthiness of '5' in logical operation '!'");
+    }
+
+    #[test]
+    fn strict_mode_denies_strict_lints() {
+        let ast = parse("let x = !5;\nlet fun f[a] = { return 1; }\n");
+        let config = Config { strict: true, ..Config::default() };
+        let diagnostics = lint(&ast, &config);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().any(|d| d.code == Some("W0004") && d.severity == Severity::Error));
+        assert!(diagnostics.iter().any(|d| d.code == Some("W0002") && d.severity == Severity::Warning));
+
+        let mut config = config;
+        config.lints.insert("implicit-truthiness".to_string(), LintLevel::Warn);
+        assert!(lint(&ast, &config).iter().all(|d| d.severity == Severity::Warning));
+    }
+
+    #[test]
+    fn reports_mixed_scripts_and_confusables() {
+        // The 'а' of the second binding is Cyrillic
+        let ast = parse("let value = 1;\nlet vаlue = 2;\nlet x = vаlue;\n");
+        let diagnostics = lint(&ast, &Config::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some("W0005"));
+        assert_eq!(diagnostics[0].message, "Identifier 'vаlue' mixes Latin and Cyrillic letters");
+
+        let ast = parse("let рор = 1;\nlet знак = 2;\nlet größe = 3;\n");
+        let diagnostics = lint(&ast, &Config::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some("W0006"));
+        assert_eq!(diagnostics[0].message, "Identifier 'рор' looks like the Latin identifier 'pop'");
+    }
+
+    #[test]
+    fn reports_anonymous_values() {
+        let ast = parse("5;\nlet x = { let y = 1; a + 2; };\nlet fun f[a] = { \"b\"; return a; }\n");
+        let diagnostics = lint(&ast, &Config::default());
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(messages, vec![
+            "Anonymous value '5' can never be accessed",
+            "Anonymous value 'a + 2' can never be accessed",
+            "Anonymous value '\"b\"' can never be accessed",
+        ]);
+        assert!(diagnostics.iter().all(|d| d.code == Some("W0008")));
+
+        let ast = parse("let x = { 5; };\nlet y = x;\nlet fun f[a] = { return a + 1; }\nf[y];\n");
+        assert!(lint(&ast, &Config::default()).is_empty());
+    }
+
+    #[test]
+    fn reports_bidi_controls_in_strings() {
+        let ast = parse("let access = \"user\u{202E} \u{2066}// admin\u{2069}\u{2066}\";\nlet fine = \"user\";\n");
+        let config = Config { strict: true, ..Config::default() };
+        let diagnostics = lint(&ast, &config);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some("W0007"));
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].message, "String literal contains the bidirectional control character U+202E");
+    }
+}
@@ -1,39 +1,488 @@
-use envlang;
+// Explicit `return`s are the house style of this crate.
+#![allow(clippy::needless_return)]
+
+use envlang::config::{find_config_file, quote, Config, ConfigError, LintLevel};
+use envlang::diagnostic::{call_stack, limit, Diagnostic, Severity};
+use envlang::error::EnvlangError;
+use envlang::io::{ExtensionPolicy, SourceFile};
+use envlang::lexer::{Location, Token};
+use envlang::lint::{find_lint, LINTS};
+use envlang::evaluator::Evaluator;
+use envlang::parser::ParseStep;
+use envlang::printer::print_ast;
+use envlang::snippet::render;
 use std::env;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "Usage: envlang [OPTIONS] <path/to/file.envl>\n       envlang info [OPTIONS]\n       envlang explain-parse [OPTIONS] <path/to/file.envl>";
 
 /// The Envlang command-line interface binary
-/// 
+///
 /// Envlang is written in `.envl` files and executed using the CLI tool.
-/// 
+///
 /// # Usage
 /// Once compiled and accessible from the terminal, envlang is run with a simple one-argument command:
 /// ```text
 /// envlang path/to/file.envl
 /// ```
-/// 
+/// The script is lexed, parsed, linted, and evaluated, and the resulting global environment is printed to standard output as Envlang source.
+/// Diagnostics are printed to standard error.
+/// Runtime errors point at the innermost binding or call they occurred in, and errors inside functions are followed by a note for each call that was being evaluated, innermost first, pointing at the call with a label at the declaration of the called function.
+///
+/// # Options
+/// Project-wide settings are read from the nearest `envlang.toml` (see [`envlang::config`]). The following flags override them:
+/// - `--config <path>`: Read settings from the given file instead of searching for `envlang.toml`
+/// - `--no-config`: Do not read any configuration file
+/// - `--strict` / `--no-strict`: Enable or disable strict mode
+/// - `--strict-braces` / `--no-strict-braces`: Report or ignore unbalanced braces (reported by default)
+/// - `--allow <lint>`, `--warn <lint>`, `--deny <lint>`: Set the level of a lint (by name or code, see [`envlang::lint`])
+/// - `--deny-warnings`: Report all warnings as errors
+/// - `--module-path <dir>`: Search the directory for imported modules (before any configured paths)
+/// - `--max-file-size <bytes>`: Refuse to run scripts larger than the given size
+/// - `--max-diagnostics <count>`: Report at most the given number of diagnostics
+/// - `--max-string-length <bytes>`: Reject string literals longer than the given size
+/// - `--max-number-digits <count>`: Reject number literals with more than the given number of digits
+/// - `--ext <extension>`: Also accept scripts with the given file extension (in addition to `.envl`)
+/// - `--no-ext-check`: Accept scripts regardless of their file extension
+///
+/// # Reporting the configuration
+/// `envlang info [OPTIONS]` prints the effective configuration in the current directory, after applying the options, in the TOML format of `envlang.toml`, preceded by comments with the version and features of the binary.
+/// The output is meant to be attached to bug reports and CI logs.
+///
+/// # Explaining the parse of a script
+/// `envlang explain-parse [OPTIONS] <path/to/file.envl>` prints each top-level statement of the script, followed by the steps the parser took to parse it:
+/// the tokens it consumed (without whitespace), the constructs it entered and exited, and how it grouped the operands of each operator.
+/// If the script cannot be parsed, the steps up to the error are printed before the error.
+///
 /// # Errors
 /// The following exit codes are defined:
 /// - `1`: No arguments given to Envlang
 /// - `2`: Too many arguments given to Envlang
+/// - `3`: Invalid command-line option
+/// - `4`: Invalid configuration file
+/// - `5`: The script exceeds the configured maximum file size
+/// - `6`: Lints were reported as errors (e.g. with `--deny-warnings`)
+/// - `7`: The script could not be read (e.g. it does not exist or has an invalid extension)
+/// - `8`: The script could not be lexed (e.g. it has an unterminated string)
+/// - `9`: The script could not be parsed (e.g. it has an unmatched brace)
+/// - `10`: The script could not be evaluated (e.g. it uses an undefined identifier)
 /// - `101`: General Rust panic (see stack trace for more information)
 fn main() {
     let args: Vec<String> = env::args().collect();
 
     // Exit with error code 1 when there are no arguments
     if args.len() < 2 {
-        eprintln!("Usage: envlang <path/to/file.envl>");
+        eprintln!("{}", USAGE);
+        std::process::exit(1);
+    }
+
+    let subcommand = ["info", "explain-parse"].into_iter().find(|command| args[1] == *command);
+    let cli = match CliArgs::parse(&args[if subcommand.is_some() { 2 } else { 1 }..]) {
+        Ok(cli) => cli,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("{}", USAGE);
+            std::process::exit(3);
+        },
+    };
+
+    // Report the effective configuration of the current directory, which takes no script
+    if subcommand == Some("info") {
+        if !cli.files.is_empty() {
+            eprintln!("Too many arguments");
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+        let dir = std::env::current_dir().unwrap_or_default();
+        match cli.load_config(&dir) {
+            Ok(config) => print!("{}", cli.info(&dir, &config)),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(4);
+            },
+        }
+        return;
+    }
+
+    // Exit with error code 1 when only options were given
+    if cli.files.is_empty() {
+        eprintln!("{}", USAGE);
         std::process::exit(1);
     }
-    
+
     // Exit with error code 2 when there are too many arguments
-    if args.len() > 2 {
+    if cli.files.len() > 1 {
         eprintln!("Too many arguments");
-        eprintln!("Usage: envlang <path/to/file.envl>");
+        eprintln!("{}", USAGE);
         std::process::exit(2);
     }
 
-    // Read in file
-    let file_path = &args[1];
-    let env_file = envlang::io::read_file(file_path).unwrap(); // TODO: Handle errors
-    dbg!(env_file);
+    let file_path = &cli.files[0];
+    let config = match cli.load_config(Path::new(file_path).parent().unwrap_or(Path::new(""))) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(4);
+        },
+    };
+
+    // Exit with error code 5 when the script is larger than allowed
+    if let Some(max) = config.limits.max_file_size {
+        if let Ok(metadata) = std::fs::metadata(file_path) {
+            if metadata.len() > max {
+                eprintln!("File '{}' is {} bytes, which exceeds the maximum file size of {} bytes", file_path, metadata.len(), max);
+                std::process::exit(5);
+            }
+        }
+    }
+
+    // Read in file, exiting with error code 7 if it cannot be read
+    let env_file = match envlang::io::read_file_with(file_path, &config.extensions) {
+        Ok(env_file) => env_file,
+        Err(err) => {
+            eprintln!("{}", envlang::error::EnvlangError::from(err));
+            std::process::exit(7);
+        },
+    };
+
+    let source = SourceFile::new(file_path.as_str(), env_file.as_str());
+    let fail = |err: EnvlangError| -> ! {
+        let diagnostic = Diagnostic::from_error_with(&err, Some(&source), &config.messages);
+        eprint!("{}", render(&diagnostic, &source));
+        std::process::exit(exit_code(&err));
+    };
+    if subcommand == Some("explain-parse") {
+        let (report, result) = explain_parse(&env_file, &config);
+        print!("{}", report);
+        if let Err(err) = result {
+            fail(err);
+        }
+        return;
+    }
+
+    // Lex and parse the script, and report lints, exiting with error code 6 if any of them are errors
+    let ast = envlang::parse_source_with_config(&env_file, &config).unwrap_or_else(|err| fail(err));
+    let diagnostics = envlang::check_ast(&ast, &config);
+    for diagnostic in &diagnostics {
+        eprint!("{}", render(diagnostic, &source));
+    }
+    if diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error) {
+        std::process::exit(6);
+    }
+
+    // Evaluate the script and print its global environment, reporting the call stack of runtime errors
+    let mut evaluator = Evaluator::new();
+    let env = match evaluator.evaluate(&ast) {
+        Ok(env) => env,
+        Err(err) => {
+            let err = EnvlangError::from(err);
+            eprint!("{}", render(&Diagnostic::from_error_with(&err, Some(&source), &config.messages), &source));
+            let notes = call_stack(evaluator.call_stack(), &config.messages);
+            for note in limit(notes, config.limits.max_diagnostics, &config.messages) {
+                eprint!("{}", render(&note, &source));
+            }
+            std::process::exit(exit_code(&err));
+        },
+    };
+    print!("{}", print_ast(&env.to_node()));
+}
+
+/// Gets the exit code for an error that stopped a script (see the exit codes of [`CliArgs`])
+fn exit_code(err: &EnvlangError) -> i32 {
+    match err {
+        EnvlangError::Io(_) => return 7,
+        EnvlangError::Lexer(_) => return 8,
+        EnvlangError::Parser(_) => return 9,
+        EnvlangError::Runtime(_) => return 10,
+    }
+}
+
+/// Renders the report of `envlang explain-parse`: each top-level statement of a script, followed by the parser steps taken for it
+///
+/// The script is lexed and parsed with the operator aliases, literal size limits, and brace checking of the configuration.
+/// Returns the report together with the result of parsing; if parsing fails, the report ends with the steps of the failing statement.
+fn explain_parse(text: &str, config: &Config) -> (String, Result<(), EnvlangError>) {
+    let mut parser = match envlang::parser_with_config(text, config) {
+        Ok(parser) => parser.with_tracing(),
+        Err(err) => return (String::new(), Err(err)),
+    };
+    let result = parser.parse().map(|_| ()).map_err(EnvlangError::from);
+    let steps = parser.take_trace();
+
+    // Group the steps by top-level statement: a statement starts at a construct or a token in the global environment, and ends at a `;` or at the end of a construct there.
+    // Closing tokens never start a statement, e.g., the brace closing a function body after its return statement.
+    let mut statements: Vec<Vec<(usize, &ParseStep)>> = Vec::new();
+    let mut depth = 0;
+    let mut closed = true;
+    for step in &steps {
+        match step {
+            ParseStep::Enter(_) => {
+                if depth == 1 && closed {
+                    statements.push(Vec::new());
+                    closed = false;
+                }
+                if depth > 0 {
+                    statements.last_mut().into_iter().for_each(|statement| statement.push((depth - 1, step)));
+                }
+                depth += 1;
+            },
+            ParseStep::Exit(_) => {
+                depth -= 1;
+                if depth > 0 {
+                    statements.last_mut().into_iter().for_each(|statement| statement.push((depth - 1, step)));
+                }
+                if depth == 1 {
+                    closed = true;
+                }
+            },
+            ParseStep::Token(_, Token::Whitespace(_) | Token::EOF) => (),
+            ParseStep::Token(_, token) if depth == 1 => {
+                if closed && !matches!(token, Token::LineTerminator | Token::RightBrace) {
+                    statements.push(Vec::new());
+                    closed = false;
+                }
+                statements.last_mut().into_iter().for_each(|statement| statement.push((0, step)));
+                if *token == Token::LineTerminator {
+                    closed = true;
+                }
+            },
+            _ => statements.last_mut().into_iter().for_each(|statement| statement.push((depth.saturating_sub(1), step))),
+        }
+    }
+
+    let position = |location: &Location| match location.column {
+        Some(column) => format!("line {}, column {}", location.line, column),
+        None => format!("line {}", location.line),
+    };
+    let mut report = String::new();
+    for (idx, statement) in statements.iter().enumerate() {
+        let spans: Vec<(usize, usize)> = statement.iter()
+            .filter_map(|(_, step)| match step {
+                ParseStep::Token(location, _) => location.span.map(|span| (span.start, span.end)),
+                _ => None,
+            })
+            .collect();
+        let start = spans.iter().map(|span| span.0).min().unwrap_or(0);
+        let end = spans.iter().map(|span| span.1).max().unwrap_or(0);
+        let line = statement.iter().find_map(|(_, step)| match step {
+            ParseStep::Token(location, _) | ParseStep::Decision(location, _) => Some(location.line),
+            _ => None,
+        });
+
+        if idx > 0 {
+            report.push('\n');
+        }
+        match line {
+            Some(line) => report.push_str(&format!("statement {} (line {}):\n", idx + 1, line)),
+            None => report.push_str(&format!("statement {}:\n", idx + 1)),
+        }
+        for source_line in text.get(start..end).unwrap_or_default().lines() {
+            report.push_str(&format!("  | {}\n", source_line));
+        }
+        for (depth, step) in statement {
+            let indent = "  ".repeat(depth + 1);
+            match step {
+                ParseStep::Token(location, token) => report.push_str(&format!("{}token '{}' at {}\n", indent, token.to_string(), position(location))),
+                ParseStep::Enter(construct) => report.push_str(&format!("{}enter {}\n", indent, construct)),
+                ParseStep::Exit(construct) => report.push_str(&format!("{}exit {}\n", indent, construct)),
+                ParseStep::Decision(location, decision) => report.push_str(&format!("{}decide at {}: {}\n", indent, position(location), decision)),
+            }
+        }
+    }
+    return (report, result);
+}
+
+/// Command-line arguments given to the binary
+///
+/// Every `Option`al setting is `None` unless given on the command line, in which case it overrides the configuration file.
+#[derive(Debug, Default)]
+struct CliArgs {
+    files: Vec<String>,
+    config_path: Option<PathBuf>,
+    no_config: bool,
+    strict: Option<bool>,
+    strict_braces: Option<bool>,
+    deny_warnings: bool,
+    lints: Vec<(String, LintLevel)>,
+    module_paths: Vec<PathBuf>,
+    max_file_size: Option<u64>,
+    max_diagnostics: Option<usize>,
+    max_string_length: Option<usize>,
+    max_number_digits: Option<usize>,
+    extensions: Vec<String>,
+    no_ext_check: bool,
+}
+
+impl CliArgs {
+    /// Parses the command-line arguments (excluding the binary name)
+    ///
+    /// # Errors
+    /// Returns a human-readable message if an option or lint is unknown, or an option is missing its value.
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut cli = CliArgs::default();
+        let mut iter = args.iter();
+        let known_lint = |name: String| match find_lint(&name) {
+            Some(_) => Ok(name),
+            None => Err(format!("Unknown lint '{}'", name)),
+        };
+        while let Some(arg) = iter.next() {
+            let mut value = |option: &str| iter.next()
+                .cloned()
+                .ok_or_else(|| format!("Option '{}' requires a value", option));
+            match arg.as_str() {
+                "--config" => cli.config_path = Some(PathBuf::from(value(arg)?)),
+                "--no-config" => cli.no_config = true,
+                "--strict" => cli.strict = Some(true),
+                "--no-strict" => cli.strict = Some(false),
+                "--strict-braces" => cli.strict_braces = Some(true),
+                "--no-strict-braces" => cli.strict_braces = Some(false),
+                "--deny-warnings" => cli.deny_warnings = true,
+                "--allow" => cli.lints.push((known_lint(value(arg)?)?, LintLevel::Allow)),
+                "--warn" => cli.lints.push((known_lint(value(arg)?)?, LintLevel::Warn)),
+                "--deny" => cli.lints.push((known_lint(value(arg)?)?, LintLevel::Deny)),
+                "--module-path" => cli.module_paths.push(PathBuf::from(value(arg)?)),
+                "--max-file-size" => {
+                    let raw = value(arg)?;
+                    let bytes = raw.parse::<u64>().map_err(|_| format!("Invalid value '{}' for option '{}'", raw, arg))?;
+                    cli.max_file_size = Some(bytes);
+                },
+                "--max-diagnostics" => {
+                    let raw = value(arg)?;
+                    let count = raw.parse::<usize>().map_err(|_| format!("Invalid value '{}' for option '{}'", raw, arg))?;
+                    cli.max_diagnostics = Some(count);
+                },
+                "--max-string-length" => {
+                    let raw = value(arg)?;
+                    let bytes = raw.parse::<usize>().map_err(|_| format!("Invalid value '{}' for option '{}'", raw, arg))?;
+                    cli.max_string_length = Some(bytes);
+                },
+                "--max-number-digits" => {
+                    let raw = value(arg)?;
+                    let count = raw.parse::<usize>().map_err(|_| format!("Invalid value '{}' for option '{}'", raw, arg))?;
+                    cli.max_number_digits = Some(count);
+                },
+                "--ext" => cli.extensions.push(value(arg)?),
+                "--no-ext-check" => cli.no_ext_check = true,
+                option if option.starts_with("--") => return Err(format!("Unknown option '{}'", option)),
+                _ => cli.files.push(arg.clone()),
+            }
+        }
+        return Ok(cli);
+    }
+
+    /// Gets the configuration file used for scripts in a directory, or `None` if no file is read
+    fn config_file(&self, dir: &Path) -> Option<PathBuf> {
+        if self.no_config {
+            return None;
+        }
+        if let Some(path) = &self.config_path {
+            return Some(path.clone());
+        }
+        let start = std::fs::canonicalize(dir).unwrap_or(dir.to_path_buf());
+        return find_config_file(&start);
+    }
+
+    /// Loads the project configuration for scripts in a directory and applies the command-line overrides on top of it
+    ///
+    /// # Errors
+    /// Any errors from reading or parsing the configuration file.
+    fn load_config(&self, dir: &Path) -> Result<Config, ConfigError> {
+        let mut config = match self.config_file(dir) {
+            Some(path) => Config::load(&path)?,
+            None => Config::default(),
+        };
+
+        if let Some(strict) = self.strict {
+            config.strict = strict;
+        }
+        // Unbalanced braces are reported unless disabled in the configuration file or on the command line
+        config.strict_braces = Some(self.strict_braces.or(config.strict_braces).unwrap_or(true));
+        if self.deny_warnings {
+            config.deny_warnings = true;
+        }
+        for (lint, level) in &self.lints {
+            config.lints.insert(lint.clone(), *level);
+        }
+        if !self.module_paths.is_empty() {
+            let mut module_paths = self.module_paths.clone();
+            module_paths.append(&mut config.module_paths);
+            config.module_paths = module_paths;
+        }
+        if self.max_file_size.is_some() {
+            config.limits.max_file_size = self.max_file_size;
+        }
+        if self.max_diagnostics.is_some() {
+            config.limits.max_diagnostics = self.max_diagnostics;
+        }
+        if self.max_string_length.is_some() {
+            config.limits.max_string_length = self.max_string_length;
+        }
+        if self.max_number_digits.is_some() {
+            config.limits.max_number_digits = self.max_number_digits;
+        }
+        for extension in &self.extensions {
+            config.extensions.allow(extension);
+        }
+        if self.no_ext_check {
+            config.extensions = ExtensionPolicy::Any;
+        }
+        return Ok(config);
+    }
+
+    /// Renders the report of `envlang info` for the configuration of a directory
+    ///
+    /// The settings are written with the tables and keys of `envlang.toml`, with every lint at its effective level, and unlimited limits left out, so that the report can be read back as a configuration file.
+    /// The version and features of the binary and the path of the configuration file are written as comments.
+    fn info(&self, dir: &Path, config: &Config) -> String {
+        let quoted = |items: Vec<String>| format!("[{}]", items.iter().map(|item| quote(item)).collect::<Vec<String>>().join(", "));
+        let mut features = vec!["std"];
+        if cfg!(feature = "testing") {
+            features.push("testing");
+        }
+
+        let mut output = format!("# envlang {}\n", env!("CARGO_PKG_VERSION"));
+        output.push_str(&format!("# features: {}\n", features.join(", ")));
+        if let Some(path) = self.config_file(dir) {
+            output.push_str(&format!("# config file: {}\n", path.display()));
+        }
+
+        output.push_str(&format!("\nstrict = {}\n", config.strict));
+        output.push_str(&format!("strict-braces = {}\n", config.strict_braces.unwrap_or(true)));
+        output.push_str(&format!("deny-warnings = {}\n", config.deny_warnings));
+
+        output.push_str("\n[lints]\n");
+        for lint in LINTS {
+            output.push_str(&format!("{} = {}\n", lint.name, quote(&lint.level(config).to_string())));
+        }
+
+        output.push_str("\n[modules]\n");
+        output.push_str(&format!("search-paths = {}\n", quoted(config.module_paths.iter().map(|path| path.display().to_string()).collect())));
+
+        output.push_str("\n[limits]\n");
+        if let Some(max) = config.limits.max_file_size {
+            output.push_str(&format!("max-file-size = {}\n", max));
+        }
+        if let Some(max) = config.limits.max_diagnostics {
+            output.push_str(&format!("max-diagnostics = {}\n", max));
+        }
+        if let Some(max) = config.limits.max_string_length {
+            output.push_str(&format!("max-string-length = {}\n", max));
+        }
+        if let Some(max) = config.limits.max_number_digits {
+            output.push_str(&format!("max-number-digits = {}\n", max));
+        }
+
+        output.push_str("\n[files]\n");
+        match &config.extensions {
+            ExtensionPolicy::Allow(extensions) => {
+                output.push_str(&format!("extensions = {}\n", quoted(extensions.clone())));
+                output.push_str("check-extension = true\n");
+            },
+            ExtensionPolicy::Any => output.push_str("check-extension = false\n"),
+        }
+
+        output.push_str("\n[operators]\n");
+        output.push_str(&format!("aliases = {}\n", quoted(config.operator_aliases.spellings().iter().map(|s| s.to_string()).collect())));
+        return output;
+    }
 }
@@ -0,0 +1,223 @@
+//! Message catalog for user-facing diagnostic text
+//!
+//! Every message shown to users is a template keyed by the error or lint code it belongs to, e.g., `E0002` for an unterminated string literal.
+//! Texts that are not tied to a code, such as notes and help messages, use dotted keys like `note.error-line`.
+//!
+//! Templates refer to their arguments by position: `{0}` is replaced by the first argument, `{1}` by the second, and so on.
+//! The arguments of an error are returned by its `arguments` method, e.g., [`LexerError::arguments`](crate::lexer::LexerError::arguments).
+//!
+//! Embedders translate the messages by filling a [`Catalog`] with their own templates, and setting it as [`Config::messages`](crate::config::Config::messages).
+//! Any key missing from the catalog falls back to the built-in English template.
+//!
+//! # Examples
+//! ```
+//! use envlang::messages::Catalog;
+//!
+//! let mut catalog = Catalog::new();
+//! catalog.insert("E0002", "Merkkijono '{0}' on päättämätön");
+//! assert_eq!(catalog.render("E0002", &["abc".to_string()]), "Merkkijono 'abc' on päättämätön");
+//! assert_eq!(catalog.render("E0003", &[]), "Empty identifier");
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+
+/// A set of message templates overriding the built-in English ones
+///
+/// The default catalog is empty, and therefore renders every message in English.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Catalog {
+    templates: BTreeMap<String, String>,
+}
+
+impl Catalog {
+    /// Creates an empty catalog
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets the template of a key, replacing any previous template
+    pub fn insert(&mut self, key: impl Into<String>, template: impl Into<String>) {
+        self.templates.insert(key.into(), template.into());
+    }
+
+    /// Gets the template of a key
+    ///
+    /// Falls back to the English template, and to the key itself if the key is unknown.
+    pub fn template<'a>(&'a self, key: &'a str) -> &'a str {
+        return self.templates.get(key)
+            .map(|template| template.as_str())
+            .or_else(|| english(key))
+            .unwrap_or(key);
+    }
+
+    /// Renders the message of a key with the given arguments
+    pub fn render(&self, key: &str, args: &[String]) -> String {
+        return fill(self.template(key), args);
+    }
+}
+
+/// Replaces the positional placeholders of a template with arguments
+///
+/// Placeholders without a matching argument are left in place, and placeholders inside the arguments are not replaced.
+pub fn fill(template: &str, args: &[String]) -> String {
+    let mut result = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        result.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let arg = after.find('}')
+            .and_then(|close| after[..close].parse::<usize>().ok().map(|idx| (idx, close)))
+            .and_then(|(idx, close)| args.get(idx).map(|arg| (arg, close)));
+        match arg {
+            Some((arg, close)) => {
+                result.push_str(arg);
+                rest = &after[close + 1..];
+            },
+            None => {
+                result.push('{');
+                rest = after;
+            },
+        }
+    }
+    result.push_str(rest);
+    return result;
+}
+
+/// Gets the built-in English template of a key
+pub fn english(key: &str) -> Option<&'static str> {
+    let template = match key {
+        // Lexer errors
+        "E0001" => "Invalid token: {0}",
+        "E0002" => "Unterminated string literal '{0}'",
+        "E0003" => "Empty identifier",
+        "E0004" => "Lexer in invalid state: position {0} beyond input length {1}",
+        "E0005" => "Invalid slice: Start position {0} greater than end position {1}",
+        "E0006" => "Attempted to get position {0} to {1} from string with length {2}",
+        "E0007" => "Attempted to access element at index {0} from input with length {1}",
+        "E0008" => "Unrecognized input stream '{0}'",
+        "E0009" => "Unrecognized first symbol for comparison operator '{0}'",
+        "E0010" => "String literal is longer than the limit of {0} bytes",
+        "E0011" => "Number literal has more than the limit of {0} digits",
+        "E0012" => "Invalid escape sequence '{0}'",
+        "E0013" => "Number literal is directly followed by '{0}', which is not a unit suffix",
+        // Parser errors
+        "E0101" => "Value '{0}' is not a number",
+        "E0102" => "Value '{0}' is a malformed number",
+        "E0103" => "Invalid operation '{0}'",
+        "E0104" => "Binary operation with no left-hand side",
+        "E0105" => "Binary operation with no right-hand side",
+        "E0106" => "Whitespace inside number '{0}'",
+        "E0107" => "Internal parser logic error",
+        "E0108" => "Unexpected end of file",
+        "E0109" => "Unclosed environment",
+        "E0110" => "Missing identifier after 'let'",
+        "E0111" => "Missing assignment operator '='",
+        "E0112" => "Expected assignment operator '=', not '{0}'",
+        "E0113" => "Empty environment '{0}'",
+        "E0114" => "Invalid accession target '{0}'",
+        "E0115" => "Invalid accession source '{0}'",
+        "E0116" => "Invalid token '{0}' in inheritance statement",
+        "E0117" => "Inheritance in parentless environment: '{0}'",
+        "E0118" => "Second opening parenthesis for inheritance: '{0}'",
+        "E0119" => "Cannot specify both identifiers and wildcard: '{0}'",
+        "E0120" => "Attempted to push name to something else than an inherit clause",
+        "E0121" => "Expected an Environment instead of: '{0}'",
+        "E0122" => "Expected whitespace, brackets, commas, or identifiers, instead of: '{0}",
+        "E0123" => "Second opening bracket for function arguments: '{0}'",
+        "E0124" => "Unclosed function argument clause",
+        "E0125" => "Expected function identifier instead of: '{0}'",
+        "E0126" => "Expected function arguments",
+        "E0127" => "Expected function body",
+        "E0128" => "Expected return statement instead of: '{0}",
+        "E0129" => "Expected inheritance clause or assignment operator instead of: '{0}'",
+        "E0130" => "The `return` keyword is not valid in this context",
+        "E0131" => "Expected ParseContext::FunctionCall or ParseContext::Normal, got {0}",
+        "E0132" => "Expected identifier or opening function argument bracket, instead of: '{0}'",
+        "E0133" => "Expected identifier, digit, boolean, or whitespace in unary operation, instead of: '{0}'",
+        "E0134" => "Brace has no matching pair: '{0}'",
+        "E0135" => "Input is nested deeper than the limit of {0} levels",
+        "E0136" => "Expected a closing parenthesis instead of: '{0}'",
+        "E0137" => "Statement has more than the limit of {0} operations",
+        "E0138" => "Number literal '{0}' is out of range",
+        // IO errors
+        "E0201" => "File '{0}' does not exist",
+        "E0202" => "Permission denied when reading '{0}'",
+        "E0203" => "File '{0}' must have .envl extension",
+        "E0204" => "File '{0}' is not valid UTF-8",
+        "E0204.input" => "Input is not valid UTF-8",
+        "E0205" => "Could not read '{0}': {1}",
+        "E0205.input" => "Could not read input: {0}",
+        "E0206" => "Module '{0}' not found, tried: {1}",
+        "E0207" => "Import cycle detected: {0}",
+        "E0208" => "Invalid assignment on line {1} of '{0}': {2}",
+        "E0208.input" => "Invalid assignment on line {0}: {1}",
+        // Runtime errors
+        "E0301" => "Identifier '{0}' is not defined",
+        "E0302" => "Operator '{0}' cannot be applied to {1} and {2}",
+        "E0303" => "Operator '{0}' cannot be applied to {1}",
+        "E0304" => "Operation '{0}' failed: {1}",
+        "E0305" => "Cannot access '{1}' of '{0}', which is {2}, not an environment",
+        "E0306" => "Environment '{0}' has no member '{1}'; its members are: {2}",
+        "E0307" => "Cannot call {0}, which is not a function",
+        "E0308" => "Function takes {0} arguments, but {1} were given",
+        "E0309" => "Function calls are nested deeper than the limit of {0} calls",
+        "E0310" => "Cannot evaluate source that could not be parsed",
+        "E0311" => "Cannot evaluate '{0}' as a value",
+        "E0312" => "Identifier '{0}' is declared in an enclosing environment, but not inherited; add it to the inherit clause, as in `inherit ({0})`",
+        "E0313" => "Evaluation is nested deeper than the limit of {0} levels",
+        "E0314" => "Expected {0}, but found {1}",
+        // Lints
+        "W0001" => "Unused binding '{0}' in function '{1}'",
+        "W0002" => "Unused parameter '{0}' in function '{1}'",
+        "W0003" => "Binding '{0}' shadows a binding of an enclosing environment",
+        "W0004" => "Implicit truthiness of '{0}' in logical operation '{1}'",
+        "W0005" => "Identifier '{0}' mixes {1} and {2} letters",
+        "W0006" => "Identifier '{0}' looks like the Latin identifier '{1}'",
+        "W0007" => "String literal contains the bidirectional control character {0}",
+        "W0008" => "Anonymous value '{0}' can never be accessed",
+        // Notes and help messages
+        "note.error-line" => "The error was found on line {0}",
+        "help.close-string" => "Close the string literal",
+        "note.lint-level" => "`{0}` is set to `{1}`",
+        "note.suppressed-one" => "1 further diagnostic with the same cause was suppressed",
+        "note.suppressed-many" => "{0} further diagnostics with the same cause were suppressed",
+        "note.too-many" => "Too many diagnostics, stopping after {0} of {1}",
+        "note.in-call" => "In the call to `{0}`",
+        "label.declared-here" => "The function is declared here",
+        _ => return None,
+    };
+    return Some(template);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_placeholders() {
+        let args: Vec<String> = (0..11).map(|idx| idx.to_string()).collect();
+        assert_eq!(fill("{0}, {1}, and {10}", &args), "0, 1, and 10");
+        assert_eq!(fill("{0} and {1}", &args[..1]), "0 and {1}");
+        assert_eq!(fill("{0} and {1}", &["{1}".to_string(), "b".to_string()]), "{1} and b");
+    }
+
+    #[test]
+    fn falls_back_to_english() {
+        let mut catalog = Catalog::new();
+        catalog.insert("E0108", "Odottamaton tiedoston loppu");
+        assert_eq!(catalog.template("E0108"), "Odottamaton tiedoston loppu");
+        assert_eq!(catalog.template("E0109"), "Unclosed environment");
+        assert_eq!(catalog.template("X9999"), "X9999");
+    }
+
+    #[test]
+    fn every_code_has_an_english_template() {
+        for code in (1..=13).map(|n| format!("E{:04}", n))
+            .chain((101..=138).map(|n| format!("E{:04}", n)))
+            .chain((201..=207).map(|n| format!("E{:04}", n)))
+            .chain((301..=314).map(|n| format!("E{:04}", n)))
+            .chain((1..=4).map(|n| format!("W{:04}", n)))
+        {
+            assert!(english(&code).is_some(), "No template for {}", code);
+        }
+    }
+}
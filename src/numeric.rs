@@ -0,0 +1,324 @@
+//! Numeric semantics of Envlang
+//!
+//! Every pass that computes with numbers, such as partial evaluation ([`specialize`](crate::specialize)) and printing ([`printer`](crate::printer)), goes through this module, so that they all agree on the result of an operation.
+//!
+//! # Numbers
+//!
+//! Numbers are either integers ([`isize`]) or floats ([`f64`]).
+//! Floats are always finite: an operation whose result would be infinite or NaN fails with a [`NumericError`] instead.
+//!
+//! # Operations
+//!
+//! * Promotion: An operation on two integers gives an integer. If either operand is a float, the other is converted to a float and the result is a float.
+//! * Overflow: Integer operations never wrap or saturate, and are not promoted to floats on overflow. They fail with [`NumericError::Overflow`].
+//! * Division: Integer division rounds towards negative infinity, e.g., `-7 / 2` is `-4`. Dividing by zero fails with [`NumericError::DivisionByZero`], also for floats.
+//! * Modulus: The result of `%` has the sign of the divisor, so that `a == (a / b) * b + a % b` for integers, e.g., `-7 % 2` is `1` and `7 % -2` is `-1`.
+//! * Exponentiation: An integer raised to a non-negative integer is an integer. An integer raised to a negative integer is a float, e.g., `2 ^ -1` is `0.5`.
+//!   A negative base can only be raised to a whole exponent; other exponents fail with [`NumericError::NotARealNumber`].
+//!   Without the `std` feature, only whole exponents are supported, and other exponents fail with [`NumericError::Unsupported`].
+//! * Comparison: Integers and floats compare by value, e.g., `1 == 1.0`. If NaN ever reaches a comparison, every comparison is false except `!=`.
+//!
+//! # Formatting
+//!
+//! Floats are formatted by [`format_float`] as the shortest decimal that reads back as the same float, always with a decimal point and never in exponent notation, so that printed floats are read back as floats.
+
+use crate::parser::AstNode;
+use crate::symbols::{ArithmeticOperators, ComparisonOperators};
+use alloc::format;
+use alloc::string::{String, ToString};
+
+/// Envlang number
+///
+/// The enum derives the traits `Debug`, `Clone`, `Copy`, and `PartialEq`, and implements [`ToString`](Number::to_string).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Integer(isize),
+    Float(f64),
+}
+
+/// Error type for numeric operations
+///
+/// The enum derives the traits `Debug`, `Clone`, `Copy`, `PartialEq`, and `Eq`, and implements [`ToString`](NumericError::to_string).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericError {
+    /// The result does not fit into an integer, or is too large for a float
+    Overflow,
+    /// The divisor of `/` or `%` is zero
+    DivisionByZero,
+    /// A negative base is raised to a fractional exponent
+    NotARealNumber,
+    /// The operation needs floating-point functions that are only available with the `std` feature
+    Unsupported,
+}
+
+impl ToString for NumericError {
+    fn to_string(&self) -> String {
+        match self {
+            NumericError::Overflow => "Numeric overflow".to_string(),
+            NumericError::DivisionByZero => "Division by zero".to_string(),
+            NumericError::NotARealNumber => "Result is not a real number".to_string(),
+            NumericError::Unsupported => "Operation is not supported without the standard library".to_string(),
+        }
+    }
+}
+
+impl Number {
+    /// Gets the number of an [`AstNode::Integer`] or [`AstNode::Float`], or `None` for other nodes
+    pub fn from_node(node: &AstNode) -> Option<Number> {
+        match node {
+            AstNode::Integer(num) => Some(Number::Integer(*num)),
+            AstNode::Float(num) => Some(Number::Float(*num)),
+            _ => None,
+        }
+    }
+
+    /// Converts the number into an [`AstNode::Integer`] or [`AstNode::Float`]
+    pub fn into_node(self) -> AstNode {
+        match self {
+            Number::Integer(num) => AstNode::Integer(num),
+            Number::Float(num) => AstNode::Float(num),
+        }
+    }
+
+    /// Gets the value of the number as a float
+    pub fn as_float(self) -> f64 {
+        match self {
+            Number::Integer(num) => num as f64,
+            Number::Float(num) => num,
+        }
+    }
+}
+
+impl ToString for Number {
+    fn to_string(&self) -> String {
+        match self {
+            Number::Integer(num) => num.to_string(),
+            Number::Float(num) => format_float(*num),
+        }
+    }
+}
+
+/// Applies an arithmetic operator to two numbers
+///
+/// # Errors
+/// * [`NumericError::Overflow`]: The result does not fit into an integer, or is too large for a float.
+/// * [`NumericError::DivisionByZero`]: The divisor of `/` or `%` is zero.
+/// * [`NumericError::NotARealNumber`]: A negative base is raised to a fractional exponent.
+/// * [`NumericError::Unsupported`]: A positive base is raised to a fractional exponent without the `std` feature.
+///
+/// # Examples
+/// ```
+/// use envlang::numeric::{arithmetic, Number, NumericError};
+/// use envlang::symbols::ArithmeticOperators;
+///
+/// assert_eq!(arithmetic(&ArithmeticOperators::MODULUS, Number::Integer(-7), Number::Integer(2)), Ok(Number::Integer(1)));
+/// assert_eq!(arithmetic(&ArithmeticOperators::ADD, Number::Integer(1), Number::Float(0.5)), Ok(Number::Float(1.5)));
+/// assert_eq!(arithmetic(&ArithmeticOperators::DIVIDE, Number::Float(1.0), Number::Integer(0)), Err(NumericError::DivisionByZero));
+/// ```
+pub fn arithmetic(op: &ArithmeticOperators, left: Number, right: Number) -> Result<Number, NumericError> {
+    match (left, right) {
+        (Number::Integer(a), Number::Integer(b)) => return integer_arithmetic(op, a, b),
+        _ => return float_arithmetic(op, left.as_float(), right.as_float()).map(Number::Float),
+    }
+}
+
+/// Negates a number
+///
+/// # Errors
+/// * [`NumericError::Overflow`]: The number is the smallest integer, whose negation does not fit into an integer.
+pub fn negate(operand: Number) -> Result<Number, NumericError> {
+    match operand {
+        Number::Integer(num) => return num.checked_neg().map(Number::Integer).ok_or(NumericError::Overflow),
+        Number::Float(num) => return Ok(Number::Float(-num)),
+    }
+}
+
+/// Compares two numbers with a comparison operator
+pub fn compare(op: &ComparisonOperators, left: Number, right: Number) -> bool {
+    match (left, right) {
+        (Number::Integer(a), Number::Integer(b)) => return compare_ordered(op, a, b),
+        _ => return compare_ordered(op, left.as_float(), right.as_float()),
+    }
+}
+
+/// Formats a float as Envlang source code
+///
+/// The float is written as the shortest decimal that reads back as the same float, with a decimal point and without an exponent.
+/// Non-finite floats, which no operation produces, are written as `NaN`, `inf`, and `-inf`.
+///
+/// # Examples
+/// ```
+/// use envlang::numeric::format_float;
+///
+/// assert_eq!(format_float(2.0), "2.0");
+/// assert_eq!(format_float(0.1), "0.1");
+/// assert_eq!(format_float(1e20), "100000000000000000000.0");
+/// ```
+pub fn format_float(num: f64) -> String {
+    let mut output = format!("{}", num);
+    if num.is_finite() && !output.contains('.') {
+        output.push_str(".0");
+    }
+    return output;
+}
+
+/// Applies an arithmetic operator to two integers
+fn integer_arithmetic(op: &ArithmeticOperators, a: isize, b: isize) -> Result<Number, NumericError> {
+    let result = match op {
+        ArithmeticOperators::ADD => a.checked_add(b),
+        ArithmeticOperators::SUBTRACT => a.checked_sub(b),
+        ArithmeticOperators::MULTIPLY => a.checked_mul(b),
+        ArithmeticOperators::DIVIDE | ArithmeticOperators::MODULUS if b == 0 => return Err(NumericError::DivisionByZero),
+        ArithmeticOperators::DIVIDE => a.checked_div(b).map(|q| if a % b != 0 && (a < 0) != (b < 0) { q - 1 } else { q }),
+        ArithmeticOperators::MODULUS => a.checked_rem(b).map(|r| if r != 0 && (r < 0) != (b < 0) { r + b } else { r }),
+        ArithmeticOperators::EXPONENTIATION if b < 0 => return float_arithmetic(op, a as f64, b as f64).map(Number::Float),
+        ArithmeticOperators::EXPONENTIATION => u32::try_from(b).ok().and_then(|b| a.checked_pow(b)),
+    };
+    return result.map(Number::Integer).ok_or(NumericError::Overflow);
+}
+
+/// Applies an arithmetic operator to two floats, failing if the result is not finite
+fn float_arithmetic(op: &ArithmeticOperators, a: f64, b: f64) -> Result<f64, NumericError> {
+    let result = match op {
+        ArithmeticOperators::ADD => a + b,
+        ArithmeticOperators::SUBTRACT => a - b,
+        ArithmeticOperators::MULTIPLY => a * b,
+        ArithmeticOperators::DIVIDE | ArithmeticOperators::MODULUS if b == 0.0 => return Err(NumericError::DivisionByZero),
+        ArithmeticOperators::DIVIDE => a / b,
+        ArithmeticOperators::MODULUS => {
+            let r = a % b;
+            if r != 0.0 && (r < 0.0) != (b < 0.0) { r + b } else { r }
+        },
+        ArithmeticOperators::EXPONENTIATION => power(a, b)?,
+    };
+    if !result.is_finite() {
+        return Err(NumericError::Overflow);
+    }
+    return Ok(result);
+}
+
+/// Raises a float to a float, by repeated squaring for whole exponents
+///
+/// Infinite results are reported by the caller.
+fn power(base: f64, exponent: f64) -> Result<f64, NumericError> {
+    if exponent % 1.0 == 0.0 {
+        if exponent < 0.0 && base == 0.0 {
+            return Err(NumericError::DivisionByZero);
+        }
+        let mut remaining = if exponent < 0.0 { -exponent } else { exponent };
+        let mut square = base;
+        let mut result = 1.0;
+        while remaining >= 1.0 {
+            if remaining % 2.0 == 1.0 {
+                result *= square;
+            }
+            square *= square;
+            remaining = (remaining - remaining % 2.0) / 2.0;
+        }
+        return Ok(if exponent < 0.0 { 1.0 / result } else { result });
+    }
+    if base < 0.0 {
+        return Err(NumericError::NotARealNumber);
+    }
+    #[cfg(feature = "std")]
+    return Ok(base.powf(exponent));
+    #[cfg(not(feature = "std"))]
+    return Err(NumericError::Unsupported);
+}
+
+/// Compares two ordered values with a comparison operator
+fn compare_ordered<T: PartialOrd>(op: &ComparisonOperators, a: T, b: T) -> bool {
+    match op {
+        ComparisonOperators::LT => a < b,
+        ComparisonOperators::LEQ => a <= b,
+        ComparisonOperators::GT => a > b,
+        ComparisonOperators::GEQ => a >= b,
+        ComparisonOperators::EQ => a == b,
+        ComparisonOperators::NEQ => a != b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ArithmeticOperators::*;
+    use Number::{Float, Integer};
+
+    fn apply(op: ArithmeticOperators, left: Number, right: Number) -> Result<Number, NumericError> {
+        return arithmetic(&op, left, right);
+    }
+
+    #[test]
+    fn promotion() {
+        assert_eq!(apply(ADD, Integer(1), Integer(2)), Ok(Integer(3)));
+        assert_eq!(apply(MULTIPLY, Integer(2), Float(1.5)), Ok(Float(3.0)));
+        assert_eq!(apply(SUBTRACT, Float(0.5), Integer(1)), Ok(Float(-0.5)));
+        assert_eq!(apply(ADD, Integer(isize::MAX), Integer(1)), Err(NumericError::Overflow));
+        assert_eq!(apply(MULTIPLY, Float(f64::MAX), Integer(2)), Err(NumericError::Overflow));
+        assert_eq!(negate(Integer(isize::MIN)), Err(NumericError::Overflow));
+    }
+
+    #[test]
+    fn division_rounds_down() {
+        assert_eq!(apply(DIVIDE, Integer(7), Integer(2)), Ok(Integer(3)));
+        assert_eq!(apply(DIVIDE, Integer(-7), Integer(2)), Ok(Integer(-4)));
+        assert_eq!(apply(DIVIDE, Integer(7), Integer(-2)), Ok(Integer(-4)));
+        assert_eq!(apply(DIVIDE, Integer(-6), Integer(2)), Ok(Integer(-3)));
+        assert_eq!(apply(DIVIDE, Integer(7), Float(2.0)), Ok(Float(3.5)));
+        assert_eq!(apply(DIVIDE, Integer(isize::MIN), Integer(-1)), Err(NumericError::Overflow));
+        assert_eq!(apply(DIVIDE, Integer(1), Integer(0)), Err(NumericError::DivisionByZero));
+        assert_eq!(apply(DIVIDE, Float(1.0), Float(0.0)), Err(NumericError::DivisionByZero));
+    }
+
+    #[test]
+    fn modulus_has_sign_of_divisor() {
+        assert_eq!(apply(MODULUS, Integer(7), Integer(2)), Ok(Integer(1)));
+        assert_eq!(apply(MODULUS, Integer(-7), Integer(2)), Ok(Integer(1)));
+        assert_eq!(apply(MODULUS, Integer(7), Integer(-2)), Ok(Integer(-1)));
+        assert_eq!(apply(MODULUS, Integer(-6), Integer(2)), Ok(Integer(0)));
+        assert_eq!(apply(MODULUS, Float(-7.5), Integer(2)), Ok(Float(0.5)));
+        assert_eq!(apply(MODULUS, Integer(1), Integer(0)), Err(NumericError::DivisionByZero));
+        assert_eq!(apply(MODULUS, Float(1.0), Float(0.0)), Err(NumericError::DivisionByZero));
+    }
+
+    #[test]
+    fn exponentiation() {
+        assert_eq!(apply(EXPONENTIATION, Integer(2), Integer(10)), Ok(Integer(1024)));
+        assert_eq!(apply(EXPONENTIATION, Integer(-2), Integer(3)), Ok(Integer(-8)));
+        assert_eq!(apply(EXPONENTIATION, Integer(2), Integer(-1)), Ok(Float(0.5)));
+        assert_eq!(apply(EXPONENTIATION, Integer(0), Integer(-1)), Err(NumericError::DivisionByZero));
+        assert_eq!(apply(EXPONENTIATION, Integer(2), Integer(64)), Err(NumericError::Overflow));
+        assert_eq!(apply(EXPONENTIATION, Float(-2.0), Integer(3)), Ok(Float(-8.0)));
+        assert_eq!(apply(EXPONENTIATION, Float(-1.5), Float(2.0)), Ok(Float(2.25)));
+        assert_eq!(apply(EXPONENTIATION, Float(1.5), Integer(0)), Ok(Float(1.0)));
+        assert_eq!(apply(EXPONENTIATION, Float(10.0), Integer(400)), Err(NumericError::Overflow));
+        assert_eq!(apply(EXPONENTIATION, Integer(-8), Float(0.5)), Err(NumericError::NotARealNumber));
+        #[cfg(feature = "std")]
+        assert_eq!(apply(EXPONENTIATION, Integer(4), Float(0.5)), Ok(Float(2.0)));
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(compare(&ComparisonOperators::EQ, Integer(1), Float(1.0)));
+        assert!(compare(&ComparisonOperators::LT, Integer(1), Float(1.5)));
+        assert!(!compare(&ComparisonOperators::GEQ, Float(-0.5), Integer(0)));
+        assert!(compare(&ComparisonOperators::EQ, Float(0.0), Float(-0.0)));
+        for op in [ComparisonOperators::LT, ComparisonOperators::LEQ, ComparisonOperators::GT, ComparisonOperators::GEQ, ComparisonOperators::EQ] {
+            assert!(!compare(&op, Float(f64::NAN), Float(f64::NAN)));
+            assert!(!compare(&op, Float(f64::NAN), Integer(1)));
+        }
+        assert!(compare(&ComparisonOperators::NEQ, Float(f64::NAN), Float(f64::NAN)));
+    }
+
+    #[test]
+    fn float_formatting() {
+        assert_eq!(format_float(1.0), "1.0");
+        assert_eq!(format_float(-0.0), "-0.0");
+        assert_eq!(format_float(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(format_float(1e-7), "0.0000001");
+        assert_eq!(format_float(1e20), "100000000000000000000.0");
+        assert_eq!(format_float(f64::NAN), "NaN");
+        assert_eq!(Float(2.5).to_string(), "2.5");
+        assert_eq!(Integer(-3).to_string(), "-3");
+    }
+}
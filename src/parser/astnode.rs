@@ -1,5 +1,8 @@
-use std::rc::Rc;
-use crate::{symbols::Operators, parser::ParserError};
+use alloc::rc::Rc;
+use crate::{lexer::Location, symbols::Operators, parser::ParserError, span::Span};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
 
 /// Enum variant representing the nodes of the Abstract Syntax Tree
 /// 
@@ -19,7 +22,7 @@ pub enum AstNode {
 
     /// Environments are structs with three fields:
     /// * `name`: Name of environment, or `None` for anonymous environments.
-    /// * `bindings`: Vector of reference-counted pointers to environment elements (as `AstNode`).
+    /// * `bindings`: Vector of reference-counted pointers to environment elements (as `AstNode`), in the order of the source.
     /// * `parent`: Reference-counted pointer to parent environment, or `None` for global environment.
     Environment {
         name: Option<Rc<str>>,
@@ -44,14 +47,16 @@ pub enum AstNode {
         right: Rc<AstNode>,
     },
 
-    /// Assignments are structs with three fields:
+    /// Assignments are structs with four fields:
     /// * `name`: Reference-counted pointer to assignment name (as `str`).
     /// * `value`: Reference-counted pointer to the assignment value (as `AstNode`), or `None` if initialized but unassigned.
     /// * `inherit`: Reference-counted pointer to the inheritance clause (as [`AstNode::Inherit`]), or `None` if no inheritance.
+    /// * `span`: Byte span of the name in the declaration, i.e., `x` in `let x = ...` or `f` in `let fun f[...]`, or `None` if the parser has no source locations.
     Let {
         name: Rc<str>,
         value: Option<Rc<AstNode>>,
         inherit: Option<Rc<AstNode>>,
+        span: Option<Span>,
     },
 
     /// Inheritance clauses are single-field structs:
@@ -60,26 +65,34 @@ pub enum AstNode {
         names: Option<Vec<Rc<str>>>,
     },
 
-    /// Functions are structs with three fields:
+    /// Functions are structs with four fields:
     /// * `params`: Reference-counted pointer to function parameters (as [`AstNode::FunctionArgs`]).
     /// * `body`: Reference-counted pointer to function body (as [`AstNode::Environment`]).
     /// * `r#return`: Reference-counted pointer to function return statement (as [`AstNode::Environment`]).
+    /// * `span`: Byte span of the name in the declaration, i.e., `f` in `let fun f[...]`, or `None` if the parser has no source locations.
     Function {
         params: Rc<AstNode>,
         body: Rc<AstNode>,
         r#return: Rc<AstNode>,
+        span: Option<Span>,
     },
 
     /// Function arguments are a single-element enum variant, with a vector or reference-counted pointers to argument identifiers (as [`AstNode::Identifier`]).
     FunctionArgs(Vec<Rc<AstNode>>),
 
-    /// Function calls are structs with two fields:
-    /// * `id`: Reference-counted pointer to the function being called (as [`AstNode::Identifier`])
+    /// Function calls are structs with three fields:
+    /// * `id`: Reference-counted pointer to the function being called (as [`AstNode::Identifier`], an accession, or another [`AstNode::FunctionCall`])
     /// * `args`: Reference-counter pointer to the function arguments (as [`AstNode::FunctionArgs`])
+    /// * `span`: Byte span of the call, from the callee to the closing bracket of the arguments, or `None` if the parser has no source locations.
     FunctionCall {
         id: Rc<AstNode>,
         args: Rc<AstNode>,
+        span: Option<Span>,
     },
+
+    /// Errors mark a statement that could not be parsed, and are only produced by [`Parser::parse_recovering`](crate::parser::Parser::parse_recovering)
+    /// * The single field is the byte span of the skipped source, or `None` if the parser has no source locations.
+    Error(Option<Span>),
 }
 
 impl ToString for AstNode {
@@ -106,7 +119,7 @@ impl ToString for AstNode {
                 => format!("{} {}", op.to_string(), operand.to_string()),
             AstNode::BinaryOp { left, operator, right }
                 => format!("{} {} {}", left.to_string(), operator.to_string(), right.to_string()),
-            AstNode::Let { name, value , inherit: _ }
+            AstNode::Let { name, value, .. }
                 => format!("Let {} = {} with {}", name, {
                     if let Some(val) = value {
                         val.to_string()
@@ -139,11 +152,15 @@ impl ToString for AstNode {
                 => format!("[{}]",
                     params.iter().map(|arg| arg.to_string()).collect::<Vec<String>>().join(", ")
                 ),
-            AstNode::FunctionCall { id, args }
+            AstNode::FunctionCall { id, args, .. }
                 => format!("Function call to {} with arguments {}",
                     id.to_string(),
                     args.to_string()
                 ),
+            AstNode::Error(Some(span))
+                => format!("Unparsed source at bytes {}..{}", span.start, span.end),
+            AstNode::Error(None)
+                => "Unparsed source".to_string(),
         }
     }
 }
@@ -152,19 +169,30 @@ impl AstNode {
     /// Checks whether the node is an environment
     pub fn is_environment(&self) -> bool { matches!(self, AstNode::Environment { .. }) }
 
+    /// Checks whether the node is an anonymous value: a literal, an identifier, or an operation, found in an environment without a `let` binding
+    /// 
+    /// Function calls are not anonymous values, as they are run for their effects.
+    pub fn is_anonymous_value(&self) -> bool {
+        matches!(self,
+            AstNode::Integer(_)
+            | AstNode::Float(_)
+            | AstNode::Boolean(_)
+            | AstNode::String(_)
+            | AstNode::Identifier(_)
+            | AstNode::UnaryOp { .. }
+            | AstNode::BinaryOp { .. }
+        )
+    }
+
     /// Checks whether a contained environment has a single element
     pub fn is_single_element_env(&self) -> bool {
         if let Some(bindings) = self.get_bindings() {
-            if bindings.len() == 1 {
-                return true;
-            } else {
-                return false;
-            }
+            return bindings.len() == 1;
         }
         false
     }
 
-    /// Gets the bindings of an environment
+    /// Gets the bindings of an environment, in the order of the source
     pub fn get_bindings(&self) -> Option<Vec<Rc<AstNode>>> {
         match self {
             AstNode::Environment { bindings, .. } => Some(bindings.to_vec()),
@@ -222,7 +250,7 @@ impl AstNode {
                     names.push(node);
                     return Ok(());
                 } else {
-                    return Err(ParserError::WildcardAndElements(0, 0, "wrong implementation".to_string()))
+                    return Err(ParserError::WildcardAndElements(Location::new(0, 0), "wrong implementation".to_string()))
                 }
             },
             _ => Err(ParserError::NotInheritClause),
@@ -238,7 +266,7 @@ impl AstNode {
                 field_setter(self)?;
                 Ok(())
             },
-            _ => Err(ParserError::ParserLogicError(0, 0)), // TODO: Make error more informative
+            _ => Err(ParserError::ParserLogicError(Location::new(0, 0))), // TODO: Make error more informative
         }
     }
 }
\ No newline at end of file
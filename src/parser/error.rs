@@ -1,13 +1,16 @@
-use std::error::Error;
-use std::fmt;
+use crate::lexer::Location;
+use crate::messages::Catalog;
+use core::error::Error;
+use core::fmt;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
 
 /// Error type for Envlang parser
 /// 
 /// The error types match various failure states during semantic analysis.
 /// 
 /// Errors always contain at least:
-/// - The current parser position.
-/// - The current line number (calculated by the parser).
+/// - The [`Location`] of the token at which the error was triggered.
 /// - The value that caused the error.
 /// 
 /// Errors may optionally include information about:
@@ -17,115 +20,207 @@ use std::fmt;
 /// Usage of the error types is documented in the [`Parser`](super::Parser).
 #[derive(Debug, PartialEq)]
 pub enum ParserError {
-    NotANumber(usize, usize, String),           // (pos, line, value)
-    MalformedNumber(usize, usize, String),      // (pos, line, value)
-    InvalidOperation(usize, usize, String),     // (pos, line, value)
-    BinaryOpWithNoLHS(usize, usize),            // (pos, line)
-    BinaryOpWithNoRHS(usize, usize),            // (pos, line)
-    WhitespaceInNumber(usize, usize, String),   // (pos, line, value)
-    ParserLogicError(usize, usize),             // (pos, line)
-    UnexpectedEOF(usize, usize),                // (pos, line)
-    UnclosedEnvironment(usize),                 // (line)
-    MissingLetIdentifier(usize, usize),         // (pos, line)
-    MissingAssignmentOp(usize, usize),          // (pos, line)
-    InvalidAssignmentOp(usize, usize, String),  // (pos, line, value)
-    EmptyEnv(usize, usize, String),             // (pos, line, value)
-    InvalidAccessionTarget(usize, usize, String), // (pos, line, value)
-    InvalidAccessionSource(usize, usize, String), // (pos, line, value)
-    InvalidInheritanceToken(usize, usize, String),// (pos, line, value)
-    ParentlessInheritance(usize, usize, String),// (pos, line, value)
-    DoubleInheritanceParen(usize, usize, String),   // (pos, line, value)
-    WildcardAndElements(usize, usize, String),  // (pos, line, value)
+    NotANumber(Location, String),               // (location, value)
+    MalformedNumber(Location, String),          // (location, value)
+    InvalidOperation(Location, String),         // (location, value)
+    BinaryOpWithNoLHS(Location),                // (location)
+    BinaryOpWithNoRHS(Location),                // (location)
+    WhitespaceInNumber(Location, String),       // (location, value)
+    ParserLogicError(Location),                 // (location)
+    UnexpectedEOF(Location),                    // (location)
+    UnclosedEnvironment(Location),              // (location)
+    MissingLetIdentifier(Location),             // (location)
+    MissingAssignmentOp(Location),              // (location)
+    InvalidAssignmentOp(Location, String),      // (location, value)
+    EmptyEnv(Location, String),                 // (location, value)
+    InvalidAccessionTarget(Location, String),   // (location, value)
+    InvalidAccessionSource(Location, String),   // (location, value)
+    InvalidInheritanceToken(Location, String),  // (location, value)
+    ParentlessInheritance(Location, String),    // (location, value)
+    DoubleInheritanceParen(Location, String),   // (location, value)
+    WildcardAndElements(Location, String),      // (location, value)
     NotInheritClause, // TODO: I am inappropriately formatted!
-    NotAnEnvironment(usize, usize, String),     // (pos, line, value)
-    InvalidFunArgToken(usize, usize, String),   // (pos, line, value)
-    DoubleFunArgBracket(usize, usize, String),  // (pos, line, value)
-    UnclosedArgumentClause(usize),              // (line)
-    MissingFunctionName(usize, usize, String),  // (pos, line, value)
-    MissingFunctionArgs(usize, usize),          // (pos, line)
-    MissingFunctionBody(usize, usize),          // (pos, line)
-    MissingReturnStatement(usize, usize, String),   // (pos, line, value)
-    InvalidTokenInFnSignature(usize, usize, String),// (pos, line, value)
-    UnexpectedReturn(usize, usize),             // (pos, line)
-    InvalidContextForIdentifier(usize, String), // (line, value)
-    InvalidTokenInFnCall(usize, usize, String), // (pos, line, value)
-    InvalidTokenInUnaryOp(usize, usize, String),// (pos, line, value)
+    NotAnEnvironment(Location, String),         // (location, value)
+    InvalidFunArgToken(Location, String),       // (location, value)
+    DoubleFunArgBracket(Location, String),      // (location, value)
+    UnclosedArgumentClause(Location),           // (location)
+    MissingFunctionName(Location, String),      // (location, value)
+    MissingFunctionArgs(Location),              // (location)
+    MissingFunctionBody(Location),              // (location)
+    MissingReturnStatement(Location, String),   // (location, value)
+    InvalidTokenInFnSignature(Location, String),// (location, value)
+    UnexpectedReturn(Location),                 // (location)
+    InvalidContextForIdentifier(Location, String),// (location, value)
+    InvalidTokenInFnCall(Location, String),     // (location, value)
+    InvalidTokenInUnaryOp(Location, String),    // (location, value)
+    UnmatchedBrace(Location, String),           // (location, value)
+    NestingTooDeep(Location, usize),            // (location, limit)
+    UnclosedParenthesis(Location, String),      // (location, value)
+    TooManyOperations(Location, usize),         // (location, limit)
+    NumberOutOfRange(Location, String),         // (location, value)
 }
 
 impl Error for ParserError {}
 
+impl ParserError {
+    /// Gets the stable error code of the error
+    /// 
+    /// Parser errors use the codes `E0101` to `E0199`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParserError::NotANumber(_, _) => "E0101",
+            ParserError::MalformedNumber(_, _) => "E0102",
+            ParserError::InvalidOperation(_, _) => "E0103",
+            ParserError::BinaryOpWithNoLHS(_) => "E0104",
+            ParserError::BinaryOpWithNoRHS(_) => "E0105",
+            ParserError::WhitespaceInNumber(_, _) => "E0106",
+            ParserError::ParserLogicError(_) => "E0107",
+            ParserError::UnexpectedEOF(_) => "E0108",
+            ParserError::UnclosedEnvironment(_) => "E0109",
+            ParserError::MissingLetIdentifier(_) => "E0110",
+            ParserError::MissingAssignmentOp(_) => "E0111",
+            ParserError::InvalidAssignmentOp(_, _) => "E0112",
+            ParserError::EmptyEnv(_, _) => "E0113",
+            ParserError::InvalidAccessionTarget(_, _) => "E0114",
+            ParserError::InvalidAccessionSource(_, _) => "E0115",
+            ParserError::InvalidInheritanceToken(_, _) => "E0116",
+            ParserError::ParentlessInheritance(_, _) => "E0117",
+            ParserError::DoubleInheritanceParen(_, _) => "E0118",
+            ParserError::WildcardAndElements(_, _) => "E0119",
+            ParserError::NotInheritClause => "E0120",
+            ParserError::NotAnEnvironment(_, _) => "E0121",
+            ParserError::InvalidFunArgToken(_, _) => "E0122",
+            ParserError::DoubleFunArgBracket(_, _) => "E0123",
+            ParserError::UnclosedArgumentClause(_) => "E0124",
+            ParserError::MissingFunctionName(_, _) => "E0125",
+            ParserError::MissingFunctionArgs(_) => "E0126",
+            ParserError::MissingFunctionBody(_) => "E0127",
+            ParserError::MissingReturnStatement(_, _) => "E0128",
+            ParserError::InvalidTokenInFnSignature(_, _) => "E0129",
+            ParserError::UnexpectedReturn(_) => "E0130",
+            ParserError::InvalidContextForIdentifier(_, _) => "E0131",
+            ParserError::InvalidTokenInFnCall(_, _) => "E0132",
+            ParserError::InvalidTokenInUnaryOp(_, _) => "E0133",
+            ParserError::UnmatchedBrace(_, _) => "E0134",
+            ParserError::NestingTooDeep(_, _) => "E0135",
+            ParserError::UnclosedParenthesis(_, _) => "E0136",
+            ParserError::TooManyOperations(_, _) => "E0137",
+            ParserError::NumberOutOfRange(_, _) => "E0138",
+        }
+    }
+
+    /// Gets the values filled into the message template of the error (see [`crate::messages`])
+    pub fn arguments(&self) -> Vec<String> {
+        match self {
+            ParserError::NotANumber(_, valuestr)
+            | ParserError::MalformedNumber(_, valuestr)
+            | ParserError::InvalidOperation(_, valuestr)
+            | ParserError::WhitespaceInNumber(_, valuestr)
+            | ParserError::InvalidAssignmentOp(_, valuestr)
+            | ParserError::EmptyEnv(_, valuestr)
+            | ParserError::InvalidAccessionTarget(_, valuestr)
+            | ParserError::InvalidAccessionSource(_, valuestr)
+            | ParserError::InvalidInheritanceToken(_, valuestr)
+            | ParserError::ParentlessInheritance(_, valuestr)
+            | ParserError::DoubleInheritanceParen(_, valuestr)
+            | ParserError::WildcardAndElements(_, valuestr)
+            | ParserError::NotAnEnvironment(_, valuestr)
+            | ParserError::InvalidFunArgToken(_, valuestr)
+            | ParserError::DoubleFunArgBracket(_, valuestr)
+            | ParserError::MissingFunctionName(_, valuestr)
+            | ParserError::MissingReturnStatement(_, valuestr)
+            | ParserError::InvalidTokenInFnSignature(_, valuestr)
+            | ParserError::InvalidContextForIdentifier(_, valuestr)
+            | ParserError::InvalidTokenInFnCall(_, valuestr)
+            | ParserError::InvalidTokenInUnaryOp(_, valuestr)
+            | ParserError::UnmatchedBrace(_, valuestr)
+            | ParserError::UnclosedParenthesis(_, valuestr)
+            | ParserError::NumberOutOfRange(_, valuestr) => vec![valuestr.clone()],
+            ParserError::NestingTooDeep(_, limit)
+            | ParserError::TooManyOperations(_, limit) => vec![limit.to_string()],
+            ParserError::BinaryOpWithNoLHS(_)
+            | ParserError::BinaryOpWithNoRHS(_)
+            | ParserError::ParserLogicError(_)
+            | ParserError::UnexpectedEOF(_)
+            | ParserError::UnclosedEnvironment(_)
+            | ParserError::MissingLetIdentifier(_)
+            | ParserError::MissingAssignmentOp(_)
+            | ParserError::NotInheritClause
+            | ParserError::UnclosedArgumentClause(_)
+            | ParserError::MissingFunctionArgs(_)
+            | ParserError::MissingFunctionBody(_)
+            | ParserError::UnexpectedReturn(_) => Vec::new(),
+        }
+    }
+
+    /// Gets the description of the error, without the phase and position prefix of its `Display` output
+    ///
+    /// The description is rendered from the English message catalog; see [`crate::messages::Catalog`] for translations.
+    pub fn message(&self) -> String { Catalog::default().render(self.code(), &self.arguments()) }
+
+    /// Gets the location at which the error was triggered, if the error records one
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            ParserError::NotInheritClause => None,
+            ParserError::NotANumber(location, ..)
+            | ParserError::MalformedNumber(location, ..)
+            | ParserError::InvalidOperation(location, ..)
+            | ParserError::BinaryOpWithNoLHS(location, ..)
+            | ParserError::BinaryOpWithNoRHS(location, ..)
+            | ParserError::WhitespaceInNumber(location, ..)
+            | ParserError::ParserLogicError(location, ..)
+            | ParserError::UnexpectedEOF(location, ..)
+            | ParserError::UnclosedEnvironment(location, ..)
+            | ParserError::MissingLetIdentifier(location, ..)
+            | ParserError::MissingAssignmentOp(location, ..)
+            | ParserError::InvalidAssignmentOp(location, ..)
+            | ParserError::EmptyEnv(location, ..)
+            | ParserError::InvalidAccessionTarget(location, ..)
+            | ParserError::InvalidAccessionSource(location, ..)
+            | ParserError::InvalidInheritanceToken(location, ..)
+            | ParserError::ParentlessInheritance(location, ..)
+            | ParserError::DoubleInheritanceParen(location, ..)
+            | ParserError::WildcardAndElements(location, ..)
+            | ParserError::NotAnEnvironment(location, ..)
+            | ParserError::InvalidFunArgToken(location, ..)
+            | ParserError::DoubleFunArgBracket(location, ..)
+            | ParserError::UnclosedArgumentClause(location, ..)
+            | ParserError::MissingFunctionName(location, ..)
+            | ParserError::MissingFunctionArgs(location, ..)
+            | ParserError::MissingFunctionBody(location, ..)
+            | ParserError::MissingReturnStatement(location, ..)
+            | ParserError::InvalidTokenInFnSignature(location, ..)
+            | ParserError::UnexpectedReturn(location, ..)
+            | ParserError::InvalidContextForIdentifier(location, ..)
+            | ParserError::InvalidTokenInFnCall(location, ..)
+            | ParserError::InvalidTokenInUnaryOp(location, ..)
+            | ParserError::UnmatchedBrace(location, ..)
+            | ParserError::NestingTooDeep(location, ..)
+            | ParserError::UnclosedParenthesis(location, ..)
+            | ParserError::TooManyOperations(location, ..)
+            | ParserError::NumberOutOfRange(location, ..) => Some(location),
+        }
+    }
+
+    /// Gets the token position at which the error was triggered, if the error records one
+    pub fn position(&self) -> Option<usize> { self.location().map(|location| location.token) }
+
+    /// Gets the source line at which the error was triggered, if the error records one
+    pub fn line(&self) -> Option<usize> { self.location().map(|location| location.line) }
+}
+
 /// TODOs:
 /// - MalformedNumber is not informative enough, the context is not visible -> Need to reconstruct the line (through tokens or source)
 /// - NotANumber is not informative enough, the context is not visible -> Need to reconstruct the line (through tokens or source)
 impl fmt::Display for ParserError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ParserError::NotANumber(pos, line, valuestr) => 
-                write!(f, "Parser error at source line {}, token position {}: Value '{}' is not a number", line, pos, valuestr),
-            ParserError::MalformedNumber(pos, line, valuestr) => 
-                write!(f, "Parser error at source line {}, token position {}: Value '{}' is a malformed number", line, pos, valuestr),
-            ParserError::InvalidOperation(pos, line, valuestr) => 
-                write!(f, "Parser error at source line {}, token position {}: Invalid operation '{}'", line, pos, valuestr),
-            ParserError::BinaryOpWithNoLHS(pos, line) =>
-                write!(f, "Parser error at source line {}, token position {}: Binary operation with no left-hand side", line, pos),
-            ParserError::BinaryOpWithNoRHS(pos, line) =>
-                write!(f, "Parser error at source line {}, token position {}: Binary operation with no right-hand side", line, pos),
-            ParserError::WhitespaceInNumber(pos, line, valuestr) => 
-                write!(f, "Parser error at source line {}, token position {}: Whitespace inside number '{}'", line, pos, valuestr),
-            ParserError::ParserLogicError(pos, line) =>
-                write!(f, "Parser error at source line {}, token position {}: Internal parser logic error", line, pos),
-            ParserError::UnexpectedEOF(pos, line) =>
-                write!(f, "Parser error at source line {}, token position {}: Unexpected end of file", line, pos),
-            ParserError::UnclosedEnvironment(line) =>
-                write!(f, "Parser error at source line {}: Unclosed environment", line),
-            ParserError::MissingLetIdentifier(pos, line) =>
-                write!(f, "Parser error at source line {}, token position {}: Missing identifier after 'let'", line, pos),
-            ParserError::MissingAssignmentOp(pos, line) =>
-                write!(f, "Parser error at source line {}, token position {}: Missing assignment operator '='", line, pos),
-            ParserError::InvalidAssignmentOp(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Expected assignment operator '=', not '{}'", line, pos, valuestr),
-            ParserError::EmptyEnv(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Empty environment '{}'", line, pos, valuestr),
-            ParserError::InvalidAccessionTarget(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Invalid accession target '{}'", line, pos, valuestr),
-            ParserError::InvalidAccessionSource(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Invalid accession source '{}'", line, pos, valuestr),
-            ParserError::InvalidInheritanceToken(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Invalid token '{}' in inheritance statement", line, pos, valuestr),
-            ParserError::ParentlessInheritance(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Inheritance in parentless environment: '{}'", line, pos, valuestr),
-            ParserError::DoubleInheritanceParen(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Second opening parenthesis for inheritance: '{}'", line, pos, valuestr),
-            ParserError::WildcardAndElements(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Cannot specify both identifiers and wildcard: '{}'", line, pos, valuestr),
-            ParserError::NotInheritClause =>
-                write!(f, "Parser error: Attempted to push name to something else than an inherit clause"),
-            ParserError::NotAnEnvironment(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Expected an Environment instead of: '{}'", line, pos, valuestr),
-            ParserError::InvalidFunArgToken(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Expected whitespace, brackets, commas, or identifiers, instead of: '{}", line, pos, valuestr),
-            ParserError::DoubleFunArgBracket(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Second opening bracket for function arguments: '{}'", line, pos, valuestr),
-            ParserError::UnclosedArgumentClause(line) =>
-                write!(f, "Parser error at source line {}: Unclosed function argument clause", line),
-            ParserError::MissingFunctionName(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Expected function identifier instead of: '{}'", line, pos, valuestr),
-            ParserError::MissingFunctionArgs(pos,line) =>
-                write!(f, "Parser error at source line {}, token position {}: Expected function arguments", line, pos),
-            ParserError::MissingFunctionBody(pos, line) =>
-                write!(f, "Parser error at source line {}, token position {}: Expected function body", line, pos),
-            ParserError::MissingReturnStatement(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Expected return statement instead of: '{}", line, pos, valuestr),
-            ParserError::InvalidTokenInFnSignature(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Expected inheritance clause or assignment operator instead of: '{}'", line, pos, valuestr),
-            ParserError::UnexpectedReturn(pos, line) =>
-                write!(f, "Parser error at source line {}, token position {}: The `return` keyword is not valid in this context", line, pos),
-            ParserError::InvalidContextForIdentifier(line, valuestr) =>
-                write!(f, "Parser error at source line {}: Expected ParseContext::FunctionCall or ParseContext::Normal, got {}", line, valuestr),
-            ParserError::InvalidTokenInFnCall(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Expected identifier or opening function argument bracket, instead of: '{}'", line, pos, valuestr),
-            ParserError::InvalidTokenInUnaryOp(pos, line, valuestr) =>
-                write!(f, "Parser error at source line {}, token position {}: Expected identifier, digit, boolean, or whitespace in unary operation, instead of: '{}'", line, pos, valuestr),
+        match self.location() {
+            Some(Location { line, column: Some(column), .. }) =>
+                write!(f, "Parser error at line {}, column {}: {}", line, column, self.message()),
+            Some(location) =>
+                write!(f, "Parser error at source line {}, token position {}: {}", location.line, location.token, self.message()),
+            None =>
+                write!(f, "Parser error: {}", self.message()),
         }
     }
 }
\ No newline at end of file
@@ -14,6 +14,8 @@
 //! 
 //! All errors include position information for reporting.
 //! 
+//! [`Parser::parse`] stops at the first error. Tools working on partially broken files can use [`Parser::parse_recovering`] instead, which reports an error for each broken top-level statement and leaves an [`AstNode::Error`] in its place.
+//! 
 //! [`Token`]: ../lexer/enum.Token.html
 //! [`ParserError`]: ./enum.ParserError.html
 
@@ -24,10 +26,14 @@ mod tests;
 pub use astnode::AstNode;
 pub use error::ParserError;
 
-use crate::lexer::Token;
-use crate::symbols::{Keywords, Booleans, Operators, ArithmeticOperators, LogicalOperators, OtherOperators};
-use std::rc::Rc;
-use std::borrow::Borrow;
+use crate::lexer::{Location, Token, TokenStream};
+use crate::span::{line_breaks, Span};
+use crate::symbols::{unit_factor, Keywords, Booleans, Operators, ArithmeticOperators, ComparisonOperators, LogicalOperators, OtherOperators};
+use alloc::rc::Rc;
+use core::borrow::Borrow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
 
 /// The `ParseContext` enum represents the context in which the parser is operating at any given time.
 /// 
@@ -35,27 +41,185 @@ use std::borrow::Borrow;
 #[derive(Debug, PartialEq, Clone)]
 enum ParseContext {
     Normal,
-    Operation,
     Function,
     FunctionReturn,
-    FunctionCall,
 }
 
 impl ToString for ParseContext {
     fn to_string(&self) -> String {
         match self {
             ParseContext::Normal => "ParseContext::Normal".to_string(),
-            ParseContext::Operation => "ParseContext::Operation".to_string(),
             ParseContext::Function => "ParseContext::Function".to_string(),
             ParseContext::FunctionReturn => "ParseContext::FunctionReturn".to_string(),
-            ParseContext::FunctionCall => "ParseContext::FunctionCall".to_string(),
         }
     }
 }
 
-/// The `Parser` struct holds the [`Token`] vector from the lexer, as well as the index of the currently lexed token and the line number.
+/// Default maximum nesting depth of environments and operations (see [`ParserOptions::max_depth`])
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Default maximum number of binary operations in a statement (see [`ParserOptions::max_operations`])
+pub const DEFAULT_MAX_OPERATIONS: usize = 512;
+
+/// Options changing how strictly the [`Parser`] treats its input
+/// 
+/// The default options are lenient, matching the behaviour of a parser created without options, and limit the nesting depth to [`DEFAULT_MAX_DEPTH`] and the operations of a statement to [`DEFAULT_MAX_OPERATIONS`].
+/// 
+/// The struct derives the traits `Debug`, `Clone`, `Copy`, `PartialEq`, and `Eq`, and implements `Default`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Whether unbalanced braces are reported as [`ParserError::UnmatchedBrace`] instead of being ignored in the global environment
+    pub strict_braces: bool,
+    /// Maximum nesting depth of environments and operations, beyond which the input is rejected with [`ParserError::NestingTooDeep`]
+    ///
+    /// The parser, the printer, and the other passes over the AST are recursive, so the limit keeps deeply nested input from overflowing the stack.
+    /// The global environment, every pair of braces or parentheses, every assigned value, every call and accession of a chain such as `env.f[1].x`, and every chain of operations count as a level.
+    /// A chain such as `1 + 2 + 3` is a single level, while an operation nested in an operand, such as `2 * 3` in `1 + 2 * 3`, adds a level.
+    pub max_depth: usize,
+    /// Maximum number of binary operations in a statement, beyond which the input is rejected with [`ParserError::TooManyOperations`]
+    ///
+    /// The operators of a chain such as `1 + 2 + 3` still nest the AST, one operation inside the next, so they are limited separately from [`max_depth`](ParserOptions::max_depth).
+    /// Every operation of a statement counts, including those inside parentheses and calls, and the operations of a statement inside braces count together with the statement enclosing the braces.
+    pub max_operations: usize,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        return ParserOptions { strict_braces: false, max_depth: DEFAULT_MAX_DEPTH, max_operations: DEFAULT_MAX_OPERATIONS };
+    }
+}
+
+/// A step taken by the parser, as recorded by a parser created with [`Parser::with_tracing`]
+///
+/// Steps are recorded in the order they are taken. Every [`ParseStep::Enter`] is matched by a later [`ParseStep::Exit`], also when parsing the construct fails, so the steps form a tree.
+///
+/// The enum derives the traits `Debug`, `Clone`, and `PartialEq`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseStep {
+    /// A token was consumed, at its location. Tokens given back to the input are consumed again later, and recorded again.
+    Token(Location, Token),
+    /// The parser started parsing a construct, e.g., an assignment or an environment
+    Enter(&'static str),
+    /// The parser finished parsing the innermost construct it entered, successfully or not
+    Exit(&'static str),
+    /// The parser decided how to group the operands of an operator, with a description of the decision
+    Decision(Location, String),
+}
+
+/// The binding power of a binary operator, ordered from the loosest to the tightest
+///
+/// Operators of higher precedence group their operands first: `5 + 3 * 2` parses as `5 + (3 * 2)`, and `a == b & c` as `(a == b) & c`.
+/// Operators of equal precedence group from the left, so that `8 - 4 - 2` parses as `(8 - 4) - 2`, unless they are right-associative (see [`is_right_associative`]).
+/// Parentheses group an expression regardless of precedence.
+///
+/// The enum derives the traits `Debug`, `Clone`, `Copy`, `PartialEq`, `Eq`, `PartialOrd`, and `Ord`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Precedence {
+    /// The assignment operator `=`
+    Assignment,
+    /// The logical OR operator `|`
+    Or,
+    /// The logical AND operator `&`
+    And,
+    /// The equality operators `==` and `!=`
+    Equality,
+    /// The ordering operators `<`, `<=`, `>`, and `>=`
+    Comparison,
+    /// The additive operators `+` and `-`
+    Sum,
+    /// The multiplicative operators `*`, `/`, and `%`
+    Product,
+    /// The exponentiation operator `^`
+    Exponent,
+    /// The unary operators `+`, `-`, and `!`, which bind tighter than any binary operator
+    Prefix,
+}
+
+impl Precedence {
+    /// Gets the next tighter precedence, which the right-hand side of a left-associative operator is parsed at
+    fn tighter(self) -> Precedence {
+        match self {
+            Precedence::Assignment => Precedence::Or,
+            Precedence::Or => Precedence::And,
+            Precedence::And => Precedence::Equality,
+            Precedence::Equality => Precedence::Comparison,
+            Precedence::Comparison => Precedence::Sum,
+            Precedence::Sum => Precedence::Product,
+            Precedence::Product => Precedence::Exponent,
+            Precedence::Exponent | Precedence::Prefix => Precedence::Prefix,
+        }
+    }
+}
+
+impl ToString for Precedence {
+    fn to_string(&self) -> String {
+        match self {
+            Precedence::Assignment => "assignment".to_string(),
+            Precedence::Or => "logical OR".to_string(),
+            Precedence::And => "logical AND".to_string(),
+            Precedence::Equality => "equality".to_string(),
+            Precedence::Comparison => "comparison".to_string(),
+            Precedence::Sum => "additive".to_string(),
+            Precedence::Product => "multiplicative".to_string(),
+            Precedence::Exponent => "exponentiation".to_string(),
+            Precedence::Prefix => "prefix".to_string(),
+        }
+    }
+}
+
+/// Gets the [`Precedence`] of a binary operator, or `None` for operators that do not join two operands
+///
+/// The accessor `.` is parsed as a postfix of its operand, and binds tighter than any operator with a precedence, while the logical NOT `!` is only unary.
+///
+/// The parser and the [`printer`](crate::printer) both group operations by this table, so tools that add or remove parentheses should use it to stay consistent with them.
+///
+/// # Examples
+/// ```
+/// use envlang::parser::{precedence_of, Precedence};
+/// use envlang::symbols::{ArithmeticOperators, LogicalOperators, Operators};
+///
+/// let add = precedence_of(&Operators::Arithmetic(ArithmeticOperators::ADD));
+/// let multiply = precedence_of(&Operators::Arithmetic(ArithmeticOperators::MULTIPLY));
+/// assert_eq!(add, Some(Precedence::Sum));
+/// assert!(multiply > add);
+/// assert_eq!(precedence_of(&Operators::Logical(LogicalOperators::NOT)), None);
+/// ```
+pub fn precedence_of(op: &Operators) -> Option<Precedence> {
+    match op {
+        Operators::Other(OtherOperators::ASSIGNMENT) => Some(Precedence::Assignment),
+        Operators::Logical(LogicalOperators::OR) => Some(Precedence::Or),
+        Operators::Logical(LogicalOperators::AND) => Some(Precedence::And),
+        Operators::Comparison(ComparisonOperators::EQ)
+        | Operators::Comparison(ComparisonOperators::NEQ) => Some(Precedence::Equality),
+        Operators::Comparison(_) => Some(Precedence::Comparison),
+        Operators::Arithmetic(ArithmeticOperators::ADD)
+        | Operators::Arithmetic(ArithmeticOperators::SUBTRACT) => Some(Precedence::Sum),
+        Operators::Arithmetic(ArithmeticOperators::EXPONENTIATION) => Some(Precedence::Exponent),
+        Operators::Arithmetic(_) => Some(Precedence::Product),
+        Operators::Logical(LogicalOperators::NOT)
+        | Operators::Other(OtherOperators::ACCESSOR) => None,
+    }
+}
+
+/// Checks whether a binary operator groups from the right, so that `2 ^ 3 ^ 2` parses as `2 ^ (3 ^ 2)`
+///
+/// Other operators of equal precedence group from the left (see [`Precedence`]).
+///
+/// # Examples
+/// ```
+/// use envlang::parser::is_right_associative;
+/// use envlang::symbols::{ArithmeticOperators, Operators};
+///
+/// assert!(is_right_associative(&Operators::Arithmetic(ArithmeticOperators::EXPONENTIATION)));
+/// assert!(!is_right_associative(&Operators::Arithmetic(ArithmeticOperators::SUBTRACT)));
+/// ```
+pub fn is_right_associative(op: &Operators) -> bool {
+    matches!(op, Operators::Arithmetic(ArithmeticOperators::EXPONENTIATION) | Operators::Other(OtherOperators::ASSIGNMENT))
+}
+
+/// The `Parser` struct holds the [`Token`] vector from the lexer, as well as the index of the currently parsed token and the location of each token.
 /// 
-/// The line number is calculated from the amount of recognised line-breaks, and is one-indexed.
+/// The line number is calculated from the amount of recognised line-breaks (see [`is_line_break`](crate::span::is_line_break)) in whitespace and string literals, and is one-indexed.
 /// 
 /// # Panics
 /// 
@@ -64,25 +228,151 @@ impl ToString for ParseContext {
 /// # Errors
 /// 
 /// All parser methods return `Result<AstNode, ParserError>` types. The errors contain a human-readable description of the intended use, as well as information on:
-/// - The token index where the error was triggered.
+/// - The [`Location`] where the error was triggered: the token index and line number, plus the column and byte span in the source code if the parser was created with [`Parser::with_locations`].
 /// - The error-triggering token (in most cases).
-/// - The line number from the input source code.
 /// 
 /// [`Token`]: ../lexer/enum.Token.html
 /// [`ParserError`]: ./enum.ParserError.html
 pub struct Parser {
     tokens: Vec<Token>,
+    locations: Vec<Location>,
+    source: Option<Rc<str>>,
+    options: ParserOptions,
     current: usize,
-    line: usize,
+    depth: usize,
+    // Number of operations parsed so far in the current statement, together with the statements enclosing it, limited by `ParserOptions::max_operations`
+    // The count never decreases within a statement, so it is at least the number of operations nesting the AST above any position, and the deepest statement of an environment is kept for the statements enclosing it
+    operations: usize,
+    deepest_operations: usize,
+    // Position of the first token within the full token vector, and the bindings preceding them, when parsing a single statement during recovery
+    offset: usize,
+    seed: Vec<Rc<AstNode>>,
+    trace: Option<Vec<ParseStep>>,
 }
 
 impl Parser {
     /// Initializes a new Parser with a given input token vector.
+    /// 
+    /// The line of each token is counted from the line breaks (see [`is_line_break`](crate::span::is_line_break)) in the whitespace and string literals before it.
+    /// Tokens do not record how string literals were written, so escaped line breaks such as `\n` are counted as well; [`Parser::from_stream`] takes the lines from the source text instead.
     pub fn new(tokens: Vec<Token>) -> Self {
+        let mut line = 1;
+        let locations = tokens.iter()
+            .enumerate()
+            .map(|(idx, token)| {
+                let location = Location::new(idx, line);
+                if let Token::Whitespace(text) | Token::StringLiteral(text) = token {
+                    line += line_breaks(text);
+                }
+                location
+            })
+            .collect();
         Self {
             tokens,
+            locations,
+            source: None,
+            options: ParserOptions::default(),
             current: 0,
-            line: 1
+            depth: 0,
+            operations: 0,
+            deepest_operations: 0,
+            offset: 0,
+            seed: Vec::new(),
+            trace: None,
+        }
+    }
+
+    /// Initializes a new Parser with tokens paired with their source locations, as returned by [`Lexer::tokenize_with_locations`].
+    /// 
+    /// Errors from the parser then report the line, column, and byte span of the offending token in the source text.
+    /// 
+    /// [`Lexer::tokenize_with_locations`]: ../lexer/struct.Lexer.html#method.tokenize_with_locations
+    pub fn with_locations(tokens: Vec<(Token, Location)>) -> Self {
+        let (tokens, locations) = tokens.into_iter().unzip();
+        Self {
+            tokens,
+            locations,
+            source: None,
+            options: ParserOptions::default(),
+            current: 0,
+            depth: 0,
+            operations: 0,
+            deepest_operations: 0,
+            offset: 0,
+            seed: Vec::new(),
+            trace: None,
+        }
+    }
+
+    /// Initializes a new Parser from a [`TokenStream`], as returned by [`Lexer::tokenize_stream`].
+    /// 
+    /// The parser reports source locations like one created with [`Parser::with_locations`], and keeps the source text, which [`source`](Parser::source) returns.
+    /// 
+    /// [`Lexer::tokenize_stream`]: ../lexer/struct.Lexer.html#method.tokenize_stream
+    pub fn from_stream(stream: TokenStream) -> Self {
+        let (source, tokens) = stream.into_parts();
+        let mut parser = Self::with_locations(tokens);
+        parser.source = Some(source);
+        return parser;
+    }
+
+    /// Sets the [`ParserOptions`] of the parser, replacing the default lenient options
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        return self;
+    }
+
+    /// Enables recording the [`ParseStep`]s taken by [`parse`](Parser::parse), e.g., to explain how a construct was parsed
+    ///
+    /// The steps of [`parse_recovering`](Parser::parse_recovering) are only recorded for its first, full parse of the input.
+    pub fn with_tracing(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        return self;
+    }
+
+    /// Takes the [`ParseStep`]s recorded so far, leaving tracing enabled
+    ///
+    /// Returns an empty vector if tracing is not enabled (see [`with_tracing`](Parser::with_tracing)).
+    pub fn take_trace(&mut self) -> Vec<ParseStep> {
+        return self.trace.as_mut().map(core::mem::take).unwrap_or_default();
+    }
+
+    /// Records a step if tracing is enabled, only building the step when it is recorded
+    fn record(&mut self, step: impl FnOnce(&Self) -> ParseStep) {
+        if self.trace.is_some() {
+            let step = step(self);
+            if let Some(trace) = self.trace.as_mut() {
+                trace.push(step);
+            }
+        }
+    }
+
+    /// Parses a construct, recording when the parser enters and exits it if tracing is enabled
+    fn traced<T>(&mut self, construct: &'static str, parse: impl FnOnce(&mut Self) -> Result<T, ParserError>) -> Result<T, ParserError> {
+        self.record(|_| ParseStep::Enter(construct));
+        let result = parse(self);
+        self.record(|_| ParseStep::Exit(construct));
+        return result;
+    }
+
+    /// Gets the source text the tokens were lexed from, if the parser was created with [`Parser::from_stream`]
+    pub fn source(&self) -> Option<&str> { self.source.as_deref() }
+
+    /// Gets the [`Location`] of the token at a position.
+    /// 
+    /// Positions past the final token are located at the final token. Without source locations, only the token position and the counted line are known.
+    fn location(&self, pos: usize) -> Location {
+        match self.locations.get(pos).or(self.locations.last()) {
+            Some(location) => Location { token: pos + self.offset, ..*location },
+            None => Location::new(pos + self.offset, 1),
+        }
+    }
+
+    /// Gets the byte span from the start of one token to the end of another, or `None` without source locations
+    fn span(&self, first: usize, last: usize) -> Option<Span> {
+        match (self.locations.get(first)?.span, self.locations.get(last)?.span) {
+            (Some(first), Some(last)) => return Some(Span::new(first.start, last.end)),
+            _ => return None,
         }
     }
 
@@ -90,7 +380,13 @@ impl Parser {
     fn peek(&self) -> Option<&Token> { self.tokens.get(self.current) }
 
     /// Increments the `current` [`Token`] index.
-    fn next(&mut self) { self.current += 1; }
+    fn next(&mut self) {
+        self.record(|parser| match parser.peek() {
+            Some(token) => ParseStep::Token(parser.location(parser.current), token.clone()),
+            None => ParseStep::Token(parser.location(parser.current), Token::EOF),
+        });
+        self.current += 1;
+    }
 
     /// Gets current [`Token`] and increment the `current` Token index.
     /// 
@@ -98,8 +394,7 @@ impl Parser {
     fn advance(&mut self) -> Option<(usize, Rc<Token>)> {
         if self.current < self.tokens.len() {
             let pos = self.current;
-            // Safety: Length is guaranteed to be valid
-            let ch = Rc::new(self.peek().unwrap().clone());
+            let ch = Rc::new(self.peek()?.clone());
             self.next();
             return Some((pos, ch));
         } else {
@@ -113,9 +408,139 @@ impl Parser {
     /// 
     /// The parsing context is always set to [`ParseContext::Normal`].
     /// 
+    /// With [`ParserOptions::strict_braces`], the braces are checked for balance before parsing.
+    /// 
+    /// # Errors
+    /// * [`ParserError::UnmatchedBrace`]: With strict braces, a brace has no matching pair.
+    /// * [`ParserError::NestingTooDeep`]: The input is nested deeper than [`ParserOptions::max_depth`].
+    /// * [`ParserError::TooManyOperations`]: A statement has more operations than [`ParserOptions::max_operations`].
+    /// * Other errors are returned as [`ParserError`] from the parser submethods
+    pub fn parse(&mut self) -> Result<AstNode, ParserError> {
+        if self.options.strict_braces {
+            self.check_braces()?;
+        }
+        return self.parse_environment(None, None, ParseContext::Normal);
+    }
+
+    /// Parses the input like [`parse`](Parser::parse), but recovers from errors in top-level statements
+    /// 
+    /// Returns the global environment together with the errors encountered, in source order.
+    /// Each top-level statement that could not be parsed is replaced by an [`AstNode::Error`], so that later passes can work on the rest of a partially broken file.
+    /// Input without errors is parsed exactly as by [`parse`](Parser::parse).
+    /// 
+    /// Top-level statements end at a `;` outside of braces, or at a closing brace that ends the braces of the statement, unless it is followed by an operator or a `;`.
+    /// Errors that are not confined to a single statement, such as [`ParserError::UnmatchedBrace`] with [`ParserOptions::strict_braces`], are returned without any statements.
+    pub fn parse_recovering(&mut self) -> (AstNode, Vec<ParserError>) {
+        let empty = AstNode::Environment { name: None, bindings: Vec::new(), parent: None };
+        if self.options.strict_braces {
+            if let Err(err) = self.check_braces() {
+                return (empty, vec![err]);
+            }
+        }
+        self.current = 0;
+        if let Ok(ast) = self.parse_environment(None, None, ParseContext::Normal) {
+            return (ast, Vec::new());
+        }
+
+        let mut bindings: Vec<Rc<AstNode>> = Vec::new();
+        let mut errors: Vec<ParserError> = Vec::new();
+        for (start, end) in self.statements() {
+            let mut statement = Parser {
+                tokens: self.tokens[start..end].to_vec(),
+                locations: self.locations[start..end].to_vec(),
+                source: None,
+                options: self.options,
+                current: 0,
+                depth: 0,
+                operations: 0,
+                deepest_operations: 0,
+                offset: start,
+                seed: bindings.clone(),
+                trace: None,
+            };
+            statement.tokens.push(Token::EOF);
+            statement.locations.push(self.location(end));
+            match statement.parse_environment(None, None, ParseContext::Normal) {
+                Ok(AstNode::Environment { bindings: parsed, .. }) => bindings = parsed,
+                Ok(_) => (),
+                Err(err) => {
+                    let span = match (self.locations[start].span, self.locations[end - 1].span) {
+                        (Some(first), Some(last)) => Some(Span::new(first.start, last.end)),
+                        _ => None,
+                    };
+                    bindings.push(Rc::new(AstNode::Error(span)));
+                    errors.push(err);
+                },
+            }
+        }
+        self.current = self.tokens.len();
+        return (AstNode::Environment { name: None, bindings, parent: None }, errors);
+    }
+
+    /// Splits the tokens into top-level statements for [`parse_recovering`](Parser::parse_recovering)
+    /// 
+    /// Returns the start and end positions of each statement, leaving out leading whitespace, statements of only whitespace, and the EOF token.
+    fn statements(&self) -> Vec<(usize, usize)> {
+        let is_blank = |token: &Token| matches!(token, Token::Whitespace(_) | Token::EOF);
+        let mut statements: Vec<(usize, usize)> = Vec::new();
+        let mut start: Option<usize> = None;
+        let mut depth: usize = 0;
+        for (pos, token) in self.tokens.iter().enumerate() {
+            if start.is_none() && !is_blank(token) {
+                start = Some(pos);
+            }
+            let ends = match token {
+                Token::LeftBrace => {
+                    depth += 1;
+                    false
+                },
+                Token::RightBrace => {
+                    depth = depth.saturating_sub(1);
+                    let next = self.tokens[pos + 1..].iter().find(|token| !is_blank(token));
+                    depth == 0 && !matches!(next, Some(Token::Operator(_) | Token::LineTerminator))
+                },
+                Token::LineTerminator => depth == 0,
+                _ => false,
+            };
+            if let (true, Some(first)) = (ends, start) {
+                statements.push((first, pos + 1));
+                start = None;
+            }
+        }
+        if let Some(first) = start {
+            let end = match self.tokens.last() {
+                Some(Token::EOF) => self.tokens.len() - 1,
+                _ => self.tokens.len(),
+            };
+            if first < end {
+                statements.push((first, end));
+            }
+        }
+        return statements;
+    }
+
+    /// Checks that every brace in the token vector has a matching pair.
+    /// 
+    /// Without the check, the parser ignores extra braces in the global environment, and lets environments left open finish on the EOF token.
+    /// 
     /// # Errors
-    /// Errors are returned as [`ParserError`] from the parser submethods
-    pub fn parse(&mut self) -> Result<AstNode, ParserError> { self.parse_environment(None, None, ParseContext::Normal) }
+    /// * [`ParserError::UnmatchedBrace`]: A closing brace was not preceded by an opening one, or an opening brace was never closed. The innermost unclosed brace is reported.
+    fn check_braces(&self) -> Result<(), ParserError> {
+        let mut open: Vec<usize> = Vec::new();
+        for (pos, token) in self.tokens.iter().enumerate() {
+            match token {
+                Token::LeftBrace => open.push(pos),
+                Token::RightBrace => {
+                    open.pop().ok_or_else(|| ParserError::UnmatchedBrace(self.location(pos), "}".into()))?;
+                },
+                _ => (),
+            }
+        }
+        match open.pop() {
+            Some(pos) => return Err(ParserError::UnmatchedBrace(self.location(pos), "{".into())),
+            None => return Ok(()),
+        }
+    }
 
     /// Returns an [`AstNode::Environment`] representing an environment.
     /// 
@@ -143,18 +568,40 @@ impl Parser {
     /// * [`ParserError::UnexpectedEOF`]: A non-global normal environment or an operation encountered the EOF token.
     /// * [`ParserError::MissingReturnStatement`]: A function environment encountered the EOF token.
     /// * [`ParserError::UnclosedEnvironment`]: EOF token was consumed before a non-global, non-function-return environment finished parsing.
+    /// * [`ParserError::NestingTooDeep`]: The environment is nested deeper than [`ParserOptions::max_depth`].
     fn parse_environment(&mut self, parent: Option<Rc<AstNode>>, name: Option<Rc<str>>, context: ParseContext) -> Result<AstNode, ParserError> {
-        // Create a temporary environment to handle parentage
+        // Every nested environment passes through here, every chain of operations through parse_operations, and every call and accession through parse_postfix, so together they bound the recursion of the parser and of every pass over its output
+        self.nest()?;
+        let deepest_operations = core::mem::replace(&mut self.deepest_operations, self.operations);
+        let construct = match context {
+            ParseContext::Normal => "environment",
+            ParseContext::Function => "function body",
+            ParseContext::FunctionReturn => "return statement",
+        };
+        let result = self.traced(construct, |parser| parser.parse_environment_contents(parent, name, context));
+        self.operations = self.operations.max(self.deepest_operations);
+        self.deepest_operations = deepest_operations;
+        self.depth -= 1;
+        return result;
+    }
+
+    /// Parses the contents of an environment (see [`parse_environment`](Parser::parse_environment))
+    fn parse_environment_contents(&mut self, parent: Option<Rc<AstNode>>, name: Option<Rc<str>>, context: ParseContext) -> Result<AstNode, ParserError> {
+        // Each statement counts its operations from the operations enclosing the environment
+        let operations = self.operations;
+        // Whether a function body opened with a brace, which closes after its return statement
+        let mut braced = false;
+        // Create a temporary environment to handle parentage, continuing after the statements parsed so far during recovery
         let mut current_env: AstNode = AstNode::Environment {
             name: name.clone(),
-            bindings: Vec::new(),
+            bindings: if parent.is_none() { core::mem::take(&mut self.seed) } else { Vec::new() },
             parent: parent.clone(),
         };
 
         while let Some((pos, token)) = self.advance() {
             match token.borrow() {
                 Token::LeftBrace => {
-                    // Ignore extra left brace in the global environment
+                    // Ignore extra left brace in the global environment (reported beforehand with strict braces)
                     if parent.is_none() { continue; };
 
                     // Create a sub-environment if `ParseContext::Normal`
@@ -167,17 +614,28 @@ impl Parser {
                         if let AstNode::Environment { ref mut bindings, .. } = current_env {
                             bindings.push(Rc::new(sub_env));
                         }
+                        // The value of an assignment ends with its braces, unless an operation or a line terminator follows them
+                        if name.is_some() && !self.continues_value() {
+                            return Ok(current_env);
+                        }
                     } else {
+                        braced = braced || context == ParseContext::Function;
                         continue;
                     }
                 },
                 Token::RightBrace => {
-                    // Ignore extra right brace in the global environment
+                    // Ignore extra right brace in the global environment (reported beforehand with strict braces)
                     if parent.is_none() { continue; };
                     return Ok(current_env);
                 },
-                Token::LeftParen => continue,           // Covered by parse_inherit_clause
-                Token::RightParen => continue,          // Covered by parse_inherit_clause
+                Token::LeftParen => {
+                    let env_rc: Option<Rc<AstNode>> = Some(Rc::new(current_env.clone()));
+                    let node: AstNode = self.traced("group", |parser| parser.parse_group(&env_rc))?;
+                    if let AstNode::Environment { ref mut bindings, .. } = current_env {
+                        bindings.push(Rc::new(node));
+                    }
+                },
+                Token::RightParen => continue,          // Covered by parse_inherit_clause and parse_group
                 Token::Comma => continue,               // Covered by parse_inherit_clause and parse_function_clause
                 Token::LeftBracket => continue,         // Covered by parse_function_clause
                 Token::RightBracket => continue,        // Covered by parse_function_clause
@@ -188,15 +646,7 @@ impl Parser {
                     continue;
                 },
                 Token::Identifier(id) => {
-                    let mut inner_context = ParseContext::Normal;
-                    if let Some(token) = self.peek() {
-                        match token {
-                            Token::LeftBracket => inner_context = ParseContext::FunctionCall,
-                            _ => (),
-                        }
-                    }
-                    
-                    let node: AstNode = self.parse_identifier(&id, inner_context)?;
+                    let node: AstNode = self.parse_postfix(pos, AstNode::Identifier(id.clone()))?;
                     if let AstNode::Environment { ref mut bindings, .. } = current_env {
                         bindings.push(Rc::new(node));
                     }
@@ -208,7 +658,7 @@ impl Parser {
                     }
                 },
                 Token::StringLiteral(string) => {
-                    let node: AstNode = self.parse_string(&string)?;
+                    let node: AstNode = self.parse_string(string)?;
                     if let AstNode::Environment { ref mut bindings, .. } = current_env {
                         bindings.push(Rc::new(node));
                     }
@@ -224,10 +674,12 @@ impl Parser {
                     }
                 },
                 Token::Keyword(Keywords::LET) => {
-                    let node: AstNode = self.parse_assignment(Some(Rc::new(current_env.clone())))?;
+                    let env_rc: Rc<AstNode> = Rc::new(current_env.clone());
+                    let node: AstNode = self.traced("assignment", |parser| parser.parse_assignment(Some(env_rc)))?;
                     if let AstNode::Environment { ref mut bindings, .. } = current_env {
                         bindings.push(Rc::new(node));
                     }
+                    self.end_statement(operations);
                 },
                 Token::Keyword(Keywords::RETURN) =>
                     // Return keyword is only valid in ParseContext::Function
@@ -243,11 +695,14 @@ impl Parser {
                                 bindings.push(Rc::new(return_env));
                             }
 
+                            if braced {
+                                self.close_function_body();
+                            }
                             return Ok(current_env);
                         },
-                        _ => return Err(ParserError::UnexpectedReturn(pos, self.line)),
+                        _ => return Err(ParserError::UnexpectedReturn(self.location(pos))),
                     }
-                Token::Whitespace(ws) => self.parse_whitespace(ws),
+                Token::Whitespace(_) => (),
                 Token::Operator(op) => {
                     let prev_operand: Option<Rc<AstNode>> = if let AstNode::Environment { ref mut bindings, .. } = current_env {
                         bindings.pop()
@@ -256,7 +711,7 @@ impl Parser {
                     };
                     if let Some(prev_operand) = prev_operand {
                         let env_rc: Rc<AstNode> = Rc::new(current_env.clone());
-                        let node: AstNode = self.parse_operator(Some(env_rc), op, &prev_operand)?;
+                        let node: AstNode = self.traced("binary operation", |parser| parser.parse_operator(&Some(env_rc), op, &prev_operand))?;
 
                         if let AstNode::Environment { ref mut bindings, .. } = current_env {
                             bindings.push(Rc::new(node));
@@ -268,7 +723,8 @@ impl Parser {
                             | Operators::Logical(LogicalOperators::NOT)
                             | Operators::Arithmetic(ArithmeticOperators::SUBTRACT) => {
                                 // Valid unary operator, call parse_unary_operator
-                                let node = self.parse_unary_operator(op)?;
+                                let env_rc: Option<Rc<AstNode>> = Some(Rc::new(current_env.clone()));
+                                let node = self.traced("unary operation", |parser| parser.parse_unary_operator(&env_rc, op))?;
 
                                 if let AstNode::Environment { ref mut bindings, .. } = current_env {
                                     bindings.push(Rc::new(node));
@@ -276,28 +732,21 @@ impl Parser {
                             },
                             _ => {
                                 // Invalid unary operator, must be a binary operator
-                                return Err(ParserError::BinaryOpWithNoLHS(pos, self.line));
+                                return Err(ParserError::BinaryOpWithNoLHS(self.location(pos)));
                             }
                         }
                     }
                 },
                 Token::LineTerminator => {
+                    self.end_statement(operations);
                     match context {
-                        ParseContext::Operation => {
-                            // Return the right-hand side of the operation
-                            if let AstNode::Environment { ref bindings, .. } = current_env {
-                                if bindings.len() == 1 {
-                                    return Ok((*bindings[0]).clone());
-                                }
-                            }
-                        },
                         ParseContext::Normal => {
                             // Return the current environment if it is named
                             if name.is_some() {
                                 return Ok(current_env.clone());
                             }
                         },
-                        ParseContext::Function | ParseContext::FunctionCall => {
+                        ParseContext::Function => {
                             continue;
                         },
                         ParseContext::FunctionReturn => {
@@ -317,12 +766,7 @@ impl Parser {
                         },
                         ParseContext::Function => {
                             // Functions cannot finish without return statements
-                            return Err(ParserError::MissingReturnStatement(pos, self.line, "".into()))
-                        },
-                        | ParseContext::FunctionCall
-                        | ParseContext::Operation => {
-                            // Operations and function calls cannot finish on EOF
-                            return Err(ParserError::UnexpectedEOF(pos, self.line));
+                            return Err(ParserError::MissingReturnStatement(self.location(pos), "".into()))
                         },
                     }
                 },
@@ -335,10 +779,33 @@ impl Parser {
             | ParseContext::FunctionReturn => {
                 return Ok(current_env);
             },
-            _ => Err(ParserError::UnclosedEnvironment(self.line))
+            _ => Err(ParserError::UnclosedEnvironment(self.location(self.current)))
+        }
+    }
+
+    /// Consumes the closing brace of a function body after its return statement
+    /// 
+    /// A return statement ending on its line terminator leaves the closing brace of the function, while one ending on the brace itself (e.g. `{ return a }`) has already consumed it.
+    fn close_function_body(&mut self) {
+        if self.current == 0 || self.tokens.get(self.current - 1) != Some(&Token::LineTerminator) {
+            return;
+        }
+        let start = self.current;
+        while let Some(Token::Whitespace(_)) = self.peek() {
+            self.current += 1;
+        }
+        match self.peek() {
+            Some(Token::RightBrace) => self.current += 1,
+            _ => self.current = start,
         }
     }
 
+    /// Checks whether the tokens after the braces of an assigned value continue the value, i.e. an operation, a call, or the line terminator of the assignment follows them
+    fn continues_value(&self) -> bool {
+        let following = self.tokens[self.current.min(self.tokens.len())..].iter().find(|token| !matches!(token, Token::Whitespace(_)));
+        return matches!(following, Some(Token::Operator(_) | Token::LeftBracket | Token::LineTerminator));
+    }
+
     /// Returns an [`AstNode::Let`] representing an assignment operation.
     /// 
     /// # Arguments
@@ -351,17 +818,17 @@ impl Parser {
     fn parse_assignment(&mut self, parent_env: Option<Rc<AstNode>>) -> Result<AstNode, ParserError> {
         while let Some((pos, token)) = self.advance() {
             match token.borrow() {
-                Token::Whitespace(ws) => self.parse_whitespace(ws),
+                Token::Whitespace(_) => (),
                 Token::Keyword(Keywords::FUN) => {
-                    return Ok(self.parse_function_declaration(&parent_env)?);
+                    return self.traced("function declaration", |parser| parser.parse_function_declaration(&parent_env));
                 }, 
                 Token::Identifier(id) => {
-                    return Ok(self.construct_let_statement(&parent_env, id, ParseContext::Normal)?);
+                    return self.construct_let_statement(&parent_env, id, self.span(pos, pos), ParseContext::Normal);
                 },
-                _ => return Err(ParserError::MissingLetIdentifier(pos, self.line)), 
+                _ => return Err(ParserError::MissingLetIdentifier(self.location(pos))), 
             }
         }
-        Err(ParserError::ParserLogicError(self.current, self.line))
+        Err(ParserError::ParserLogicError(self.location(self.current)))
     }
 
     /// Returns an [`AstNode::Function`] representing a function declaration.
@@ -386,34 +853,33 @@ impl Parser {
         let mut fn_return: Option<AstNode> = None;
 
         // Step 1: Parse function name
+        let mut span: Option<Span> = None;
         while let Some((pos, token)) = self.advance() {
             match token.borrow() {
-                Token::Whitespace(ws) => self.parse_whitespace(ws),
+                Token::Whitespace(_) => (),
                 Token::Identifier(id) => {
                     fn_name = Some(id.clone());
+                    span = self.span(pos, pos);
                     break;
                 },
-                _ => return Err(ParserError::MissingFunctionName(pos, self.line, token.to_string())),
+                _ => return Err(ParserError::MissingFunctionName(self.location(pos), token.to_string())),
             }
         }
 
         // Step 2: Parse function arguments (no allowed whitespace between name and arguments)
-        while let Some((pos, token)) = self.advance() {
+        if let Some((pos, token)) = self.advance() {
             match token.borrow() {
-                Token::LeftBracket => {
-                    fn_args = Some(self.parse_function_clause()?);
-                    break;
-                },
-                _ => return Err(ParserError::MissingFunctionArgs(pos, self.line)),
+                Token::LeftBracket => fn_args = Some(self.traced("parameters", |parser| parser.parse_function_clause(false))?),
+                _ => return Err(ParserError::MissingFunctionArgs(self.location(pos))),
             }
         }
 
         // Step 3: Parse optional inheritance clause
         while let Some((pos, token)) = self.advance() {
             match token.borrow() {
-                Token::Whitespace(ws) => self.parse_whitespace(ws),
+                Token::Whitespace(_) => (),
                 Token::Keyword(Keywords::INHERIT) => {
-                    inheritance = Some(Rc::new(self.parse_inherit_clause()?));
+                    inheritance = Some(Rc::new(self.traced("inherit clause", Parser::parse_inherit_clause)?));
                     break;
                 },
                 Token::Operator(Operators::Other(OtherOperators::ASSIGNMENT)) => {
@@ -421,14 +887,14 @@ impl Parser {
                     self.current -= 1;
                     break;
                 },
-                _ => return Err(ParserError::InvalidTokenInFnSignature(pos, self.line, token.to_string())),
+                _ => return Err(ParserError::InvalidTokenInFnSignature(self.location(pos), token.to_string())),
             }
         }
 
         // Step 4: Parse assignment operator, function body, and return statement
         while let Some((pos, token)) = self.advance() {
             match token.borrow() {
-                Token::Whitespace(ws) => self.parse_whitespace(ws),
+                Token::Whitespace(_) => (),
                 Token::Operator(Operators::Other(OtherOperators::ASSIGNMENT)) => {
                     let body = self.parse_environment(
                         parent_env.clone(),
@@ -454,17 +920,17 @@ impl Parser {
                             break;
                         }
                     }
-                    return Err(ParserError::MissingReturnStatement(pos, self.line, "".into()));
+                    return Err(ParserError::MissingReturnStatement(self.location(pos), "".into()));
                 },
-                _ => return Err(ParserError::MissingAssignmentOp(pos, self.line)),
+                _ => return Err(ParserError::MissingAssignmentOp(self.location(pos))),
             }
         }
 
         // Validate that required components were parsed
-        let fn_name = fn_name.ok_or_else(|| ParserError::MissingFunctionName(self.current, self.line, "".into()))?;
-        let fn_args = fn_args.ok_or_else(|| ParserError::MissingFunctionArgs(self.current, self.line))?;
-        let fn_body = fn_body.ok_or_else(|| ParserError::MissingFunctionBody(self.current, self.line))?;
-        let fn_return = fn_return.ok_or_else(|| ParserError::MissingReturnStatement(self.current, self.line, "".into()))?;
+        let fn_name = fn_name.ok_or_else(|| ParserError::MissingFunctionName(self.location(self.current), "".into()))?;
+        let fn_args = fn_args.ok_or(ParserError::MissingFunctionArgs(self.location(self.current)))?;
+        let fn_body = fn_body.ok_or(ParserError::MissingFunctionBody(self.location(self.current)))?;
+        let fn_return = fn_return.ok_or_else(|| ParserError::MissingReturnStatement(self.location(self.current), "".into()))?;
 
         // Construct the complete function node
         Ok(AstNode::Let {
@@ -473,29 +939,35 @@ impl Parser {
                 params: Rc::new(fn_args),
                 body: Rc::new(fn_body),
                 r#return: Rc::new(fn_return),
+                span,
             })),
             inherit: inheritance,
+            span,
         })
     }
 
     /// Returns an [`AstNode::FunctionArgs`] representing the arguments of a function.
     /// 
+    /// # Arguments
+    /// * `call`: Whether the arguments are those of a function call rather than the parameters of a declaration.
+    ///   Parameters can only be identifiers, while call arguments can also be literals, accessions, and function calls.
+    /// 
     /// # Errors
-    /// * Any errors bubbled up from [`parse_identifier`](Parser::parse_identifier).
+    /// * Any errors bubbled up from [`parse_postfix`](Parser::parse_postfix) and [`parse_number`](Parser::parse_number).
     /// * [`ParserError::DoubleFunArgBracket`]: Two (or more) left brackets in the function argument clause.
     /// * [`ParserError::UnclosedArgumentClause`]: EOF token met before finishing the argument clause.
-    /// * [`ParserError::InvalidFunArgToken`]: Any other token than identifiers, commas, or the EOF token met before finishing the argument clause.
+    /// * [`ParserError::InvalidFunArgToken`]: Any other token than valid arguments, commas, or the EOF token met before finishing the argument clause.
     /// * [`ParserError::ParserLogicError`]: Parser somehow finished the token stream without errors (catch-all for seemingly impossible scenarios).
-    fn parse_function_clause(&mut self) -> Result<AstNode, ParserError> {
+    fn parse_function_clause(&mut self, call: bool) -> Result<AstNode, ParserError> {
         let mut result = AstNode::FunctionArgs(Vec::new());
         while let Some((pos, token)) = self.advance() {
             match token.borrow() {
-                Token::Whitespace(ws) => self.parse_whitespace(ws),
+                Token::Whitespace(_) => (),
                 Token::LeftBracket => {
                     // If the element vector is non-empty, this represents a syntax error
                     if let Some(names) = result.get_params() {
                         if !names.is_empty() {
-                            return Err(ParserError::DoubleFunArgBracket(pos, self.line, token.to_string()));
+                            return Err(ParserError::DoubleFunArgBracket(self.location(pos), token.to_string()));
                         }
                     }
                     continue;
@@ -509,27 +981,43 @@ impl Parser {
                 },
                 Token::Identifier(id) => {
                     // Add identifier pointer to vector
+                    let node = if call {
+                        self.parse_postfix(pos, AstNode::Identifier(id.clone()))?
+                    } else {
+                        self.parse_identifier(id, ParseContext::Normal)?
+                    };
                     result.set_field::<AstNode>(|v| {
                         let AstNode::FunctionArgs(args) = v else {
-                            unreachable!("Safety: Will always be AstNode::FunctionArgs");
+                            return Err(ParserError::ParserLogicError(self.location(pos)));
                         };
-                        let node = self.parse_identifier(&id, ParseContext::Normal)?;
                         args.push(Rc::new(node));
                         Ok(())
                     })?;
                     continue;
                 },
+                Token::Number(_) | Token::StringLiteral(_) | Token::Boolean(_) if call => {
+                    let node = match token.borrow() {
+                        Token::StringLiteral(string) => self.parse_string(string)?,
+                        Token::Boolean(Booleans::TRUE) => AstNode::Boolean(true),
+                        Token::Boolean(Booleans::FALSE) => AstNode::Boolean(false),
+                        _ => self.parse_number(pos, &token)?,
+                    };
+                    if let AstNode::FunctionArgs(ref mut args) = result {
+                        args.push(Rc::new(node));
+                    }
+                    continue;
+                },
                 Token::EOF => {
                     // ERROR: Unclosed argument clause
-                    return Err(ParserError::UnclosedArgumentClause(self.line));
+                    return Err(ParserError::UnclosedArgumentClause(self.location(self.current)));
                 }
                 _ => {
                     // ERROR: Not a valid symbol in a function clause (could be unclosed argument clause!)
-                    return Err(ParserError::InvalidFunArgToken(pos, self.line, token.to_string()));
+                    return Err(ParserError::InvalidFunArgToken(self.location(pos), token.to_string()));
                 },
             }
         }
-        return Err(ParserError::ParserLogicError(self.current, self.line));
+        return Err(ParserError::ParserLogicError(self.location(self.current)));
     }
 
     /// Returns an [`AstNode::Let`] representing the assignment.
@@ -537,6 +1025,7 @@ impl Parser {
     /// # Arguments
     /// * `parent_env`: An `Option`al reference-counted pointer to the parent environment, or `None` for the global environment.
     /// * `id`: A reference-counted pointer to the name of the assignment.
+    /// * `span`: The byte span of the name, or `None` without source locations.
     /// * `context`: A [`ParseContext`] representing the context within which the assignment is done.
     /// 
     /// # Errors
@@ -544,18 +1033,19 @@ impl Parser {
     /// * [`ParserError::InvalidAssignmentOp`]: Any other operator than the assignment operator encountered after either an identifier or an inheritance clause.
     /// * [`ParserError::MissingAssignmentOp`]: No assignment operator was found.
     /// * [`ParserError::ParserLogicError`]: Parser somehow finished the token stream without errors (catch-all for seemingly impossible scenarios).
-    fn construct_let_statement(&mut self, parent_env: &Option<Rc<AstNode>>, id: &Rc<str>, context: ParseContext) -> Result<AstNode, ParserError> {
+    fn construct_let_statement(&mut self, parent_env: &Option<Rc<AstNode>>, id: &Rc<str>, span: Option<Span>, context: ParseContext) -> Result<AstNode, ParserError> {
         let mut result = AstNode::Let {
             name: id.clone(),
             value: None,
-            inherit: None
+            inherit: None,
+            span,
         };
     
         while let Some((pos, token)) = self.advance() {
             match token.borrow() {
-                Token::Whitespace(ws) => self.parse_whitespace(ws),
+                Token::Whitespace(_) => (),
                 Token::Keyword(Keywords::INHERIT) => {
-                    let inheritance = self.parse_inherit_clause()?; 
+                    let inheritance = self.traced("inherit clause", Parser::parse_inherit_clause)?; 
 
                     // Modify the mutable Let object from above to contain the inheritance clause on the inherit element
                     result.set_field::<AstNode>(|v| {
@@ -563,36 +1053,35 @@ impl Parser {
                             *inherit = Some(Rc::new(inheritance));
                         }
                         Ok(())
-                    }).expect("Safety: Will always be AstNode::Let");
+                    })?;
                     continue;
                 },
                 Token::Operator(op) => {
                     if *op == Operators::Other(OtherOperators::ASSIGNMENT) {
                         let expr: AstNode = self.parse_environment(parent_env.clone(), Some(id.clone()), context)?;
                         
-                        let let_env: Rc<AstNode>;
-                        if expr.is_single_element_env() {
-                            let_env = self.flatten_environment(&expr, pos, &token)?;
+                        let let_env: Rc<AstNode> = if expr.is_single_element_env() {
+                            self.flatten_environment(&expr, pos, &token)?
                         } else {
-                            let_env = Rc::new(expr);
-                        }
+                            Rc::new(expr)
+                        };
 
                         result.set_field::<AstNode>(|v| {
                             if let AstNode::Let{ value, .. } = v {
                                 *value = Some(let_env);
                             }
                             Ok(())
-                        }).expect("Safety: Will always be AstNode::Let");
+                        })?;
 
                         return Ok(result);
                     } else {
-                        return Err(ParserError::InvalidAssignmentOp(pos, self.line, token.to_string()));
+                        return Err(ParserError::InvalidAssignmentOp(self.location(pos), token.to_string()));
                     }
                 },
-                _ => return Err(ParserError::MissingAssignmentOp(pos, self.line)),
+                _ => return Err(ParserError::MissingAssignmentOp(self.location(pos))),
             }
         }
-        Err(ParserError::ParserLogicError(self.current, self.line))
+        Err(ParserError::ParserLogicError(self.location(self.current)))
     }
 
     /// Returns an [`AstNode::Inherit`] representing the inheritance clause.
@@ -607,12 +1096,12 @@ impl Parser {
         let mut inheritance_arg = AstNode::Inherit { names: Some(Vec::new()) };
         while let Some((pos, token)) = self.advance() {
             match token.borrow() {
-                Token::Whitespace(ws) => self.parse_whitespace(ws),
+                Token::Whitespace(_) => (),
                 Token::LeftParen => {
                     // If the element vector is non-empty, this represents a syntax error
                     if let Some(names) = inheritance_arg.get_inherited_names() {
                         if !names.is_empty() {
-                            return Err(ParserError::DoubleInheritanceParen(pos, self.line, token.to_string()));
+                            return Err(ParserError::DoubleInheritanceParen(self.location(pos), token.to_string()));
                         }
                     }
                     continue;
@@ -623,7 +1112,7 @@ impl Parser {
                 Token::Operator(Operators::Arithmetic(ArithmeticOperators::MULTIPLY)) => {
                     if let Some(names) = inheritance_arg.get_inherited_names() {
                         if !names.is_empty() {
-                            return Err(ParserError::WildcardAndElements(pos, self.line, token.to_string()));
+                            return Err(ParserError::WildcardAndElements(self.location(pos), token.to_string()));
                         }
                     }
                     if let AstNode::Inherit { ref mut names } = inheritance_arg {
@@ -634,18 +1123,17 @@ impl Parser {
                     // None represents a previous wildcard, which cannot be combined with identifiers
                     match inheritance_arg.get_inherited_names() {
                         Some(_) => (),
-                        None => return Err(ParserError::WildcardAndElements(pos, self.line, token.to_string())),
+                        None => return Err(ParserError::WildcardAndElements(self.location(pos), token.to_string())),
                     }
 
-                    inheritance_arg.push_inherited_name(id.clone())
-                        .expect("Safety: Will always be AstNode::Inherit");
+                    inheritance_arg.push_inherited_name(id.clone())?;
                     continue;
                 },
                 Token::Comma => {
                     continue;
                 },
                 _ => {
-                    return Err(ParserError::InvalidInheritanceToken(pos, self.line, token.to_string()))
+                    return Err(ParserError::InvalidInheritanceToken(self.location(pos), token.to_string()))
                 }
             }
         }
@@ -670,39 +1158,94 @@ impl Parser {
     /// * `id`: A reference-counted pointer to the identifier (as `str`) to be converted.
     /// * `context`: The context within which the identifier is parsed (as [`ParseContext`]).
     /// 
+    /// Function calls on the identifier are parsed by [`parse_postfix`](Parser::parse_postfix).
+    /// 
     /// # Errors
-    /// * Any errors bubbled up from [`parse_function_call`](Parser::parse_function_call).
     /// * [`ParserError::InvalidContextForIdentifier`]: The context given to the function does not match a valid context for identifiers.
     fn parse_identifier(&mut self, id: &Rc<str>, context: ParseContext) -> Result<AstNode, ParserError> {
         match context {
-            ParseContext::FunctionCall => {
-                return Ok(self.parse_function_call(Rc::new(AstNode::Identifier(id.clone())))?);
-            },
             ParseContext::Normal => {
                 return Ok(AstNode::Identifier(id.clone()));
             },
-            _ => return Err(ParserError::InvalidContextForIdentifier(self.line, context.to_string())),
+            _ => return Err(ParserError::InvalidContextForIdentifier(self.location(self.current), context.to_string())),
         }
     }
 
+    /// Applies the postfix operations following an operand: function calls and accessions.
+    /// 
+    /// Calls bind tighter than any other operation, so `foo[a] + 2`, `!foo[]`, `env.f[a]`, and `foo[a].b` all call the function before applying the surrounding operation.
+    /// The argument list of a call must follow the callee immediately, without whitespace.
+    /// 
+    /// # Arguments
+    /// * `start`: The position of the first token of the operand, where the spans of the calls start.
+    /// * `node`: The operand (as [`AstNode`]).
+    /// 
+    /// # Errors
+    /// * Any errors bubbled up from [`parse_function_call`](Parser::parse_function_call) and [`parse_accessor_op`](Parser::parse_accessor_op).
+    /// * [`ParserError::NestingTooDeep`]: The calls and accessions nest deeper than [`ParserOptions::max_depth`].
+    fn parse_postfix(&mut self, start: usize, node: AstNode) -> Result<AstNode, ParserError> {
+        // Every call and accession nests the AST a level deeper, including the calls inside the arguments of a call
+        let depth = self.depth;
+        let result = self.parse_postfix_chain(start, node);
+        self.depth = depth;
+        return result;
+    }
+
+    /// Parses the calls and accessions of a postfix chain (see [`parse_postfix`](Parser::parse_postfix))
+    fn parse_postfix_chain(&mut self, start: usize, node: AstNode) -> Result<AstNode, ParserError> {
+        let mut node = node;
+        loop {
+            match self.peek() {
+                Some(Token::LeftBracket) => {
+                    self.nest()?;
+                    node = self.traced("function call", |parser| parser.parse_function_call(start, Rc::new(node)))?;
+                },
+                Some(Token::Operator(Operators::Other(OtherOperators::ACCESSOR))) => {
+                    self.next();
+                    self.nest()?;
+                    node = self.parse_accessor_op(&Operators::Other(OtherOperators::ACCESSOR), &Rc::new(node))?;
+                },
+                _ => return Ok(node),
+            }
+        }
+    }
+
+    /// Enters a level of nesting, which the caller leaves by restoring or decrementing the depth
+    /// 
+    /// # Errors
+    /// * [`ParserError::NestingTooDeep`]: The level would be deeper than [`ParserOptions::max_depth`].
+    fn nest(&mut self) -> Result<(), ParserError> {
+        if self.depth >= self.options.max_depth {
+            return Err(ParserError::NestingTooDeep(self.location(self.current.saturating_sub(1)), self.options.max_depth));
+        }
+        self.depth += 1;
+        return Ok(());
+    }
+
+    /// Ends a statement, keeping its operations as the deepest of the environment if they are, and counting the next statement from the operations enclosing the environment
+    fn end_statement(&mut self, operations: usize) {
+        self.deepest_operations = self.deepest_operations.max(self.operations);
+        self.operations = operations;
+    }
+
     /// Constructs a function call object.
     /// 
     /// # Arguments
-    /// * `id`: A reference-counted pointer to the identifier to which the function call is being bound (as [`AstNode::Identifier`]).
+    /// * `start`: The position of the first token of the callee.
+    /// * `id`: A reference-counted pointer to the callee, which is an identifier, an accession, or another function call (as [`AstNode`]).
     /// 
     /// # Errors
     /// * Any errors bubbled up from [`parse_function_clause`](Parser::parse_function_clause),
     /// * [`ParserError::InvalidTokenInFnCall`]: The next token does not match the start of a function argument/parameter clause.
-    fn parse_function_call(&mut self, id: Rc<AstNode>) -> Result<AstNode, ParserError> {
+    fn parse_function_call(&mut self, start: usize, id: Rc<AstNode>) -> Result<AstNode, ParserError> {
         let mut call_args = Rc::new(AstNode::FunctionArgs(vec![]));
-        while let Some((pos, token)) = self.advance() {
+        if let Some((pos, token)) = self.advance() {
             match token.borrow() {
                 Token::LeftBracket => {
-                    call_args = Rc::new(self.parse_function_clause()?);
-                    break;
+                    call_args = Rc::new(self.traced("arguments", |parser| parser.parse_function_clause(true))?);
                 },
                 _ => {
-                    return Err(ParserError::InvalidTokenInFnCall(pos, self.line, token.to_string()))
+                    return Err(ParserError::InvalidTokenInFnCall(self.location(pos), token.to_string()))
                 },
             }
         }
@@ -710,63 +1253,155 @@ impl Parser {
         return Ok(AstNode::FunctionCall {
             id,
             args: call_args,
+            span: self.span(start, self.current.saturating_sub(1)),
         });
     }
 
-    /// Increments the line counter if the whitespace is a new-line character.
+    /// Returns an `[AstNode::UnaryOp`] representing the unary operation.
     /// 
-    /// This is a bottom-level submethod and does not call other submethods.
+    /// Unary operators bind tighter than any binary operator, so `-2 ^ 2` applies the exponentiation to `-2`. A parenthesised operand can be a full expression.
     /// 
     /// # Arguments
-    /// * `ws`: A reference-counted pointer to the whitespace (as `str`) to be converted.
+    /// * `parent_env`: An `Option`al reference-counted pointer to the parent environment, or `None` for the global environment.
+    /// * `op`: A reference to the operator enum variant.
     /// 
-    /// The method does not panic or return errors.
-    fn parse_whitespace(&mut self, ws: &Rc<str>) { match ws.borrow() { "\r\n" | "\n" => self.line += 1, _ => () }}
+    /// # Errors
+    /// * Any errors bubbled up from [`parse_number`](Parser::parse_number), [`parse_postfix`](Parser::parse_postfix), and [`parse_group`](Parser::parse_group).
+    /// * [`ParserError::InvalidTokenInUnaryOp`]: The RHS of the unary operation does not match valid operands.
+    /// * [`ParserError::UnexpectedEOF`]: Dangling unary operator at the end of source file.
+    fn parse_unary_operator(&mut self, parent_env: &Option<Rc<AstNode>>, op: &Operators) -> Result<AstNode, ParserError> {
+        while let Some((pos, token)) = self.advance() {
+            let operand = match token.borrow() {
+                Token::Whitespace(_) => continue,
+                Token::Number(_) => self.parse_number(pos, &token)?,
+                Token::Identifier(id) => self.parse_postfix(pos, AstNode::Identifier(id.clone()))?,
+                Token::Boolean(bool) => AstNode::Boolean(match bool { Booleans::TRUE => true, Booleans::FALSE => false }),
+                Token::LeftParen => self.traced("group", |parser| parser.parse_group(parent_env))?,
+                _ => {
+                    return Err(ParserError::InvalidTokenInUnaryOp(self.location(pos), token.to_string()))
+                },
+            };
+            return Ok(AstNode::UnaryOp {
+                op: op.clone(),
+                operand: Rc::new(operand),
+            });
+        }
+        return Err(ParserError::UnexpectedEOF(self.location(self.current)));
+    }
 
-    /// Returns an `[AstNode::UnaryOp`] representing the unary operation.
+    /// Returns the expression enclosed by parentheses, after the opening parenthesis.
+    /// 
+    /// The expression is grouped regardless of the precedence of the surrounding operators, and can be followed by postfix operations like any other operand.
+    /// The grouping itself is not kept in the AST: `(1 + 2) * 3` is a multiplication with an addition as its left-hand side.
     /// 
     /// # Arguments
-    /// * `op`: A reference to the operator enum variant.
+    /// * `parent_env`: An `Option`al reference-counted pointer to the parent environment, or `None` for the global environment.
     /// 
     /// # Errors
-    /// * Any errors bubbled up from [`parse_number`](Parser::parse_number)
-    /// * [`ParserError::InvalidTokenInUnaryOp`]: The RHS of the unary operation does not match valid operands.
-    /// * [`ParserError::UnexpectedEOF`]: Dangling unary operator at the end of source file.
-    fn parse_unary_operator(&mut self, op: &Operators) -> Result<AstNode, ParserError> {
+    /// * Any errors bubbled up from [`parse_expression`](Parser::parse_expression) and [`parse_postfix`](Parser::parse_postfix).
+    /// * [`ParserError::NestingTooDeep`]: The parentheses are nested deeper than [`ParserOptions::max_depth`].
+    /// * [`ParserError::UnclosedParenthesis`]: The expression was followed by something else than a closing parenthesis.
+    /// * [`ParserError::UnexpectedEOF`]: The token stream ended before the closing parenthesis.
+    fn parse_group(&mut self, parent_env: &Option<Rc<AstNode>>) -> Result<AstNode, ParserError> {
+        let start = self.current.saturating_sub(1);
+        self.nest()?;
+        let expr = self.parse_expression(parent_env, Precedence::Assignment);
+        self.depth -= 1;
+        let expr = expr?;
+
         while let Some((pos, token)) = self.advance() {
             match token.borrow() {
-                Token::Whitespace(ws) => {
-                    self.parse_whitespace(ws);
-                },
-                Token::Number(_) => {
-                    let number = self.parse_number(pos, &token)?;
-                    return Ok(AstNode::UnaryOp {
-                        op: op.clone(),
-                        operand: Rc::new(number),
-                    })
+                Token::Whitespace(_) => (),
+                Token::RightParen => return self.parse_postfix(start, expr),
+                Token::EOF => return Err(ParserError::UnexpectedEOF(self.location(pos))),
+                _ => return Err(ParserError::UnclosedParenthesis(self.location(pos), token.to_string())),
+            }
+        }
+        return Err(ParserError::UnexpectedEOF(self.location(self.current)));
+    }
+
+    /// Returns an [`AstNode`] representing an operand, together with the operations following it whose operators have at least the given precedence.
+    /// 
+    /// # Arguments
+    /// * `parent_env`: An `Option`al reference-counted pointer to the parent environment, or `None` for the global environment.
+    /// * `min`: The loosest [`Precedence`] of an operator included in the expression.
+    /// 
+    /// # Errors
+    /// * Any errors bubbled up from [`parse_operand`](Parser::parse_operand) and [`parse_operations`](Parser::parse_operations).
+    fn parse_expression(&mut self, parent_env: &Option<Rc<AstNode>>, min: Precedence) -> Result<AstNode, ParserError> {
+        let operand = self.parse_operand(parent_env)?;
+        match self.next_operator(min) {
+            Some((op, precedence)) => return self.parse_operations(parent_env, op, precedence, operand, min),
+            None => return Ok(operand),
+        }
+    }
+
+    /// Returns an [`AstNode`] representing the operand of a binary operation: a literal, an identifier, a parenthesised expression, an environment, or a unary operation.
+    /// 
+    /// An environment with a single item is flattened into the item, so that `5 + { 3 }` adds the numbers.
+    /// 
+    /// # Arguments
+    /// * `parent_env`: An `Option`al reference-counted pointer to the parent environment, or `None` for the global environment.
+    /// 
+    /// # Errors
+    /// * Any errors bubbled up from [`parse_number`](Parser::parse_number), [`parse_postfix`](Parser::parse_postfix), [`parse_group`](Parser::parse_group), [`parse_environment`](Parser::parse_environment), [`parse_unary_operator`](Parser::parse_unary_operator), and [`flatten_environment`].
+    /// * [`ParserError::UnexpectedEOF`]: The token stream ended before the operand.
+    /// * [`ParserError::BinaryOpWithNoRHS`]: The operator was followed by something else than an operand.
+    fn parse_operand(&mut self, parent_env: &Option<Rc<AstNode>>) -> Result<AstNode, ParserError> {
+        while let Some((pos, token)) = self.advance() {
+            match token.borrow() {
+                Token::Whitespace(_) => (),
+                Token::Number(_) => return self.parse_number(pos, &token),
+                Token::Identifier(id) => return self.parse_postfix(pos, AstNode::Identifier(id.clone())),
+                Token::StringLiteral(string) => return self.parse_string(string),
+                Token::Boolean(Booleans::TRUE) => return Ok(AstNode::Boolean(true)),
+                Token::Boolean(Booleans::FALSE) => return Ok(AstNode::Boolean(false)),
+                Token::LeftParen => return self.traced("group", |parser| parser.parse_group(parent_env)),
+                Token::LeftBrace => {
+                    // Single-item environments are flattened into their item, as in assignments
+                    let env = self.parse_environment(parent_env.clone(), None, ParseContext::Normal)?;
+                    let env = if env.is_single_element_env() { (*self.flatten_environment(&env, pos, &token)?).clone() } else { env };
+                    return self.parse_postfix(pos, env);
                 },
-                Token::Identifier(id) => {
-                    return Ok(AstNode::UnaryOp {
-                        op: op.clone(),
-                        operand: Rc::new(AstNode::Identifier(id.clone())),
-                    })
-                },
-                Token::Boolean(bool) => {
-                    // Is the operand line nasty? It feels elegant, but also nasty...
-                    return Ok(AstNode::UnaryOp {
-                        op: op.clone(),
-                        operand: Rc::new(AstNode::Boolean(match bool { Booleans::TRUE => true, Booleans::FALSE => false}))
-                    })
-                }
-                _ => {
-                    return Err(ParserError::InvalidTokenInUnaryOp(pos, self.line, token.to_string()))
+                Token::Operator(op @ Operators::Arithmetic(ArithmeticOperators::ADD))
+                | Token::Operator(op @ Operators::Arithmetic(ArithmeticOperators::SUBTRACT))
+                | Token::Operator(op @ Operators::Logical(LogicalOperators::NOT)) => {
+                    return self.traced("unary operation", |parser| parser.parse_unary_operator(parent_env, op));
                 },
+                Token::EOF => return Err(ParserError::UnexpectedEOF(self.location(pos))),
+                _ => return Err(ParserError::BinaryOpWithNoRHS(self.location(pos))),
             }
         }
-        return Err(ParserError::UnexpectedEOF(self.current, self.line));
+        return Err(ParserError::UnexpectedEOF(self.location(self.current)));
+    }
+
+    /// Consumes the next binary operator, if it has at least the given precedence.
+    /// 
+    /// Whitespace before the operator is consumed either way, while an operator of lower precedence is left for an enclosing operation.
+    /// 
+    /// # Arguments
+    /// * `min`: The loosest [`Precedence`] of an operator to consume.
+    fn next_operator(&mut self, min: Precedence) -> Option<(Operators, Precedence)> {
+        while let Some(Token::Whitespace(_)) = self.peek() {
+            self.next();
+        }
+        let op = match self.peek() {
+            Some(Token::Operator(op)) => op.clone(),
+            _ => return None,
+        };
+        let precedence = precedence_of(&op)?;
+        if precedence < min {
+            self.record(|parser| ParseStep::Decision(
+                parser.location(parser.current),
+                format!("'{}' has {} precedence, so it ends the right-hand side, which only takes operators of {} precedence or tighter", op.to_string(), precedence.to_string(), min.to_string()),
+            ));
+            return None;
+        }
+        self.next();
+        return Some((op, precedence));
     }
 
-    /// Returns an `[AstNode::BinaryOp`] representing the binary operation.
+    /// Returns an `[AstNode::BinaryOp`] representing the binary operation, or an accession.
     /// 
     /// # Arguments
     /// * `parent_env`: An `Option`al reference-counted pointer to the parent environment, or `None` for the global environment.
@@ -774,53 +1409,73 @@ impl Parser {
     /// * `prev`: A reference-counted pointer to the previous (left-hand-side) element (as [`AstNode`]).
     /// 
     /// # Errors
-    /// * Any errors bubbled up from [`parse_accessor_op`](Parser::parse_accessor_op), and [`parse_generic_op`](Parser::parse_accessor_op).
-    fn parse_operator(&mut self, parent_env: Option<Rc<AstNode>>, op: &Operators, prev: &Rc<AstNode>) -> Result<AstNode, ParserError> {
-        match op {
-            Operators::Other(OtherOperators::ACCESSOR) => {
-                return Ok(self.parse_accessor_op(op, prev)?)
+    /// * Any errors bubbled up from [`parse_accessor_op`](Parser::parse_accessor_op), and [`parse_operations`](Parser::parse_operations).
+    /// * [`ParserError::InvalidOperation`]: The unary NOT operator was given a left-hand side.
+    fn parse_operator(&mut self, parent_env: &Option<Rc<AstNode>>, op: &Operators, prev: &Rc<AstNode>) -> Result<AstNode, ParserError> {
+        match precedence_of(op) {
+            Some(precedence) => {
+                return self.parse_operations(parent_env, op.clone(), precedence, (**prev).clone(), Precedence::Assignment)
             },
-            Operators::Logical(LogicalOperators::NOT) => {
-                todo!() // TODO: Implement unary NOT parsing as its own method
+            None if *op == Operators::Other(OtherOperators::ACCESSOR) => {
+                return self.parse_accessor_op(op, prev)
             },
-            _ => {
-                return Ok(self.parse_generic_op(parent_env, op, prev)?)
+            // NOT is a unary operator, and cannot follow a left-hand side
+            None => {
+                return Err(ParserError::InvalidOperation(self.location(self.current.saturating_sub(1)), op.to_string()))
             },
         }
     }
 
-    /// Returns an [`AstNode::BinaryOp`] representing a generic, non-accession binary operation.
+    /// Returns an [`AstNode::BinaryOp`] representing a chain of binary operations, grouped by the precedence of their operators ("precedence climbing").
+    /// 
+    /// Each right-hand side is parsed to include the following operators that bind tighter than the operator before it, or equally tight if the operator is right-associative.
+    /// The chain ends at the first token that is not an operator of at least the given minimum precedence.
     /// 
     /// # Arguments
     /// * `parent_env`: An `Option`al reference-counted pointer to the parent environment, or `None` for the global environment.
-    /// * `op`: A reference to the operator enum variant.
-    /// * `prev`: A reference-counted pointer to the previous (left-hand-side) element (as [`AstNode`]).
+    /// * `op`: The first operator of the chain, which has already been consumed.
+    /// * `precedence`: The [`Precedence`] of the first operator.
+    /// * `left`: The left-hand side of the first operator (as [`AstNode`]).
+    /// * `min`: The loosest [`Precedence`] of an operator included in the chain.
     /// 
     /// # Errors
-    /// * Any errors bubbled up from [`parse_environment`](Parser::parse_environment).
-    /// * [`ParserError::UnexpectedEOF`]: The token stream was unexpectedly empty.
-    fn parse_generic_op(&mut self, parent_env: Option<Rc<AstNode>>, op: &Operators, prev: &Rc<AstNode>) -> Result<AstNode, ParserError> {
-        if let Some(_) = self.peek() {
-            let next_node: AstNode = self.parse_environment(parent_env, None, ParseContext::Operation)?;
-        
-            // Flatten single-item environments into a single node
-            if let Some(bindings) = next_node.get_bindings() {
-                if bindings.len() == 1 {
-                    return Ok(AstNode::BinaryOp {
-                        left: prev.clone(),
-                        operator: op.clone(),
-                        right: bindings[0].clone()
-                    });
-                }
+    /// * Any errors bubbled up from [`parse_expression`](Parser::parse_expression).
+    /// * [`ParserError::NestingTooDeep`]: The operations nest deeper than [`ParserOptions::max_depth`].
+    /// * [`ParserError::TooManyOperations`]: The statement has more operations than [`ParserOptions::max_operations`].
+    fn parse_operations(&mut self, parent_env: &Option<Rc<AstNode>>, op: Operators, precedence: Precedence, left: AstNode, min: Precedence) -> Result<AstNode, ParserError> {
+        // The chain is a single level, and operations nested in its operands are chains of their own, adding further levels
+        // Each operation of the chain still nests the AST a level deeper, so the operations are counted until the statement ends
+        let depth = self.depth;
+        let result = self.nest().and_then(|_| self.parse_operation_chain(parent_env, op, precedence, left, min));
+        self.depth = depth;
+        return result;
+    }
+
+    /// Parses the operations of a chain (see [`parse_operations`](Parser::parse_operations))
+    fn parse_operation_chain(&mut self, parent_env: &Option<Rc<AstNode>>, op: Operators, precedence: Precedence, left: AstNode, min: Precedence) -> Result<AstNode, ParserError> {
+        let (mut op, mut precedence, mut left) = (op, precedence, left);
+        loop {
+            if self.operations >= self.options.max_operations {
+                return Err(ParserError::TooManyOperations(self.location(self.current.saturating_sub(1)), self.options.max_operations));
+            }
+            self.operations += 1;
+
+            let right_min = if is_right_associative(&op) { precedence } else { precedence.tighter() };
+            self.record(|parser| ParseStep::Decision(
+                parser.location(parser.current.saturating_sub(1)),
+                format!("'{}' has {} precedence, and takes '{}' as its left-hand side", op.to_string(), precedence.to_string(), left.to_string()),
+            ));
+            let right = self.traced("right-hand side", |parser| parser.parse_expression(parent_env, right_min))?;
+            left = AstNode::BinaryOp {
+                left: Rc::new(left),
+                operator: op,
+                right: Rc::new(right),
+            };
+
+            match self.next_operator(min) {
+                Some((next, next_precedence)) => (op, precedence) = (next, next_precedence),
+                None => return Ok(left),
             }
-            // Return multi-item rhs environments as is
-            return Ok(AstNode::BinaryOp {
-                left: prev.clone(),
-                operator: op.clone(),
-                right: Rc::new(next_node)
-            });
-        } else {
-            return Err(ParserError::UnexpectedEOF(self.current, self.line));
         }
     }
     
@@ -836,16 +1491,19 @@ impl Parser {
     /// * [`ParserError::ParserLogicError`]: The operator given to the method was not an accessor operator (indicating an implementation error in Envlang).
     /// * [`ParserError::InvalidAccessionTarget`]: The accessor operator was used on any non-identifier left-hand-side operand.
     /// * [`ParserError::UnexpectedEOF`]: The token stream unexpectedly ended.
-    /// * [`ParserError::InvalidAccessionSource`]: The accessor operator was used on a right-hand-side operand being something else than an identifier or environment.
+    /// * [`ParserError::InvalidAccessionSource`]: The accessor operator was used on a left-hand-side operand being something else than an identifier, environment, accession, or function call.
     fn parse_accessor_op(&mut self, op: &Operators, prev: &Rc<AstNode>) -> Result<AstNode, ParserError> {
         // Exit early if the operator is not an accessor
         match op {
             Operators::Other(OtherOperators::ACCESSOR) => (),
-            _ => return Err(ParserError::ParserLogicError(self.current, self.line))
+            _ => return Err(ParserError::ParserLogicError(self.location(self.current)))
         }
 
         match prev.borrow() {
-            AstNode::Identifier(_) | AstNode::Environment{ .. } => {
+            AstNode::Identifier(_)
+            | AstNode::Environment{ .. }
+            | AstNode::BinaryOp { operator: Operators::Other(OtherOperators::ACCESSOR), .. }
+            | AstNode::FunctionCall { .. } => {
                 if let Some((pos, token)) = self.advance() {
                     match token.borrow() {
                         Token::Identifier(id) => {
@@ -855,17 +1513,19 @@ impl Parser {
                                 right: Rc::new(AstNode::Identifier(id.clone()))
                             });
                         },
-                        _ => return Err(ParserError::InvalidAccessionTarget(pos, self.line, token.to_string()))
+                        _ => return Err(ParserError::InvalidAccessionTarget(self.location(pos), token.to_string()))
                     }
                 }
-                return Err(ParserError::UnexpectedEOF(self.current, self.line));
+                return Err(ParserError::UnexpectedEOF(self.location(self.current)));
             },
-            _ => return Err(ParserError::InvalidAccessionSource(self.current, self.line, prev.to_string())),
+            _ => return Err(ParserError::InvalidAccessionSource(self.location(self.current), prev.to_string())),
         }
     }
     
     /// Returns an [`AstNode::Integer`] or [`AstNode::Float`] representing the number type and data.
     /// 
+    /// A unit suffix of [`UNIT_SUFFIXES`](crate::symbols::UNIT_SUFFIXES) multiplies the number by the factor of the unit, e.g., `10s` is the integer `10000` and `1.5s` the float `1500.0`.
+    /// 
     /// This is a bottom-level submethod and does not call other submethods.
     /// 
     /// # Arguments
@@ -873,15 +1533,16 @@ impl Parser {
     /// * `start_token`: A reference to the starting token (as `Token`).
     /// 
     /// # Errors
-    /// * [`ParserError::NotANumber`]: The declared number does not start with a number or full-stop, or a parsed number does not map into `isize` or `f64`.
+    /// * [`ParserError::NotANumber`]: The declared number does not start with a number or full-stop, or has a unit suffix before its decimal point.
     /// * [`ParserError::MalformedNumber`]: The declared number contains two (or more) full-stops.
+    /// * [`ParserError::NumberOutOfRange`]: An integer with a unit suffix does not fit into `isize`, before or after applying the unit, or a float is infinite. The location covers the whole literal.
     fn parse_number(&mut self, start_pos: usize, start_token: &Token) -> Result<AstNode, ParserError> {
         let mut numstr: String = String::new();
         // Valid numbers start with a number or a full stop (if float)
         match start_token {
             Token::Number(num) => numstr.push_str(num),
             Token::Operator(Operators::Other(OtherOperators::ACCESSOR)) => numstr.push_str("0."),
-            _ => return Err(ParserError::NotANumber(start_pos, self.line, numstr)),
+            _ => return Err(ParserError::NotANumber(self.location(start_pos), numstr)),
         }
         
         while let Some(token) = self.peek() {
@@ -892,22 +1553,49 @@ impl Parser {
                 },
                 Token::Operator(Operators::Other(OtherOperators::ACCESSOR)) => {
                     if numstr.contains(".") { // A float can only have one decimal point
-                        return Err(ParserError::MalformedNumber(self.current, self.line, numstr));
+                        return Err(ParserError::MalformedNumber(self.location(self.current), numstr));
                     }
-                    numstr.push_str(".");
+                    numstr.push('.');
                     self.next();
                 },
                 _ => break,
             }
         }
         
-        // Try to cast the vector to an isize
-        // If possible, return AstNode::Integer(cast_vector); If not, try to cast to an f64
-        // If possible, return AstNode::Float(cast_vector); If not, return Err(ParserError::NotANumber)
-        numstr.parse::<isize>()
-            .map(|num| AstNode::Integer(num))
-            .or_else(|_| numstr.parse::<f64>().map(|num| AstNode::Float(num)))
-            .map_err(|_| ParserError::NotANumber(self.current, self.line, numstr))
+        let literal = Location { span: self.span(start_pos, self.current.saturating_sub(1)), ..self.location(start_pos) };
+
+        // The lexer only reads known suffixes, but a suffix before the decimal point (e.g. `1s.5`) is not a number
+        let digits = numstr.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let factor = match &numstr[digits.len()..] {
+            "" => None,
+            suffix => match unit_factor(suffix) {
+                Some(factor) => Some(factor),
+                None => return Err(ParserError::NotANumber(self.location(self.current), numstr)),
+            },
+        };
+
+        // Integers without a unit that do not fit into an isize are promoted to floats
+        // Integers with a unit are not, following the numeric semantics, so they are out of range whether the digits or the product overflow
+        if !digits.contains('.') {
+            match (digits.parse::<isize>(), factor) {
+                (Ok(num), None) => return Ok(AstNode::Integer(num)),
+                (Ok(num), Some(factor)) => {
+                    return isize::try_from(factor).ok()
+                        .and_then(|factor| num.checked_mul(factor))
+                        .map(AstNode::Integer)
+                        .ok_or(ParserError::NumberOutOfRange(literal, numstr));
+                },
+                (Err(_), Some(_)) => return Err(ParserError::NumberOutOfRange(literal, numstr)),
+                (Err(_), None) => (),
+            }
+        }
+        return match digits.parse::<f64>() {
+            Ok(num) => Some(num * factor.unwrap_or(1) as f64)
+                .filter(|num| num.is_finite())
+                .map(AstNode::Float)
+                .ok_or(ParserError::NumberOutOfRange(literal, numstr)),
+            Err(_) => Err(ParserError::NotANumber(self.location(self.current), numstr)),
+        };
     }
 
     /// Takes a single-element environment and returns its binding as a pointer.
@@ -929,23 +1617,25 @@ impl Parser {
     fn flatten_environment(&self, expr: &AstNode, pos: usize, token: &Token) -> Result<Rc<AstNode>, ParserError> {
         match expr {
             AstNode::Environment{ bindings, .. } => {
-                if bindings.len() == 0 {
-                    return Err(ParserError::EmptyEnv(pos, self.line, token.to_string()));
+                if bindings.is_empty() {
+                    return Err(ParserError::EmptyEnv(self.location(pos), token.to_string()));
                 }
                 return Ok(bindings[0].clone());
             },
-            _ => return Err(ParserError::NotAnEnvironment(pos, self.line, token.to_string()))
+            _ => return Err(ParserError::NotAnEnvironment(self.location(pos), token.to_string()))
         }
     }
 
     /// Debugging function to print the next token in the token queue
     #[allow(dead_code)]
+    #[cfg(feature = "std")]
     fn debug_next_token(&self) {
         println!("Next token at index: {}, token: {:?}", self.current, self.peek());
     }
 
     /// Debugging function to print a pos-token tuple
     #[allow(dead_code)]
+    #[cfg(feature = "std")]
     fn debug_token_tuple(&self, pos: usize, token: &Token) {
         println!("Grabbed token at index: {}, token: {:?}", pos, token);
     }
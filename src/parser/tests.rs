@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use crate::lexer::Token;
-    use crate::parser::{Parser, AstNode, ParserError};
+    use crate::lexer::{Lexer, Location, Token};
+    use crate::parser::{is_right_associative, precedence_of, Parser, ParserOptions, ParseStep, AstNode, ParserError};
+    use crate::span::Span;
     use crate::symbols::{Keywords, Operators, ArithmeticOperators, ComparisonOperators, LogicalOperators, OtherOperators, Booleans};
     use std::rc::Rc;
 
@@ -70,6 +71,7 @@ mod tests {
                 name: "x".into(),
                 value: Some(Rc::new(AstNode::Integer(5))),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -222,6 +224,7 @@ mod tests {
                 name: "x".into(),
                 value: Some(Rc::new(AstNode::Identifier("y".into()))),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -249,6 +252,7 @@ mod tests {
                     operand: Rc::new(AstNode::Integer(5)),
                 })),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -282,6 +286,7 @@ mod tests {
                     })
                 })),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -337,6 +342,7 @@ mod tests {
                     name: "x".into(),
                     value: Some(Rc::new(AstNode::Integer(5))),
                     inherit: None,
+                    span: None,
                 }));
             }
         } else {
@@ -418,6 +424,7 @@ mod tests {
                         "b".into(),
                     ])
                 })),
+                span: None,
             })],
             parent: None
         });
@@ -447,6 +454,7 @@ mod tests {
                 inherit: Some(Rc::new(AstNode::Inherit {
                     names: None
                 })),
+                span: None,
             })],
             parent: None
         });
@@ -492,8 +500,10 @@ mod tests {
                         bindings: vec![Rc::new(AstNode::Integer(5))],
                         parent: Some(global_env.clone())
                     }),
+                    span: None,
                 })),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -539,9 +549,11 @@ mod tests {
                         name: None,
                         bindings: vec![],
                         parent: Some(global_env.clone())
-                    })
+                    }),
+                    span: None,
                 })),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -593,9 +605,11 @@ mod tests {
                         name: None,
                         bindings: vec![Rc::new(AstNode::Integer(5))],
                         parent: Some(global_env.clone())
-                    })
+                    }),
+                    span: None,
                 })),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -647,7 +661,8 @@ mod tests {
                         name: None,
                         bindings: vec![Rc::new(AstNode::Integer(5))],
                         parent: Some(global_env.clone())
-                    })
+                    }),
+                    span: None,
                 })),
                 inherit: Some(Rc::new(AstNode::Inherit {
                     names: Some(vec![
@@ -655,6 +670,7 @@ mod tests {
                         "y".into()
                     ])
                 })),
+                span: None,
             })],
             parent: None
         });
@@ -704,11 +720,13 @@ mod tests {
                         name: None,
                         bindings: vec![Rc::new(AstNode::Integer(5))],
                         parent: Some(global_env.clone())
-                    })
+                    }),
+                    span: None,
                 })),
                 inherit: Some(Rc::new(AstNode::Inherit {
                     names: None
                 })),
+                span: None,
             })],
             parent: None
         });
@@ -764,11 +782,13 @@ mod tests {
                                 name: "x".into(),
                                 value: Some(Rc::new(AstNode::Integer(1))),
                                 inherit: None,
+                                span: None,
                             }),
                             Rc::new(AstNode::Let {
                                 name: "y".into(),
                                 value: Some(Rc::new(AstNode::Integer(2))),
                                 inherit: None,
+                                span: None,
                             })
                         ],
                         parent: Some(global_env.clone())
@@ -784,8 +804,10 @@ mod tests {
                         ],
                         parent: Some(global_env.clone())
                     }),
+                    span: None,
                 })),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -844,8 +866,10 @@ mod tests {
                         ],
                         parent: Some(global_env.clone())
                     }),
+                    span: None,
                 })),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -893,9 +917,11 @@ mod tests {
                         name: None,
                         bindings: vec![Rc::new(AstNode::Integer(5))],
                         parent: Some(global_env.clone())
-                    })
+                    }),
+                    span: None,
                 })),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -960,22 +986,27 @@ mod tests {
                                 name: "x".into(),
                                 value: Some(Rc::new(AstNode::Integer(5))),
                                 inherit: None,
+                                span: None,
                             }),
                             Rc::new(AstNode::Let {
                                 name: "y".into(),
                                 value: Some(Rc::new(AstNode::Integer(3))),
                                 inherit: None,
+                                span: None,
                             }),
                             Rc::new(AstNode::Let {
                                 name: "z".into(),
                                 value: Some(Rc::new(AstNode::Integer(1))),
                                 inherit: None,
+                                span: None,
                             })
                         ],
                         parent: Some(global_env.clone())
-                    })
+                    }),
+                    span: None,
                 })),
                 inherit: None,
+                span: None,
             })],
             parent: None
         });
@@ -1005,15 +1036,277 @@ mod tests {
                         id: Rc::new(AstNode::Identifier("foo".into())),
                         args: Rc::new(AstNode::FunctionArgs(
                             vec![]
-                        ))
+                        )),
+                        span: None,
                     })),
                     inherit: None,
+                    span: None,
                 })
             ],
             parent: None,
         });
     }
 
+    /// Parses source code and gets the value of its first binding, or the first binding itself if it is not an assignment
+    fn first_value(source: &str) -> AstNode {
+        let tokens = Lexer::from_text(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let first = ast.get_bindings().unwrap()[0].clone();
+        match first.as_ref() {
+            AstNode::Let { value: Some(value), .. } => return (**value).clone(),
+            node => return node.clone(),
+        }
+    }
+
+    fn call(id: AstNode, args: Vec<AstNode>) -> AstNode {
+        return AstNode::FunctionCall {
+            id: Rc::new(id),
+            args: Rc::new(AstNode::FunctionArgs(args.into_iter().map(Rc::new).collect())),
+            span: None,
+        };
+    }
+
+    fn access(left: AstNode, right: &str) -> AstNode {
+        return AstNode::BinaryOp {
+            left: Rc::new(left),
+            operator: Operators::Other(OtherOperators::ACCESSOR),
+            right: Rc::new(AstNode::Identifier(right.into())),
+        };
+    }
+
+    #[test]
+    fn operations_leave_the_terminator_to_their_statement() {
+        let tokens = Lexer::from_text("let a = 1 + 2;\nlet b = { let c = a * a; };\nb;\n").tokenize().unwrap();
+        let bindings = Parser::new(tokens).parse().unwrap().get_bindings().unwrap();
+        assert_eq!(bindings.len(), 3);
+        assert!(matches!(bindings[0].as_ref(), AstNode::Let { value: Some(value), .. } if matches!(value.as_ref(), AstNode::BinaryOp { .. })));
+        assert!(matches!(bindings[1].as_ref(), AstNode::Let { value: Some(value), .. } if value.is_single_element_env()));
+    }
+
+    #[test]
+    fn function_call_statements() {
+        assert_eq!(first_value("foo[1, \"a\", true];\n"), call(AstNode::Identifier("foo".into()), vec![
+            AstNode::Integer(1),
+            AstNode::String("a".into()),
+            AstNode::Boolean(true),
+        ]));
+        assert_eq!(first_value("foo[g[a], b.c];\n"), call(AstNode::Identifier("foo".into()), vec![
+            call(AstNode::Identifier("g".into()), vec![AstNode::Identifier("a".into())]),
+            access(AstNode::Identifier("b".into()), "c"),
+        ]));
+    }
+
+    #[test]
+    fn function_calls_as_operands() {
+        let foo = call(AstNode::Identifier("foo".into()), vec![]);
+        assert_eq!(first_value("let x = foo[] + 2;\n"), AstNode::BinaryOp {
+            left: Rc::new(foo.clone()),
+            operator: Operators::Arithmetic(ArithmeticOperators::ADD),
+            right: Rc::new(AstNode::Integer(2)),
+        });
+        assert_eq!(first_value("let x = !foo[];\n"), AstNode::UnaryOp {
+            op: Operators::Logical(LogicalOperators::NOT),
+            operand: Rc::new(foo),
+        });
+    }
+
+    #[test]
+    fn function_calls_on_accessions() {
+        let method = call(access(AstNode::Identifier("env".into()), "f"), vec![AstNode::Identifier("a".into())]);
+        assert_eq!(first_value("let x = env.f[a];\n"), method);
+        assert_eq!(first_value("let x = env.f[a].b;\n"), access(method, "b"));
+        assert_eq!(first_value("let x = a.b.c;\n"), access(access(AstNode::Identifier("a".into()), "b"), "c"));
+    }
+
+    #[test]
+    fn function_parameters_are_identifiers() {
+        let tokens = Lexer::from_text("let fun f[1] = { return a; }\n").tokenize().unwrap();
+        assert!(matches!(Parser::new(tokens).parse(), Err(ParserError::InvalidFunArgToken(_, _))));
+    }
+
+    fn parse_strict(source: &str) -> Result<AstNode, ParserError> {
+        let stream = Lexer::from_text(source).tokenize_stream().unwrap();
+        return Parser::from_stream(stream).with_options(ParserOptions { strict_braces: true, ..ParserOptions::default() }).parse();
+    }
+
+    #[test]
+    fn extra_global_braces_are_ignored_by_default() {
+        let tokens = Lexer::from_text("let x = 5;\n}\n{ let y = x; }\n").tokenize().unwrap();
+        assert_eq!(Parser::new(tokens).parse().unwrap().get_bindings().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn strict_braces_report_unmatched_braces() {
+        assert!(parse_strict("let x = { let y = \"}\"; };\n{ let z = x; }\n").is_ok());
+
+        let err = parse_strict("let x = 5;\n}\n").unwrap_err();
+        assert!(matches!(&err, ParserError::UnmatchedBrace(_, brace) if brace == "}"));
+        assert_eq!(err.location().and_then(|location| location.column), Some(1));
+        assert_eq!(err.line(), Some(2));
+
+        let err = parse_strict("let x = {\n    let y = { 5; };\n").unwrap_err();
+        assert!(matches!(&err, ParserError::UnmatchedBrace(_, brace) if brace == "{"));
+        assert_eq!(err.location().and_then(|location| location.column), Some(9));
+        assert_eq!(err.line(), Some(1));
+    }
+
+    fn parse_recovering(source: &str) -> (AstNode, Vec<ParserError>) {
+        let stream = Lexer::from_text(source).tokenize_stream().unwrap();
+        return Parser::from_stream(stream).parse_recovering();
+    }
+
+    #[test]
+    fn recovery_replaces_broken_statements() {
+        let source = "let a = 1;\nlet b inherit (x, *) = 2;\nlet c = a;\n";
+        let (ast, errors) = parse_recovering(source);
+        let bindings = ast.get_bindings().unwrap();
+        assert_eq!(bindings.len(), 3);
+        assert!(matches!(bindings[0].as_ref(), AstNode::Let { name, .. } if name.as_ref() == "a"));
+        assert!(matches!(bindings[2].as_ref(), AstNode::Let { name, .. } if name.as_ref() == "c"));
+        let AstNode::Error(Some(span)) = bindings[1].as_ref() else { panic!("Not an error node: {:?}", bindings[1]) };
+        assert_eq!(&source[span.start..span.end], "let b inherit (x, *) = 2;");
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ParserError::WildcardAndElements(_, _)));
+        assert_eq!(errors[0].line(), Some(2));
+        // Errors are located in the full source, as without recovery
+        let stream = Lexer::from_text(source).tokenize_stream().unwrap();
+        assert_eq!(errors[0].location(), Parser::from_stream(stream).parse().unwrap_err().location());
+    }
+
+    #[test]
+    fn recovery_splits_statements_at_braces() {
+        let source = "let e = { let y = 1; }\nlet = 2;\nlet fun g[] = { return 1; }\nlet h = { 1 } + 2;\nlet i inherit (x, *) = { 3 }\n";
+        let (ast, errors) = parse_recovering(source);
+        let kinds: Vec<bool> = ast.get_bindings().unwrap().iter().map(|b| matches!(b.as_ref(), AstNode::Error(_))).collect();
+        assert_eq!(kinds, vec![false, true, false, false, true]);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.iter().map(|err| err.line()).collect::<Vec<_>>(), vec![Some(2), Some(5)]);
+    }
+
+    #[test]
+    fn recovery_keeps_valid_input() {
+        let source = "let x = { let y = 5; };\nlet fun f[a] = { return a; }\nlet z = f[x];\n";
+        let tokens = Lexer::from_text(source).tokenize().unwrap();
+        let (ast, errors) = Parser::new(tokens.clone()).parse_recovering();
+        assert!(errors.is_empty());
+        assert_eq!(ast, Parser::new(tokens).parse().unwrap());
+    }
+
+    #[test]
+    fn nesting_depth_is_limited() {
+        let parse_with_depth = |source: &str, max_depth: usize| {
+            let tokens = Lexer::from_text(source).tokenize().unwrap();
+            return Parser::new(tokens).with_options(ParserOptions { max_depth, ..ParserOptions::default() }).parse();
+        };
+        assert!(parse_with_depth("let x = 1 + 2;\n", 3).is_ok());
+        // A chain is a single level, however long, but operations nested in its operands add levels
+        assert!(parse_with_depth(&format!("let x = 1{};\n", " + 1".repeat(500)), 3).is_ok());
+        assert!(matches!(parse_with_depth("let x = 1 + 2 * 3;\n", 3), Err(ParserError::NestingTooDeep(_, 3))));
+        assert!(matches!(parse_with_depth("let x = 2 ^ 2 ^ 2;\n", 3), Err(ParserError::NestingTooDeep(_, 3))));
+        // Every call and accession is a level
+        assert!(parse_with_depth("let x = a.b;\n", 3).is_ok());
+        assert!(matches!(parse_with_depth("let x = a.b.c;\n", 3), Err(ParserError::NestingTooDeep(_, 3))));
+        assert!(matches!(parse_with_depth("let x = f[][];\n", 3), Err(ParserError::NestingTooDeep(_, 3))));
+        assert!(matches!(parse_with_depth("let x = f[g[1]];\n", 3), Err(ParserError::NestingTooDeep(_, 3))));
+        assert!(parse_with_depth("let x = { 1 };\n", 3).is_ok());
+        let err = parse_with_depth("let x = { { 1 } };\n", 3).unwrap_err();
+        assert_eq!(err.code(), "E0135");
+        assert_eq!(err.message(), "Input is nested deeper than the limit of 3 levels");
+    }
+
+    #[test]
+    fn chained_operations_are_limited() {
+        let parse_with_operations = |source: &str, max_operations: usize| {
+            let tokens = Lexer::from_text(source).tokenize().unwrap();
+            return Parser::new(tokens).with_options(ParserOptions { max_operations, ..ParserOptions::default() }).parse();
+        };
+        assert!(parse_with_operations("let x = 1 + 2 + 3;\n", 2).is_ok());
+        assert!(matches!(parse_with_operations("let x = 1 + 2 + 3 + 4;\n", 2), Err(ParserError::TooManyOperations(_, 2))));
+        // Operations inside parentheses and braces count together with the statement around them
+        assert!(matches!(parse_with_operations("let x = (1 + 2) + 3;\n", 1), Err(ParserError::TooManyOperations(_, 1))));
+        assert!(matches!(parse_with_operations("let x = { let y = 1 + 2; }.y + 3;\n", 1), Err(ParserError::TooManyOperations(_, 1))));
+        assert!(parse_with_operations("let x = { let y = 1 + 2; let z = 3 + 4; };\n", 1).is_ok());
+        let err = parse_with_operations("let x = 1 + (2 + 3);\n", 1).unwrap_err();
+        assert_eq!(err.code(), "E0137");
+        assert_eq!(err.message(), "Statement has more than the limit of 1 operations");
+        assert!(parse_with_operations("let x = 1 + 2;\nlet y = 3 + 4;\n", 1).is_ok());
+    }
+
+    #[test]
+    fn precedence_table_matches_the_parser() {
+        // The assignment operator is left out, as it is not allowed inside expressions
+        let operators = [
+            Operators::Logical(LogicalOperators::OR),
+            Operators::Logical(LogicalOperators::AND),
+            Operators::Comparison(ComparisonOperators::EQ),
+            Operators::Comparison(ComparisonOperators::LT),
+            Operators::Arithmetic(ArithmeticOperators::ADD),
+            Operators::Arithmetic(ArithmeticOperators::MULTIPLY),
+            Operators::Arithmetic(ArithmeticOperators::EXPONENTIATION),
+        ];
+        for first in &operators {
+            for second in &operators {
+                let source = format!("let x = a {} b {} c;\n", first.to_string(), second.to_string());
+                let ast = Parser::new(Lexer::from_text(&source).tokenize().unwrap()).parse().unwrap();
+                let Some(AstNode::Let { value: Some(value), .. }) = ast.get_bindings().map(|bindings| (*bindings[0]).clone()) else {
+                    panic!("Expected a let statement in {:?}", source);
+                };
+                let AstNode::BinaryOp { operator, .. } = value.as_ref() else { panic!("Expected an operation in {:?}", source); };
+                // The operator at the root of the tree is the one that groups last
+                let first_groups_last = precedence_of(first) < precedence_of(second)
+                    || (first == second && is_right_associative(first));
+                assert_eq!(operator, if first_groups_last { first } else { second }, "{}", source);
+            }
+        }
+    }
+
+    #[test]
+    fn operations_group_by_precedence() {
+        let parse_value = |source: &str| {
+            let tokens = Lexer::from_text(source).tokenize().unwrap();
+            let ast = Parser::new(tokens).parse().unwrap();
+            match &*ast.get_bindings().unwrap()[0] {
+                AstNode::Let { value: Some(value), .. } => return (**value).clone(),
+                other => panic!("Expected a let statement, got {:?}", other),
+            }
+        };
+        let binary = |left: AstNode, operator: Operators, right: AstNode| AstNode::BinaryOp { left: Rc::new(left), operator, right: Rc::new(right) };
+        let id = |name: &str| AstNode::Identifier(name.into());
+        let add = Operators::Arithmetic(ArithmeticOperators::ADD);
+        let sub = Operators::Arithmetic(ArithmeticOperators::SUBTRACT);
+        let mul = Operators::Arithmetic(ArithmeticOperators::MULTIPLY);
+        let pow = Operators::Arithmetic(ArithmeticOperators::EXPONENTIATION);
+
+        assert_eq!(parse_value("let x = 5 + 3 * 2;\n"), binary(AstNode::Integer(5), add.clone(), binary(AstNode::Integer(3), mul.clone(), AstNode::Integer(2))));
+        assert_eq!(parse_value("let x = 5 * 3 + 2;\n"), binary(binary(AstNode::Integer(5), mul.clone(), AstNode::Integer(3)), add.clone(), AstNode::Integer(2)));
+        assert_eq!(parse_value("let x = 8 - 4 - 2;\n"), binary(binary(AstNode::Integer(8), sub.clone(), AstNode::Integer(4)), sub, AstNode::Integer(2)));
+        assert_eq!(parse_value("let x = 2 ^ 3 ^ 2;\n"), binary(AstNode::Integer(2), pow.clone(), binary(AstNode::Integer(3), pow, AstNode::Integer(2))));
+        assert_eq!(parse_value("let x = (5 + 3) * 2;\n"), binary(binary(AstNode::Integer(5), add, AstNode::Integer(3)), mul, AstNode::Integer(2)));
+        assert_eq!(
+            parse_value("let x = a == b & !c | d < 1;\n"),
+            binary(
+                binary(
+                    binary(id("a"), Operators::Comparison(ComparisonOperators::EQ), id("b")),
+                    Operators::Logical(LogicalOperators::AND),
+                    AstNode::UnaryOp { op: Operators::Logical(LogicalOperators::NOT), operand: Rc::new(id("c")) },
+                ),
+                Operators::Logical(LogicalOperators::OR),
+                binary(id("d"), Operators::Comparison(ComparisonOperators::LT), AstNode::Integer(1)),
+            ),
+        );
+    }
+
+    #[test]
+    fn unclosed_parentheses_are_rejected() {
+        let tokens = Lexer::from_text("let x = (1 + 2;\n").tokenize().unwrap();
+        let err = Parser::new(tokens).parse().unwrap_err();
+        assert!(matches!(err, ParserError::UnclosedParenthesis(_, ref value) if value == ";"), "{:?}", err);
+        assert_eq!(err.code(), "E0136");
+        let tokens = Lexer::from_text("let x = (1 + 2").tokenize().unwrap();
+        assert!(matches!(Parser::new(tokens).parse(), Err(ParserError::UnexpectedEOF(_))));
+    }
+
     #[test]
     fn function_call_with_one_parameter() {
         let tokens = vec![
@@ -1040,9 +1333,11 @@ mod tests {
                             vec![
                                 Rc::new(AstNode::Identifier("y".into()))
                             ]
-                        ))
+                        )),
+                        span: None,
                     })),
                     inherit: None,
+                    span: None,
                 })
             ],
             parent: None,
@@ -1081,9 +1376,11 @@ mod tests {
                                 Rc::new(AstNode::Identifier("z".into())),
                                 Rc::new(AstNode::Identifier("a".into())),
                             ]
-                        ))
+                        )),
+                        span: None,
                     })),
                     inherit: None,
+                    span: None,
                 })
             ],
             parent: None,
@@ -1105,7 +1402,7 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse();
         assert!(ast.is_err());
-        assert_eq!(ast.unwrap_err(), ParserError::MalformedNumber(3, 1, "5.0".into()));
+        assert_eq!(ast.unwrap_err(), ParserError::MalformedNumber(Location::new(3, 1), "5.0".into()));
     }
 
     #[test]
@@ -1117,7 +1414,7 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse();
         assert!(ast.is_err());
-        assert_eq!(ast.unwrap_err(), ParserError::NotANumber(1, 1, "abc".into()));
+        assert_eq!(ast.unwrap_err(), ParserError::NotANumber(Location::new(1, 1), "abc".into()));
     }
 
     #[test]
@@ -1129,7 +1426,7 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse();
         assert!(ast.is_err());
-        assert_eq!(ast.unwrap_err(), ParserError::BinaryOpWithNoLHS(0, 1))
+        assert_eq!(ast.unwrap_err(), ParserError::BinaryOpWithNoLHS(Location::new(0, 1)))
     }
 
     #[test]
@@ -1151,7 +1448,7 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse();
         assert!(ast.is_err());
-        assert_eq!(ast.unwrap_err(), ParserError::WildcardAndElements(6, 1, "*".into()))
+        assert_eq!(ast.unwrap_err(), ParserError::WildcardAndElements(Location::new(6, 1), "*".into()))
     }
 
     #[test]
@@ -1173,6 +1470,132 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let ast = parser.parse();
         assert!(ast.is_err());
-        assert_eq!(ast.unwrap_err(), ParserError::WildcardAndElements(6, 1, "a".into()))
+        assert_eq!(ast.unwrap_err(), ParserError::WildcardAndElements(Location::new(6, 1), "a".into()))
+    }
+
+    #[test]
+    fn errors_report_source_locations() {
+        let source = "let x = 1;\nlet y inherit (a, *) = 2;\n";
+        let tokens = Lexer::new(crate::unicodesegmenters::segment_graphemes(source)).tokenize_with_locations().unwrap();
+        let mut parser = Parser::with_locations(tokens);
+        let err = parser.parse().unwrap_err();
+        let location = *err.location().unwrap();
+        assert_eq!(location.line, 2);
+        assert_eq!(location.column, Some(19));
+        assert_eq!(location.span.map(|span| &source[span.start..span.end]), Some("*"));
+        assert_eq!(err.to_string(), "Parser error at line 2, column 19: Cannot specify both identifiers and wildcard: '*'");
+    }
+
+    #[test]
+    fn lines_count_breaks_inside_strings() {
+        let source = "let x = \"first\nsecond\";\rlet y inherit (a, *) = 2;\n";
+        let tokens = Lexer::from_text(source).tokenize().unwrap();
+        let err = Parser::new(tokens).parse().unwrap_err();
+        assert_eq!(err.line(), Some(3));
+
+        let tokens = Lexer::from_text(source).tokenize_with_locations().unwrap();
+        let err = Parser::with_locations(tokens).parse().unwrap_err();
+        assert_eq!(err.line(), Some(3));
+        assert_eq!(err.location().unwrap().column, Some(19));
+    }
+
+    #[test]
+    fn tracing_records_steps() {
+        let mut parser = Parser::from_stream(Lexer::from_text("let x = a + b;").tokenize_stream().unwrap()).with_tracing();
+        parser.parse().unwrap();
+        let steps = parser.take_trace();
+        let constructs: Vec<&str> = steps.iter().filter_map(|step| match step {
+            ParseStep::Enter(construct) => Some(*construct),
+            _ => None,
+        }).collect();
+        assert_eq!(constructs, vec!["environment", "assignment", "environment", "binary operation", "right-hand side"]);
+        assert_eq!(steps.iter().filter(|step| matches!(step, ParseStep::Exit(_))).count(), constructs.len());
+        assert!(steps.contains(&ParseStep::Token(Location { token: 0, line: 1, column: Some(1), span: Some(Span::new(0, 3)) }, Token::Keyword(Keywords::LET))));
+        assert!(steps.iter().any(|step| matches!(step, ParseStep::Decision(location, _) if location.column == Some(11))));
+        assert!(parser.take_trace().is_empty());
+
+        // Steps are recorded up to an error, and not at all without tracing
+        let mut parser = Parser::new(Lexer::from_text("let = 2;").tokenize().unwrap()).with_tracing();
+        assert!(parser.parse().is_err());
+        assert_eq!(parser.take_trace().last(), Some(&ParseStep::Exit("environment")));
+        let mut parser = Parser::new(Lexer::from_text("let x = 2;").tokenize().unwrap());
+        parser.parse().unwrap();
+        assert!(parser.take_trace().is_empty());
+    }
+
+    #[test]
+    fn parsers_from_streams_keep_the_source() {
+        let source = "let x = 1;\nlet y inherit (a, *) = 2;\n";
+        let mut parser = Parser::from_stream(Lexer::from_text(source).tokenize_stream().unwrap());
+        assert_eq!(parser.source(), Some(source));
+        let err = parser.parse().unwrap_err();
+        assert_eq!(err.location().unwrap().span.map(|span| &source[span.start..span.end]), Some("*"));
+        assert_eq!(Parser::new(vec![Token::EOF]).source(), None);
+    }
+
+    #[test]
+    fn error_not_with_lhs() {
+        let tokens = vec![
+            Token::Identifier("a".into()),
+            Token::Operator(Operators::Logical(LogicalOperators::NOT)),
+            Token::Boolean(Booleans::TRUE),
+            Token::EOF
+        ];
+        let mut parser = Parser::new(tokens);
+        let ast = parser.parse();
+        assert_eq!(ast.unwrap_err(), ParserError::InvalidOperation(Location::new(1, 1), "!".into()))
+    }
+
+    #[test]
+    fn unit_suffixes_multiply_numbers() {
+        let parsed = |source: &str| Parser::new(Lexer::from_text(source).tokenize().unwrap()).parse().map(|ast| ast.get_bindings().unwrap()[0].clone());
+        assert_eq!(parsed("10s;").unwrap().as_ref(), &AstNode::Integer(10_000));
+        assert_eq!(parsed("2h;").unwrap().as_ref(), &AstNode::Integer(7_200_000));
+        assert_eq!(parsed("512kb;").unwrap().as_ref(), &AstNode::Integer(512_000));
+        assert_eq!(parsed("1gib;").unwrap().as_ref(), &AstNode::Integer(1 << 30));
+        assert_eq!(parsed("1.5s;").unwrap().as_ref(), &AstNode::Float(1500.0));
+        assert!(matches!(parsed("1s.5;"), Err(ParserError::NotANumber(_, num)) if num == "1s.5"));
+
+        // Overflowing digits and overflowing products are both out of range, reported over the whole literal
+        let located = |source: &str| Parser::with_locations(Lexer::from_text(source).tokenize_with_locations().unwrap()).parse();
+        for (literal, end) in [("99999999999999999999h", 29), ("9999999999999999h", 25), ("99999999999tib", 22)] {
+            let err = located(&format!("let x = {};\n", literal)).unwrap_err();
+            assert_eq!(err.code(), "E0138");
+            assert!(matches!(&err, ParserError::NumberOutOfRange(location, num) if num == literal && location.span == Some(Span::new(8, end))), "{:?}", err);
+            assert_eq!(err.message(), format!("Number literal '{}' is out of range", literal));
+        }
+        // Integers without a unit are still promoted to floats
+        assert_eq!(parsed("99999999999999999999;").unwrap().as_ref(), &AstNode::Float(99999999999999999999.0));
+    }
+
+    #[test]
+    fn parses_functions_declared_in_function_bodies() {
+        let source = "let fun make[n] = {\n    let fun add[x] inherit (n) = {\n        return x + n;\n    }\n    return add;\n}\nlet a = 1;\n";
+        let ast = Parser::new(Lexer::from_text(source).tokenize().unwrap()).parse().unwrap();
+        let bindings = ast.get_bindings().unwrap();
+        assert_eq!(bindings.len(), 2);
+        let AstNode::Let { value: Some(make), .. } = bindings[0].as_ref() else { panic!("{:?}", bindings[0]) };
+        let AstNode::Function { body, r#return, .. } = make.as_ref() else { panic!("{:?}", make) };
+        assert!(matches!(body.get_bindings().unwrap()[..], [ref add] if matches!(add.as_ref(), AstNode::Let { name, .. } if name.as_ref() == "add")));
+        assert_eq!(r#return.get_bindings().unwrap(), vec![Rc::new(AstNode::Identifier("add".into()))]);
+    }
+
+    #[test]
+    fn parses_functions_declared_in_environments() {
+        let names = |node: &AstNode| node.get_bindings().unwrap().iter()
+            .filter_map(|binding| match binding.as_ref() { AstNode::Let { name, .. } => Some(name.to_string()), _ => None })
+            .collect::<Vec<String>>();
+        let source = "let env = { let fun f[] = { return 1; } let y = 2; };\nlet z = 3;\n";
+        let ast = Parser::new(Lexer::from_text(source).tokenize().unwrap()).parse().unwrap();
+        assert_eq!(names(&ast), vec!["env", "z"]);
+        let bindings = ast.get_bindings().unwrap();
+        let AstNode::Let { value: Some(env), .. } = bindings[0].as_ref() else { panic!("{:?}", bindings[0]) };
+        assert_eq!(names(env), vec!["f", "y"]);
+
+        // The braces of an assigned value end it without a line terminator
+        for source in ["let env = { let fun f[] = { return 1; } }\nlet z = 3;\n", "let env = { let y = 2; }\nlet z = 3;\n"] {
+            let ast = Parser::new(Lexer::from_text(source).tokenize().unwrap()).parse().unwrap();
+            assert_eq!(names(&ast), vec!["env", "z"], "{}", source);
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,189 @@
+//! Single-call entry points running the phases of Envlang in a row
+//!
+//! Each phase can also be run on its own (see [`crate::unicodesegmenters`], [`crate::lexer`], [`crate::parser`], and [`crate::lint`]), but most callers only need the end result:
+//! * [`parse_source`] segments, lexes, and parses source code into an [`AstNode`]. [`parse_source_with`] does the same with [`ParserOptions`].
+//! * [`check_source`] additionally runs the lints configured in a [`Config`]. Callers that also need the AST use [`parse_source_with_config`] and [`check_ast`] instead.
+//! * [`run_source`] parses source code with the settings of a [`Config`] and evaluates it with the [`Evaluator`].
+//!
+//! Callers that drive the [`Parser`] themselves, e.g. to trace it, get one set up with the settings of a [`Config`] from [`parser_with_config`].
+//!
+//! All of these functions record source locations, so that parser errors carry line and column numbers, and runtime errors the span of the binding or call they occurred in.
+
+use crate::config::Config;
+use crate::diagnostic::{deduplicate, limit, Diagnostic};
+use crate::error::EnvlangError;
+use crate::evaluator::Evaluator;
+use crate::lexer::Lexer;
+use crate::lint::lint;
+use crate::parser::{AstNode, Parser, ParserOptions};
+use crate::value::Value;
+
+/// Parses Envlang source code into an [`AstNode`] representing the global environment
+///
+/// # Errors
+/// * [`EnvlangError::Lexer`]: The source could not be tokenized.
+/// * [`EnvlangError::Parser`]: The tokens could not be parsed.
+///
+/// # Examples
+/// ```
+/// use envlang::parse_source;
+///
+/// let ast = parse_source("let x = 5;\n").unwrap();
+/// assert_eq!(ast.get_bindings().map(|bindings| bindings.len()), Some(1));
+/// assert!(parse_source("let x = \"abc").is_err());
+/// ```
+pub fn parse_source(source: &str) -> Result<AstNode, EnvlangError> {
+    return parse_source_with(source, ParserOptions::default());
+}
+
+/// Parses Envlang source code into an [`AstNode`] with the given [`ParserOptions`]
+///
+/// # Errors
+/// Any errors from [`parse_source`], and the errors enabled by the options (e.g. [`ParserError::UnmatchedBrace`](crate::parser::ParserError::UnmatchedBrace)).
+pub fn parse_source_with(source: &str, options: ParserOptions) -> Result<AstNode, EnvlangError> {
+    let tokens = Lexer::from_text(source).tokenize_stream()?;
+    return Ok(Parser::from_stream(tokens).with_options(options).parse()?);
+}
+
+/// Parses Envlang source code with the settings of a [`Config`]
+///
+/// The operator aliases enabled in [`Config::operator_aliases`] are recognised in the source.
+/// Braces are only checked for balance if [`Config::strict_braces`] is set, and literals are limited in size by the [`Limits`](crate::config::Limits) of the configuration.
+///
+/// # Errors
+/// Any errors from [`parse_source_with`], and the errors of the literal size limits (e.g. [`LexerError::StringTooLong`](crate::lexer::LexerError::StringTooLong)).
+pub fn parse_source_with_config(source: &str, config: &Config) -> Result<AstNode, EnvlangError> {
+    return Ok(parser_with_config(source, config)?.parse()?);
+}
+
+/// Lexes Envlang source code with the settings of a [`Config`], and sets up a [`Parser`] to parse it with them (see [`parse_source_with_config`])
+///
+/// # Errors
+/// * [`EnvlangError::Lexer`]: The source could not be tokenized, or a literal exceeds the size limits of the configuration.
+pub fn parser_with_config(source: &str, config: &Config) -> Result<Parser, EnvlangError> {
+    let options = ParserOptions { strict_braces: config.strict_braces.unwrap_or(false), ..ParserOptions::default() };
+    let tokens = Lexer::from_text(source)
+        .with_aliases(config.operator_aliases.clone())
+        .with_options(config.limits.lexer_options())
+        .tokenize_stream()?;
+    return Ok(Parser::from_stream(tokens).with_options(options));
+}
+
+/// Runs the lints configured in a [`Config`] on a parsed program
+///
+/// Returns the diagnostics of the lints, which may include errors if lints are denied in the configuration.
+/// Repeated diagnostics are folded together, and the number of diagnostics is capped at [`Limits::max_diagnostics`](crate::config::Limits::max_diagnostics).
+pub fn check_ast(ast: &AstNode, config: &Config) -> Vec<Diagnostic> {
+    let diagnostics = deduplicate(lint(ast, config), &config.messages);
+    return limit(diagnostics, config.limits.max_diagnostics, &config.messages);
+}
+
+/// Parses Envlang source code and runs the lints on it (see [`parse_source_with_config`] and [`check_ast`])
+///
+/// # Errors
+/// Any errors from [`parse_source_with_config`].
+pub fn check_source(source: &str, config: &Config) -> Result<Vec<Diagnostic>, EnvlangError> {
+    let ast = parse_source_with_config(source, config)?;
+    return Ok(check_ast(&ast, config));
+}
+
+/// Parses Envlang source code with the settings of a [`Config`] and evaluates it, returning the global environment as a [`Value::Environment`]
+///
+/// The lints are not run; use [`check_source`] or [`check_ast`] for them.
+///
+/// # Errors
+/// * Any errors from [`parse_source_with_config`].
+/// * [`EnvlangError::Runtime`]: The program could not be evaluated.
+///
+/// # Examples
+/// ```
+/// use envlang::config::Config;
+/// use envlang::printer::print_ast;
+/// use envlang::run_source;
+///
+/// let config = Config::default();
+/// let env = run_source("let port = 8000 + 80;\nlet url = \"localhost\";\n", &config).unwrap();
+/// assert_eq!(print_ast(&env.to_node()), "let port = 8080;\nlet url = \"localhost\";\n");
+/// assert!(run_source("let x = y;\n", &config).is_err());
+/// ```
+pub fn run_source(source: &str, config: &Config) -> Result<Value, EnvlangError> {
+    let ast = parse_source_with_config(source, config)?;
+    return Ok(Evaluator::new().evaluate(&ast)?);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::Severity;
+
+    #[test]
+    fn parses_sources() {
+        let ast = parse_source("let x = 5;\nlet y = x;\n").unwrap();
+        assert_eq!(ast.get_bindings().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn reports_errors_from_every_phase() {
+        let err = parse_source("let x = \"abc").unwrap_err();
+        assert!(matches!(err, EnvlangError::Lexer(_)));
+
+        let err = parse_source("let x inherit (a, *) = 2;\n").unwrap_err();
+        assert!(matches!(err, EnvlangError::Parser(_)));
+        assert_eq!(err.line(), Some(1));
+
+        let err = run_source("let x = 1 + true;\n", &Config::default()).unwrap_err();
+        assert!(matches!(err, EnvlangError::Runtime(_)));
+        assert_eq!(err.code(), "E0302");
+    }
+
+    #[test]
+    fn runs_sources_with_config() {
+        let mut config = Config::default();
+        config.operator_aliases.enable("and");
+        let env = run_source("let x = true and false;\n", &config).unwrap();
+        assert_eq!(crate::printer::print_ast(&env.to_node()), "let x = false;\n");
+
+        config.strict_braces = Some(true);
+        assert_eq!(run_source("let x = 5;\n}\n", &config).unwrap_err().code(), "E0134");
+        config.limits.max_string_length = Some(2);
+        assert_eq!(run_source("let x = \"abc\";\n", &config).unwrap_err().code(), "E0010");
+    }
+
+    #[test]
+    fn checks_braces_when_configured() {
+        let source = "let x = 5;\n}\n";
+        assert!(check_source(source, &Config::default()).is_ok());
+
+        let config = Config { strict_braces: Some(true), ..Config::default() };
+        let err = check_source(source, &config).unwrap_err();
+        assert_eq!(err.code(), "E0134");
+        assert_eq!(err.line(), Some(2));
+    }
+
+    #[test]
+    fn checks_sources_with_operator_aliases() {
+        // Without the alias, `and` is an identifier between two anonymous values
+        let source = "let x = a and b;\n";
+        assert_eq!(check_source(source, &Config::default()).unwrap().len(), 3);
+
+        let mut config = Config::default();
+        config.operator_aliases.enable("and");
+        assert!(check_source(source, &config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn checks_sources() {
+        let config = Config::default();
+        assert!(check_source("let x = 5;\n", &config).unwrap().is_empty());
+
+        let diagnostics = check_source("let fun f[a] = { return 1; }\n", &config).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+
+        let mut config = Config::default();
+        config.limits.max_diagnostics = Some(1);
+        let diagnostics = check_source("let fun f[a, b] = { return 1; }\n", &config).unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[1].severity, Severity::Note);
+    }
+}
@@ -0,0 +1,420 @@
+//! Pretty-printer and formatter for the Envlang Abstract Syntax Tree
+//!
+//! This module renders an [`AstNode`] back into Envlang source code, e.g., for writing generated scripts with [`write_file`], and formats Envlang source code with [`format_source`].
+//!
+//! The output is canonical rather than faithful: comments and the original layout are not part of the AST, so every statement is printed on its own line, indented by one level per environment.
+//! The indentation, the placement of opening braces, and the line width are controlled with [`FormatOptions`].
+//!
+//! Statements that could not be parsed (see [`AstNode::Error`]) are printed as the comment `# unparsed source`.
+//!
+//! Statements are printed in the order of the AST, which is the order of the source for parsed programs, so printed output is the same on every run and platform.
+//!
+//! Printing a parsed program and parsing the result yields a program that prints identically.
+//!
+//! [`write_file`]: ../io/fn.write_file.html
+
+#[cfg(feature = "std")]
+use crate::error::EnvlangError;
+use crate::lexer::escape_string;
+use crate::numeric::format_float;
+use crate::parser::{is_right_associative, precedence_of, AstNode};
+use crate::symbols::{Operators, OtherOperators};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+/// Comment printed in place of a statement that could not be parsed (see [`AstNode::Error`])
+const UNPARSED: &str = "# unparsed source";
+
+/// Placement of the opening brace of an environment or a function body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BraceStyle {
+    /// The brace ends the line of the assignment: `let x = {`
+    #[default]
+    SameLine,
+    /// The brace starts a line of its own, at the indentation of the assignment
+    NextLine,
+}
+
+/// Options controlling the layout of printed source code
+///
+/// The default options indent by four spaces, keep opening braces on the same line, and wrap lines longer than 100 characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Number of spaces per indentation level
+    pub indent_width: usize,
+    /// Placement of opening braces
+    pub brace_style: BraceStyle,
+    /// Maximum length of a line before function arguments and inheritance clauses are wrapped onto one line per element
+    pub line_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        return FormatOptions { indent_width: 4, brace_style: BraceStyle::SameLine, line_width: 100 };
+    }
+}
+
+/// Render an [`AstNode`] as Envlang source code with the default [`FormatOptions`]
+///
+/// The global environment (an [`AstNode::Environment`] without a parent) is printed as a list of top-level statements, each terminated by a newline.
+///
+/// Any other node is printed as a single statement or expression.
+///
+/// # Examples
+/// ```
+/// use envlang::parser::AstNode;
+/// use envlang::printer::print_ast;
+/// use std::rc::Rc;
+///
+/// let ast = AstNode::Environment {
+///     name: None,
+///     bindings: vec![Rc::new(AstNode::Let { name: "x".into(), value: Some(Rc::new(AstNode::Integer(5))), inherit: None, span: None })],
+///     parent: None,
+/// };
+/// assert_eq!(print_ast(&ast), "let x = 5;\n");
+/// ```
+pub fn print_ast(node: &AstNode) -> String { print_ast_with(node, &FormatOptions::default()) }
+
+/// Render an [`AstNode`] as Envlang source code with the given [`FormatOptions`]
+///
+/// See [`print_ast`].
+pub fn print_ast_with(node: &AstNode, options: &FormatOptions) -> String {
+    let printer = Printer { options, first_lines: false };
+    let mut output = String::new();
+    match node {
+        AstNode::Environment { bindings, parent: None, .. } => {
+            for binding in bindings {
+                output.push_str(&printer.statement(binding, 0));
+                output.push('\n');
+            }
+        },
+        _ => output.push_str(&printer.statement(node, 0)),
+    }
+    return output;
+}
+
+/// Render a single expression as Envlang source code, without a terminator
+///
+/// # Examples
+/// ```
+/// use envlang::lexer::Lexer;
+/// use envlang::parser::Parser;
+/// use envlang::printer::print_expression;
+///
+/// let tokens = Lexer::from_text("a+2;").tokenize().unwrap();
+/// let ast = Parser::new(tokens).parse().unwrap();
+/// assert_eq!(print_expression(&ast.get_bindings().unwrap()[0]), "a + 2");
+/// ```
+pub fn print_expression(node: &AstNode) -> String {
+    let printer = Printer { options: &FormatOptions::default(), first_lines: false };
+    return printer.expression(node, 0, false);
+}
+
+/// Formats Envlang source code
+///
+/// # Errors
+/// Any errors from parsing the source code (see [`parse_source`](crate::parse_source)).
+///
+/// # Examples
+/// ```
+/// use envlang::printer::{format_source, FormatOptions};
+///
+/// let options = FormatOptions { indent_width: 2, ..FormatOptions::default() };
+/// assert_eq!(format_source("let x = {let y = 5;};", &options).unwrap(), "let x = {\n  let y = 5;\n};\n");
+/// ```
+#[cfg(feature = "std")]
+pub fn format_source(source: &str, options: &FormatOptions) -> Result<String, EnvlangError> {
+    let ast = crate::parse_source(source)?;
+    return Ok(print_ast_with(&ast, options));
+}
+
+/// Renders nodes according to a set of [`FormatOptions`]
+///
+/// A printer for `first_lines` stops rendering environments and function bodies at their opening brace, which measures the first line of a statement without rendering its contents.
+struct Printer<'a> {
+    options: &'a FormatOptions,
+    first_lines: bool,
+}
+
+impl Printer<'_> {
+    /// Gets the indentation of a depth
+    fn indent(&self, depth: usize) -> String { " ".repeat(self.options.indent_width * depth) }
+
+    /// Gets the separator between an assignment and an opening brace at a depth
+    fn brace_separator(&self, depth: usize) -> String {
+        match self.options.brace_style {
+            BraceStyle::SameLine => " ".to_string(),
+            BraceStyle::NextLine => format!("\n{}", self.indent(depth)),
+        }
+    }
+
+    /// Render a node as a statement at the given indentation depth
+    ///
+    /// If the first line of the statement is longer than the line width, the statement is rendered with its lists wrapped.
+    /// Only the first line is measured, so that the contents of nested environments are rendered once, instead of once per enclosing statement.
+    fn statement(&self, node: &AstNode, depth: usize) -> String {
+        let first_lines = Printer { options: self.options, first_lines: true };
+        let output = first_lines.statement_wrapped(node, depth, false);
+        let first_line = output.lines().next().unwrap_or_default();
+        let wrap = first_line.chars().count() > self.options.line_width;
+        if self.first_lines && !wrap {
+            return output;
+        }
+        return self.statement_wrapped(node, depth, wrap);
+    }
+
+    /// Render a node as a statement, with its lists wrapped or on a single line
+    ///
+    /// Assignments and function declarations are printed with their keywords, and all other nodes are printed as terminated expressions.
+    fn statement_wrapped(&self, node: &AstNode, depth: usize, wrap: bool) -> String {
+        let indent = self.indent(depth);
+        match node {
+            AstNode::Let { name, value, inherit, .. } => {
+                let inheritance = match inherit {
+                    Some(clause) => format!(" {}", self.expression(clause, depth, wrap)),
+                    None => String::new(),
+                };
+                match value.as_deref() {
+                    Some(AstNode::Function { params, body, r#return, .. }) => {
+                        let mut output = format!("{}let fun {}{}{} ={}{{\n", indent, name, self.expression(params, depth, wrap), inheritance, self.brace_separator(depth));
+                        if self.first_lines {
+                            return output;
+                        }
+                        if let Some(bindings) = body.get_bindings() {
+                            for binding in bindings {
+                                output.push_str(&self.statement(&binding, depth + 1));
+                                output.push('\n');
+                            }
+                        }
+                        output.push_str(&format!("{}return {};\n", self.indent(depth + 1), self.return_value(r#return, depth + 1)));
+                        output.push_str(&format!("{}}}", indent));
+                        return output;
+                    },
+                    Some(value @ AstNode::Environment { bindings, .. }) if !bindings.is_empty() => {
+                        return format!("{}let {}{} ={}{};", indent, name, inheritance, self.brace_separator(depth), self.expression(value, depth, wrap));
+                    },
+                    Some(value) => {
+                        return format!("{}let {}{} = {};", indent, name, inheritance, self.expression(value, depth, wrap));
+                    },
+                    None => {
+                        return format!("{}let {}{} = {{}};", indent, name, inheritance);
+                    },
+                }
+            },
+            AstNode::Environment { .. } | AstNode::Error(_) => {
+                return format!("{}{}", indent, self.expression(node, depth, wrap));
+            },
+            _ => {
+                return format!("{}{};", indent, self.expression(node, depth, wrap));
+            },
+        }
+    }
+
+    /// Render the returned environment of a function
+    ///
+    /// A single expression is returned as is, while anything else is wrapped in an explicit environment.
+    fn return_value(&self, node: &AstNode, depth: usize) -> String {
+        if let Some(bindings) = node.get_bindings() {
+            if bindings.len() == 1 && !matches!(*bindings[0], AstNode::Let { .. }) {
+                return self.expression(&bindings[0], depth, false);
+            }
+        }
+        return self.expression(node, depth, false);
+    }
+
+    /// Render a list of elements between delimiters, either on one line or with one element per line
+    fn list(&self, elements: Vec<String>, open: &str, close: &str, depth: usize, wrap: bool) -> String {
+        if !wrap || elements.is_empty() {
+            return format!("{}{}{}", open, elements.join(", "), close);
+        }
+        let mut output = format!("{}\n", open);
+        for element in elements {
+            output.push_str(&format!("{}{},\n", self.indent(depth + 1), element));
+        }
+        output.push_str(&format!("{}{}", self.indent(depth), close));
+        return output;
+    }
+
+    /// Render a node as an expression at the given indentation depth
+    ///
+    /// The depth is only used for the closing delimiters of multi-line environments and wrapped lists, as the expression itself continues the current line.
+    fn expression(&self, node: &AstNode, depth: usize, wrap: bool) -> String {
+        match node {
+            AstNode::Integer(num)
+                => num.to_string(),
+            AstNode::Float(num)
+                => format_float(*num),
+            AstNode::Boolean(b)
+                => b.to_string(),
+            AstNode::String(s)
+                => format!("\"{}\"", escape_string(s)),
+            AstNode::Identifier(name)
+                => name.to_string(),
+            AstNode::Environment { bindings, .. } => {
+                if bindings.is_empty() {
+                    return "{}".to_string();
+                }
+                if self.first_lines {
+                    return "{\n".to_string();
+                }
+                let mut output = "{\n".to_string();
+                for binding in bindings {
+                    output.push_str(&self.statement(binding, depth + 1));
+                    output.push('\n');
+                }
+                output.push_str(&self.indent(depth));
+                output.push('}');
+                return output;
+            },
+            AstNode::UnaryOp { op, operand } => match operand.as_ref() {
+                AstNode::Identifier(_) | AstNode::Boolean(_) | AstNode::FunctionCall { .. }
+                | AstNode::BinaryOp { operator: Operators::Other(OtherOperators::ACCESSOR), .. }
+                    => format!("{}{}", op.to_string(), self.expression(operand, depth, wrap)),
+                AstNode::Integer(num) if *num >= 0
+                    => format!("{}{}", op.to_string(), self.expression(operand, depth, wrap)),
+                AstNode::Float(num) if *num >= 0.0
+                    => format!("{}{}", op.to_string(), self.expression(operand, depth, wrap)),
+                _ => format!("{}({})", op.to_string(), self.expression(operand, depth, wrap)),
+            },
+            AstNode::BinaryOp { left, operator: Operators::Other(OtherOperators::ACCESSOR), right }
+                => format!("{}.{}", self.expression(left, depth, wrap), self.expression(right, depth, wrap)),
+            AstNode::BinaryOp { left, operator, right }
+                => format!("{} {} {}", self.operand(left, operator, false, depth, wrap), operator.to_string(), self.operand(right, operator, true, depth, wrap)),
+            AstNode::Let { .. }
+                => self.statement(node, depth).trim_start().to_string(),
+            AstNode::Inherit { names }
+                => format!("inherit {}", match names {
+                    Some(names) => self.list(names.iter().map(|name| name.to_string()).collect(), "(", ")", depth, wrap),
+                    None => "(*)".to_string(),
+                }),
+            AstNode::Function { .. }
+                => "{}".to_string(),
+            AstNode::FunctionArgs(args)
+                => self.list(args.iter().map(|arg| self.expression(arg, depth + 1, wrap)).collect(), "[", "]", depth, wrap),
+            AstNode::FunctionCall { id, args, .. }
+                => format!("{}{}", self.expression(id, depth, wrap), self.expression(args, depth, wrap)),
+            AstNode::Error(_)
+                => UNPARSED.to_string(),
+        }
+    }
+
+    /// Renders an operand of a binary operation, parenthesised if the operation it contains would otherwise group differently
+    ///
+    /// An operation needs parentheses when its operator binds looser than the enclosing operator, or equally tight on the side the enclosing operator does not group from.
+    fn operand(&self, node: &AstNode, parent: &Operators, right: bool, depth: usize, wrap: bool) -> String {
+        let expression = self.expression(node, depth, wrap);
+        let AstNode::BinaryOp { operator, .. } = node else {
+            return expression;
+        };
+        match (precedence_of(operator), precedence_of(parent)) {
+            (Some(inner), Some(outer)) if inner < outer || (inner == outer && right != is_right_associative(parent)) => {
+                return format!("({})", expression);
+            },
+            _ => return expression,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::unicodesegmenters::segment_graphemes;
+
+    fn parse(source: &str) -> AstNode {
+        let tokens = Lexer::new(segment_graphemes(source)).tokenize().unwrap();
+        return Parser::new(tokens).parse().unwrap();
+    }
+
+    #[test]
+    fn prints_literals() {
+        let ast = parse("let a = 5;\nlet b = 1.0;\nlet c = true;\nlet d = \"text\";\nlet e = f;\n");
+        assert_eq!(print_ast(&ast), "let a = 5;\nlet b = 1.0;\nlet c = true;\nlet d = \"text\";\nlet e = f;\n");
+        // Large floats are printed without an exponent, so that they can be read back
+        assert_eq!(print_expression(&AstNode::Float(1e20)), "100000000000000000000.0");
+    }
+
+    #[test]
+    fn prints_escaped_strings() {
+        let ast = parse("let a = 'say \\u{22}hi\\\"\\\\';\nlet b = \"line\\nbreak\\u{7}\";\n");
+        assert_eq!(print_ast(&ast), "let a = \"say \\\"hi\\\"\\\\\";\nlet b = \"line\nbreak\\u{7}\";\n");
+        assert_eq!(print_ast(&parse(&print_ast(&ast))), print_ast(&ast));
+    }
+
+    #[test]
+    fn prints_operations() {
+        let ast = parse("a.b;\n");
+        assert_eq!(print_ast(&ast), "a.b;\n");
+        let ast = parse("1 + 2;\n");
+        assert_eq!(print_ast(&ast), "1 + 2;\n");
+        // Parentheses are only printed where the grouping differs from the precedence of the operators
+        let ast = parse("(1 + 2) * 3 - (4 - 5) + 2 ^ (3 ^ 2) + (2 ^ 3) ^ 2 + -(a * b) + 1 * 2;\n");
+        assert_eq!(print_ast(&ast), "(1 + 2) * 3 - (4 - 5) + 2 ^ 3 ^ 2 + (2 ^ 3) ^ 2 + -(a * b) + 1 * 2;\n");
+    }
+
+    #[test]
+    fn prints_inherit_clauses() {
+        let ast = parse("let x inherit (a, b) = 1;\nlet y inherit (*) = 2;\n");
+        assert_eq!(print_ast(&ast), "let x inherit (a, b) = 1;\nlet y inherit (*) = 2;\n");
+    }
+
+    #[test]
+    fn prints_nested_environments() {
+        let ast = parse("let x = { let y = 5; let z = 3; };\n");
+        assert_eq!(print_ast(&ast), "let x = {\n    let y = 5;\n    let z = 3;\n};\n");
+    }
+
+    #[test]
+    fn prints_functions() {
+        let ast = parse("let fun foo[a, b] = { let c = a; return c; }\nlet x = foo[y, z];\n");
+        assert_eq!(print_ast(&ast), "let fun foo[a, b] = {\n    let c = a;\n    return c;\n}\nlet x = foo[y, z];\n");
+    }
+
+    #[test]
+    fn printing_is_stable() {
+        let source = "let x = { let y = 5; };\nlet fun f[] inherit (x) = { return { let z = 1; }; }\n";
+        let printed = print_ast(&parse(source));
+        assert_eq!(print_ast(&parse(&printed)), printed);
+    }
+
+    #[test]
+    fn prints_unparsed_statements_as_comments() {
+        let tokens = Lexer::from_text("let a = 1;\nlet = 2;\n").tokenize().unwrap();
+        let (ast, _) = Parser::new(tokens).parse_recovering();
+        assert_eq!(print_ast(&ast), "let a = 1;\n# unparsed source\n");
+    }
+
+    #[test]
+    fn keeps_binding_order() {
+        let source = "let zeta = 1;\nlet alpha = { let y = 2; let b = 3; let y = 4; };\nlet mid = 5;\n";
+        let printed = print_ast(&parse(source));
+        assert_eq!(printed, "let zeta = 1;\nlet alpha = {\n    let y = 2;\n    let b = 3;\n    let y = 4;\n};\nlet mid = 5;\n");
+    }
+
+    #[test]
+    fn honors_indent_width_and_brace_style() {
+        let ast = parse("let x = { let y = 5; };\nlet fun f[a] = { return a; }\n");
+        let options = FormatOptions { indent_width: 2, brace_style: BraceStyle::NextLine, ..FormatOptions::default() };
+        let printed = print_ast_with(&ast, &options);
+        assert_eq!(printed, "let x =\n{\n  let y = 5;\n};\nlet fun f[a] =\n{\n  return a;\n}\n");
+        assert_eq!(print_ast_with(&parse(&printed), &options), printed);
+    }
+
+    #[test]
+    fn wraps_long_lines() {
+        let ast = parse("let x inherit (alpha, beta, gamma) = foo[alpha, beta];\n");
+        let options = FormatOptions { line_width: 30, ..FormatOptions::default() };
+        let printed = print_ast_with(&ast, &options);
+        assert_eq!(printed, "let x inherit (\n    alpha,\n    beta,\n    gamma,\n) = foo[\n    alpha,\n    beta,\n];\n");
+        assert_eq!(print_ast_with(&parse(&printed), &options), printed);
+        assert_eq!(print_ast(&ast), "let x inherit (alpha, beta, gamma) = foo[alpha, beta];\n");
+    }
+
+    #[test]
+    fn formats_sources() {
+        assert_eq!(format_source("let x={let y=5;};", &FormatOptions::default()).unwrap(), "let x = {\n    let y = 5;\n};\n");
+        assert!(format_source("let x = \"abc", &FormatOptions::default()).is_err());
+    }
+}
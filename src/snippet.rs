@@ -0,0 +1,250 @@
+//! Rendering of diagnostics as annotated source snippets
+//!
+//! [`render`] lays out a [`Diagnostic`] together with the lines of source code it refers to, so that every frontend prints diagnostics identically:
+//!
+//! ```text
+//! error[E0002]: Unterminated string literal 'abc'
+//!  --> script.envl:1:9
+//!   |
+//! 1 | let x = "abc
+//!   |         ^
+//!   = help: Close the string literal
+//! ```
+//!
+//! The primary span is underlined with `^`, and the spans of labels with `-` followed by the label message.
+//! Spans covering several lines are underlined on each line, with the label message on the final line.
+//! Lines that are not annotated are left out, and gaps between the shown lines are marked with `...`.
+
+use crate::diagnostic::Diagnostic;
+use crate::span::Span;
+use crate::io::SourceFile;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Width of a tab character in rendered snippets
+const TAB_WIDTH: usize = 4;
+
+/// A single underline of a source line
+struct Underline<'a> {
+    /// One-indexed grapheme column of the first underlined grapheme
+    start: usize,
+    /// Number of underlined graphemes (at least one)
+    len: usize,
+    marker: char,
+    message: Option<&'a str>,
+}
+
+/// Renders a diagnostic with the source lines its spans point to
+///
+/// Spans that do not fall on grapheme boundaries of the source are ignored. A diagnostic without any valid spans is rendered like its `Display` output, with notes and help messages below the header.
+///
+/// # Examples
+/// ```
+/// use envlang::diagnostic::{Diagnostic, Span};
+/// use envlang::io::SourceFile;
+/// use envlang::snippet::render;
+///
+/// let source = SourceFile::new("script.envl", "let x = 5;\nlet y = x;\n");
+/// let diagnostic = Diagnostic::warning("Unused binding 'y'")
+///     .with_code("W0001")
+///     .with_span(Span::new(15, 16));
+/// assert_eq!(render(&diagnostic, &source), "\
+/// warning[W0001]: Unused binding 'y'
+///  --> script.envl:2:5
+///   |
+/// 2 | let y = x;
+///   |     ^
+/// ");
+/// ```
+pub fn render(diagnostic: &Diagnostic, source: &SourceFile) -> String {
+    let mut output = match diagnostic.code {
+        Some(code) => format!("{}[{}]: {}\n", diagnostic.severity.to_string(), code, diagnostic.message),
+        None => format!("{}: {}\n", diagnostic.severity.to_string(), diagnostic.message),
+    };
+
+    let mut annotations: Vec<(Span, char, Option<&str>)> = Vec::new();
+    if let Some(span) = diagnostic.span {
+        annotations.push((span, '^', None));
+    }
+    for label in &diagnostic.labels {
+        annotations.push((label.span, '-', Some(label.message.as_str())));
+    }
+
+    // Underlines keyed by one-indexed line number, in the order the lines appear in the source
+    let mut lines: Vec<(usize, Vec<Underline>)> = Vec::new();
+    let mut anchor: Option<(usize, usize)> = None;
+    for (span, marker, message) in annotations {
+        let (Some(start), Some(end)) = (source.line_col(span.start), source.line_col(span.end.max(span.start))) else {
+            continue;
+        };
+        if anchor.is_none() {
+            anchor = Some(start);
+        }
+        for (line, underline) in underlines(source, start, end, marker, message) {
+            match lines.iter_mut().find(|(existing, _)| *existing == line) {
+                Some((_, underlines)) => underlines.push(underline),
+                None => lines.push((line, vec![underline])),
+            }
+        }
+    }
+    lines.sort_by_key(|(line, _)| *line);
+
+    let width = lines.last().map(|(line, _)| line.to_string().len()).unwrap_or(0);
+    let pad = " ".repeat(width);
+    if let Some((line, column)) = anchor {
+        output.push_str(&format!("{}--> {}:{}:{}\n", pad, source.name(), line, column));
+        output.push_str(&format!("{} |\n", pad));
+        let mut previous: Option<usize> = None;
+        for (line, underlines) in &lines {
+            if previous.is_some_and(|previous| line - previous > 1) {
+                output.push_str("...\n");
+            }
+            let text = source.line_text(*line).unwrap_or_default();
+            output.push_str(&format!("{:>width$} | {}\n", line, expand_tabs(text), width = width));
+            for underline in underlines {
+                output.push_str(&format!("{} | {}", pad, underline_text(text, underline)));
+                if let Some(message) = underline.message {
+                    output.push(' ');
+                    output.push_str(message);
+                }
+                output.push('\n');
+            }
+            previous = Some(*line);
+        }
+    }
+
+    for note in &diagnostic.notes {
+        output.push_str(&format!("{} = note: {}\n", pad, note));
+    }
+    for suggestion in &diagnostic.suggestions {
+        output.push_str(&format!("{} = help: {}\n", pad, suggestion.message));
+    }
+    return output;
+}
+
+/// Splits the span between two `(line, column)` pairs into underlines of single lines
+///
+/// The message is attached to the underline of the final line.
+fn underlines<'a>(source: &SourceFile, start: (usize, usize), end: (usize, usize), marker: char, message: Option<&'a str>) -> Vec<(usize, Underline<'a>)> {
+    let (start_line, start_col) = start;
+    let (mut end_line, mut end_col) = end;
+    // A span ending at the start of a line does not cover any of that line
+    if end_line > start_line && end_col == 1 {
+        end_line -= 1;
+        end_col = line_length(source, end_line) + 1;
+    }
+
+    let mut result = Vec::new();
+    for line in start_line..=end_line {
+        let first = if line == start_line { start_col } else { 1 };
+        let last = if line == end_line { end_col } else { line_length(source, line) + 1 };
+        result.push((line, Underline {
+            start: first,
+            len: last.saturating_sub(first).max(1),
+            marker,
+            message: if line == end_line { message } else { None },
+        }));
+    }
+    return result;
+}
+
+/// Gets the length of a line in graphemes
+fn line_length(source: &SourceFile, line: usize) -> usize {
+    return source.line_text(line).unwrap_or_default().graphemes(true).count();
+}
+
+/// Replaces tabs with spaces, so that underlines line up with the text above them
+fn expand_tabs(text: &str) -> String {
+    return text.replace('\t', &" ".repeat(TAB_WIDTH));
+}
+
+/// Builds the marker line of an underline below a line of text
+fn underline_text(text: &str, underline: &Underline) -> String {
+    let mut result = String::new();
+    let mut graphemes = text.graphemes(true);
+    for _ in 1..underline.start {
+        match graphemes.next() {
+            Some("\t") => result.push_str(&" ".repeat(TAB_WIDTH)),
+            _ => result.push(' '),
+        }
+    }
+    for _ in 0..underline.len {
+        match graphemes.next() {
+            Some("\t") => result.push_str(&underline.marker.to_string().repeat(TAB_WIDTH)),
+            _ => result.push(underline.marker),
+        }
+    }
+    return result;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_headers_without_spans() {
+        let source = SourceFile::new("test.envl", "let x = 5;\n");
+        let diagnostic = Diagnostic::error("Unexpected end of file").with_note("The error was found on line 1");
+        assert_eq!(render(&diagnostic, &source), "error: Unexpected end of file\n = note: The error was found on line 1\n");
+    }
+
+    #[test]
+    fn renders_primary_spans() {
+        let source = SourceFile::new("test.envl", "let x = \"abc");
+        let diagnostic = Diagnostic::error("Unterminated string literal 'abc'")
+            .with_code("E0002")
+            .with_span(Span::new(8, 9))
+            .with_suggestion(Span::new(12, 12), "\"", "Close the string literal");
+        assert_eq!(render(&diagnostic, &source), "\
+error[E0002]: Unterminated string literal 'abc'
+ --> test.envl:1:9
+  |
+1 | let x = \"abc
+  |         ^
+  = help: Close the string literal
+");
+    }
+
+    #[test]
+    fn renders_labels_on_several_lines() {
+        let text = "let x = 1;\nlet y = 2;\nlet z = 3;\n\n\n\n\n\n\nlet x = 4;\n";
+        let source = SourceFile::new("test.envl", text);
+        let second = text.rfind("x").unwrap();
+        let diagnostic = Diagnostic::warning("Binding 'x' shadows a binding of an enclosing environment")
+            .with_span(Span::new(second, second + 1))
+            .with_label(Span::new(4, 5), "first declared here");
+        assert_eq!(render(&diagnostic, &source), "\
+warning: Binding 'x' shadows a binding of an enclosing environment
+  --> test.envl:10:5
+   |
+ 1 | let x = 1;
+   |     - first declared here
+...
+10 | let x = 4;
+   |     ^
+");
+    }
+
+    #[test]
+    fn renders_multi_line_spans() {
+        let source = SourceFile::new("test.envl", "let x = {\n\tlet y = 5;\n};\n");
+        let diagnostic = Diagnostic::error("Unclosed environment").with_label(Span::new(8, 23), "environment");
+        assert_eq!(render(&diagnostic, &source), "\
+error: Unclosed environment
+ --> test.envl:1:9
+  |
+1 | let x = {
+  |         -
+2 |     let y = 5;
+  | --------------
+3 | };
+  | - environment
+");
+    }
+
+    #[test]
+    fn renders_empty_spans_at_the_end_of_the_file() {
+        let source = SourceFile::new("test.envl", "let x = 5");
+        let diagnostic = Diagnostic::error("Missing ';'").with_span(Span::new(9, 9));
+        assert_eq!(render(&diagnostic, &source), "error: Missing ';'\n --> test.envl:1:10\n  |\n1 | let x = 5\n  |          ^\n");
+    }
+}
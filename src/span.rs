@@ -0,0 +1,106 @@
+//! Source positions and ranges shared across the crate
+//!
+//! Everything that points into source text uses these types:
+//! * A [`Position`] is a single point, known by both its byte offset and its one-indexed line and column.
+//! * A [`Span`] is a half-open range of byte offsets.
+//!
+//! Lines are separated by `\n`, `\r\n`, or a lone `\r` (see [`is_line_break`]), and columns count graphemes.
+//! Token [`Location`](crate::lexer::Location)s and [`Diagnostic`](crate::diagnostic::Diagnostic)s carry spans, and [`SourceFile::position`](crate::io::SourceFile::position) turns an offset into a position.
+
+use crate::unicodesegmenters::graphemes_iter;
+
+/// Checks whether a grapheme ends a line
+///
+/// This is the single definition of line breaks, which the lexer, the parser, and [`SourceFile`](crate::io::SourceFile) all count lines by.
+pub fn is_line_break(grapheme: &str) -> bool { matches!(grapheme, "\n" | "\r\n" | "\r") }
+
+/// Counts the line breaks in a text
+///
+/// # Examples
+/// ```
+/// use envlang::span::line_breaks;
+///
+/// assert_eq!(line_breaks("a\nb\r\nc\rd"), 3);
+/// ```
+pub fn line_breaks(text: &str) -> usize { graphemes_iter(text).filter(|grapheme| is_line_break(grapheme)).count() }
+
+/// A point in the source text
+///
+/// `offset` is the number of bytes before the point, and `line` and `column` are its one-indexed line and grapheme column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// Creates a new position from a byte offset, line, and column
+    pub fn new(offset: usize, line: usize, column: usize) -> Self {
+        Self { offset, line, column }
+    }
+}
+
+/// A range of bytes in the source text
+///
+/// The range is half-open: `start` is the offset of the first byte, and `end` is the offset one past the final byte.
+/// An empty span (`start == end`) points between two characters, e.g., at the end of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// Creates a new span from a start and an end offset
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Gets the length of the span in bytes
+    pub fn len(&self) -> usize { self.end.saturating_sub(self.start) }
+
+    /// Checks whether the span is empty
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Checks whether two spans share at least one byte, or whether an empty span lies within the other
+    pub fn overlaps(&self, other: &Span) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return self.start <= other.end && other.start <= self.end;
+        }
+        return self.start < other.end && other.start < self.end;
+    }
+}
+
+impl From<(Position, Position)> for Span {
+    fn from((start, end): (Position, Position)) -> Self {
+        return Span::new(start.offset, end.offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_overlap() {
+        assert!(Span::new(0, 4).overlaps(&Span::new(3, 5)));
+        assert!(!Span::new(0, 3).overlaps(&Span::new(3, 5)));
+        assert!(Span::new(3, 3).overlaps(&Span::new(0, 3)));
+    }
+
+    #[test]
+    fn recognizes_line_breaks() {
+        assert!(is_line_break("\n") && is_line_break("\r\n") && is_line_break("\r"));
+        assert!(!is_line_break(" ") && !is_line_break("\t"));
+        // A `\r\n` pair is a single grapheme, and therefore a single line break
+        assert_eq!(line_breaks("\r\n\r\n"), 2);
+    }
+
+    #[test]
+    fn spans_from_positions() {
+        let span = Span::from((Position::new(4, 1, 5), Position::new(10, 2, 2)));
+        assert_eq!(span, Span::new(4, 10));
+        assert_eq!(span.len(), 6);
+    }
+}
@@ -0,0 +1,265 @@
+//! Partial evaluation of Envlang programs
+//!
+//! Given known values for some of the global bindings of a program, [`specialize`] substitutes them into the program and folds every operation that becomes constant.
+//! The resulting program can be printed with the [`printer`](crate::printer), or in a single call with [`specialize_source`], e.g. to generate per-deployment environment files from a template script.
+//!
+//! # Scoping
+//!
+//! Constants are only substituted where their binding is visible:
+//! * Implicit environments (assigned without braces) see the bindings declared before them in the environment they are assigned in.
+//! * Explicit environments and function bodies only see the bindings they inherit, and function parameters are never constant.
+//! * The members of accessions are left as they are, as they are looked up in another environment.
+//!
+//! Bindings are never removed, since they are a part of the resulting environment.
+//!
+//! # Folding
+//!
+//! Only operations whose result does not depend on undecided semantics are folded:
+//! * Arithmetic and comparisons on two numbers, following the [`numeric`] semantics. Operations that fail, e.g., by overflowing or dividing by zero, are left as they are.
+//! * `&`, `|`, `==`, and `!=` on two booleans, `!` on a boolean, and `==` and `!=` on two strings.
+//! * `-` and `+` on a number.
+
+use crate::numeric::{self, Number};
+use crate::parser::AstNode;
+use crate::symbols::{ArithmeticOperators, ComparisonOperators, LogicalOperators, Operators, OtherOperators};
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use crate::error::EnvlangError;
+
+/// Names visible in an environment, with their constant value, or `None` if the value is not known
+type Scope = BTreeMap<Rc<str>, Option<AstNode>>;
+
+/// Specializes a program for known values of its global bindings
+///
+/// The value of each global `let` binding named in `known` is replaced with the known value, and the program is then folded (see the [module documentation](self)).
+/// Known values that are literals are substituted into the rest of the program; other values only replace the value of their binding.
+/// Names in `known` without a global binding are ignored.
+///
+/// # Examples
+/// ```
+/// use envlang::parse_source;
+/// use envlang::parser::AstNode;
+/// use envlang::printer::print_ast;
+/// use envlang::specialize::specialize;
+/// use std::collections::BTreeMap;
+///
+/// let ast = parse_source("let replicas = 1;\nlet workers = replicas * 4;\n").unwrap();
+/// let known = BTreeMap::from([("replicas".into(), AstNode::Integer(3))]);
+/// assert_eq!(print_ast(&specialize(&ast, &known)), "let replicas = 3;\nlet workers = 12;\n");
+/// ```
+pub fn specialize(ast: &AstNode, known: &BTreeMap<Rc<str>, AstNode>) -> AstNode {
+    match ast {
+        AstNode::Environment { name, bindings, parent } => {
+            let mut scope = Scope::new();
+            let bindings = bindings.iter()
+                .map(|binding| match binding.as_ref() {
+                    AstNode::Let { name, value: Some(_), inherit, span } if known.contains_key(name) => {
+                        let value = known[name].clone();
+                        scope.insert(name.clone(), constant(&value));
+                        Rc::new(AstNode::Let { name: name.clone(), value: Some(Rc::new(value)), inherit: inherit.clone(), span: *span })
+                    },
+                    _ => Rc::new(statement(binding, &mut scope)),
+                })
+                .collect();
+            return AstNode::Environment { name: name.clone(), bindings, parent: parent.clone() };
+        },
+        node => return statement(node, &mut Scope::new()),
+    }
+}
+
+/// Parses Envlang source code, specializes it for known values of its global bindings, and prints the result
+///
+/// # Errors
+/// Any errors from parsing the source code (see [`parse_source`](crate::parse_source)).
+#[cfg(feature = "std")]
+pub fn specialize_source(source: &str, known: &BTreeMap<Rc<str>, AstNode>) -> Result<alloc::string::String, EnvlangError> {
+    let ast = crate::parse_source(source)?;
+    return Ok(crate::printer::print_ast(&specialize(&ast, known)));
+}
+
+/// Gets the constant value of a node, if it is a literal
+fn constant(node: &AstNode) -> Option<AstNode> {
+    match node {
+        AstNode::Integer(_) | AstNode::Float(_) | AstNode::Boolean(_) | AstNode::String(_) => Some(node.clone()),
+        _ => None,
+    }
+}
+
+/// Specializes an element of an environment, declaring the bindings it makes in the scope
+fn statement(node: &AstNode, scope: &mut Scope) -> AstNode {
+    match node {
+        AstNode::Let { name, value, inherit, span } => {
+            let value = value.as_ref().map(|value| match value.as_ref() {
+                AstNode::Environment { .. } => environment_in(value, &mut inherited(inherit.as_deref(), scope)),
+                AstNode::Function { .. } => statement(value, &mut inherited(inherit.as_deref(), scope)),
+                _ => expression(value, scope),
+            });
+            scope.insert(name.clone(), value.as_ref().and_then(constant));
+            return AstNode::Let { name: name.clone(), value: value.map(Rc::new), inherit: inherit.clone(), span: *span };
+        },
+        // Explicit environments without an inherit clause see none of the enclosing bindings
+        AstNode::Environment { .. } => return environment_in(node, &mut Scope::new()),
+        AstNode::Function { params, body, r#return, span } => {
+            for param in params.get_params().unwrap_or_default() {
+                if let AstNode::Identifier(param) = param.as_ref() {
+                    scope.insert(param.clone(), None);
+                }
+            }
+            // The return statement sees the bindings of the function body
+            let body = Rc::new(environment_in(body, scope));
+            let r#return = Rc::new(environment_in(r#return, scope));
+            return AstNode::Function { params: params.clone(), body, r#return, span: *span };
+        },
+        _ => return expression(node, scope),
+    }
+}
+
+/// Specializes the bindings of an environment in an existing scope
+fn environment_in(node: &AstNode, scope: &mut Scope) -> AstNode {
+    match node {
+        AstNode::Environment { name, bindings, parent } => {
+            let bindings = bindings.iter().map(|binding| Rc::new(statement(binding, scope))).collect();
+            return AstNode::Environment { name: name.clone(), bindings, parent: parent.clone() };
+        },
+        _ => return statement(node, scope),
+    }
+}
+
+/// Gets the scope inherited from an enclosing scope through an inherit clause
+fn inherited(inherit: Option<&AstNode>, scope: &Scope) -> Scope {
+    match inherit {
+        Some(AstNode::Inherit { names: None }) => return scope.clone(),
+        Some(AstNode::Inherit { names: Some(names) }) => {
+            return names.iter()
+                .map(|name| (name.clone(), scope.get(name).cloned().flatten()))
+                .collect();
+        },
+        _ => return Scope::new(),
+    }
+}
+
+/// Specializes an expression, substituting constants and folding constant operations
+fn expression(node: &AstNode, scope: &Scope) -> AstNode {
+    match node {
+        AstNode::Identifier(name) => match scope.get(name) {
+            Some(Some(value)) => return value.clone(),
+            _ => return node.clone(),
+        },
+        AstNode::BinaryOp { operator: Operators::Other(OtherOperators::ACCESSOR), .. } => return node.clone(),
+        AstNode::BinaryOp { left, operator, right } => {
+            let left = expression(left, scope);
+            let right = expression(right, scope);
+            return fold_binary(&left, operator, &right).unwrap_or(AstNode::BinaryOp {
+                left: Rc::new(left),
+                operator: operator.clone(),
+                right: Rc::new(right),
+            });
+        },
+        AstNode::UnaryOp { op, operand } => {
+            let operand = expression(operand, scope);
+            return fold_unary(op, &operand).unwrap_or(AstNode::UnaryOp { op: op.clone(), operand: Rc::new(operand) });
+        },
+        AstNode::FunctionCall { id, args, span } => {
+            let args = args.get_params().unwrap_or_default().iter()
+                .map(|arg| Rc::new(expression(arg, scope)))
+                .collect::<Vec<Rc<AstNode>>>();
+            return AstNode::FunctionCall { id: id.clone(), args: Rc::new(AstNode::FunctionArgs(args)), span: *span };
+        },
+        _ => return node.clone(),
+    }
+}
+
+/// Folds a binary operation on two literals, or returns `None` if the result is not certain
+fn fold_binary(left: &AstNode, operator: &Operators, right: &AstNode) -> Option<AstNode> {
+    if let (Some(a), Some(b)) = (Number::from_node(left), Number::from_node(right)) {
+        match operator {
+            Operators::Arithmetic(op) => return numeric::arithmetic(op, a, b).ok().map(Number::into_node),
+            Operators::Comparison(op) => return Some(AstNode::Boolean(numeric::compare(op, a, b))),
+            _ => return None,
+        }
+    }
+    match (left, right) {
+        (AstNode::Boolean(a), AstNode::Boolean(b)) => match operator {
+            Operators::Logical(LogicalOperators::AND) => return Some(AstNode::Boolean(*a && *b)),
+            Operators::Logical(LogicalOperators::OR) => return Some(AstNode::Boolean(*a || *b)),
+            Operators::Comparison(ComparisonOperators::EQ) => return Some(AstNode::Boolean(a == b)),
+            Operators::Comparison(ComparisonOperators::NEQ) => return Some(AstNode::Boolean(a != b)),
+            _ => return None,
+        },
+        (AstNode::String(a), AstNode::String(b)) => match operator {
+            Operators::Comparison(ComparisonOperators::EQ) => return Some(AstNode::Boolean(a == b)),
+            Operators::Comparison(ComparisonOperators::NEQ) => return Some(AstNode::Boolean(a != b)),
+            _ => return None,
+        },
+        _ => return None,
+    }
+}
+
+/// Folds a unary operation on a literal, or returns `None` if the result is not certain
+fn fold_unary(op: &Operators, operand: &AstNode) -> Option<AstNode> {
+    match (op, operand) {
+        (Operators::Logical(LogicalOperators::NOT), AstNode::Boolean(b)) => return Some(AstNode::Boolean(!b)),
+        (Operators::Arithmetic(ArithmeticOperators::SUBTRACT), AstNode::Integer(_) | AstNode::Float(_)) =>
+            return Number::from_node(operand).and_then(|num| numeric::negate(num).ok()).map(Number::into_node),
+        (Operators::Arithmetic(ArithmeticOperators::ADD), AstNode::Integer(_) | AstNode::Float(_)) => return Some(operand.clone()),
+        _ => return None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::printer::print_ast;
+
+    fn specialized(source: &str, known: &[(&str, AstNode)]) -> alloc::string::String {
+        let tokens = Lexer::from_text(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let known = known.iter().map(|(name, value)| (Rc::from(*name), value.clone())).collect();
+        return print_ast(&specialize(&ast, &known));
+    }
+
+    #[test]
+    fn substitutes_known_values_and_folds() {
+        let source = "let port = 80;\nlet tls = port == 443;\nlet open = !tls | false;\nlet next = port + 1;\n";
+        assert_eq!(
+            specialized(source, &[("port", AstNode::Integer(443))]),
+            "let port = 443;\nlet tls = true;\nlet open = false;\nlet next = 444;\n",
+        );
+        // Without known values, constants are still propagated
+        assert_eq!(
+            specialized(source, &[]),
+            "let port = 80;\nlet tls = false;\nlet open = true;\nlet next = 81;\n",
+        );
+    }
+
+    #[test]
+    fn honors_scopes() {
+        let source = "let x = 2;\nlet k = g[x];\nlet a = { let y = x; };\nlet b inherit (x) = { let y = x * x; };\nlet c = b.x;\nlet fun f[x] = { return x + 1; }\n";
+        assert_eq!(
+            specialized(source, &[]),
+            "let x = 2;\nlet k = g[2];\nlet a = {\n    let y = x;\n};\nlet b inherit (x) = {\n    let y = 4;\n};\nlet c = b.x;\nlet fun f[x] = {\n    return x + 1;\n}\n",
+        );
+    }
+
+    #[test]
+    fn leaves_uncertain_operations() {
+        let source = "let a = 1 / 0;\nlet b = 8 ^ 64;\nlet c = -2 ^ 0.5;\nlet d = \"a\" + \"b\";\nlet e = n * 2;\nlet f = g[e, a];\n";
+        assert_eq!(
+            specialized(source, &[]),
+            "let a = 1 / 0;\nlet b = 8 ^ 64;\nlet c = -2 ^ 0.5;\nlet d = \"a\" + \"b\";\nlet e = n * 2;\nlet f = g[e, a];\n",
+        );
+    }
+
+    #[test]
+    fn folds_with_numeric_semantics() {
+        let source = "let a = -7 / 2;\nlet b = -7 % 2;\nlet c = 2 ^ -1;\nlet d = 1 + 1.5;\nlet e = 1 == 1.0;\n";
+        assert_eq!(
+            specialized(source, &[]),
+            "let a = -4;\nlet b = 1;\nlet c = 0.5;\nlet d = 2.5;\nlet e = true;\n",
+        );
+    }
+}
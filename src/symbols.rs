@@ -4,6 +4,9 @@
 //! 
 //! This list will grow significantly until the release of version 1.0.0.
 
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
 /// Types of operators
 /// 
 /// The enum derives the traits `Debug`, `Clone`, `PartialEq`, and `Eq`, and implements [`ToString`](Operators::to_string).
@@ -177,4 +180,120 @@ impl ToString for Booleans {
             Booleans::FALSE => "false".to_string()
         }
     }
-}
\ No newline at end of file
+}
+
+/// Unit suffixes of number literals, with the factor a number is multiplied by
+/// 
+/// Durations are measured in milliseconds and sizes in bytes, so that `10s` stands for `10000` and `512kb` for `512000`.
+/// Decimal size suffixes are powers of 1000, and binary size suffixes (`kib`, `mib`, ...) powers of 1024.
+pub const UNIT_SUFFIXES: [(&str, u64); 14] = [
+    ("ms", 1),
+    ("s", 1_000),
+    ("m", 60_000),
+    ("h", 3_600_000),
+    ("d", 86_400_000),
+    ("b", 1),
+    ("kb", 1_000),
+    ("mb", 1_000_000),
+    ("gb", 1_000_000_000),
+    ("tb", 1_000_000_000_000),
+    ("kib", 1 << 10),
+    ("mib", 1 << 20),
+    ("gib", 1 << 30),
+    ("tib", 1 << 40),
+];
+
+/// Gets the factor of a unit suffix of [`UNIT_SUFFIXES`], or `None` if the suffix is not a unit
+pub fn unit_factor(suffix: &str) -> Option<u64> {
+    return UNIT_SUFFIXES.iter()
+        .find(|(known, _)| *known == suffix)
+        .map(|(_, factor)| *factor);
+}
+
+/// Alternative spellings of operators, which can be enabled in an [`OperatorAliases`] table
+pub const OPERATOR_ALIASES: [(&str, Operators); 4] = [
+    ("=!", Operators::Comparison(ComparisonOperators::NEQ)),
+    ("and", Operators::Logical(LogicalOperators::AND)),
+    ("or", Operators::Logical(LogicalOperators::OR)),
+    ("not", Operators::Logical(LogicalOperators::NOT)),
+];
+
+/// A table of the enabled operator aliases
+/// 
+/// By default, the lexer only recognises the standard spelling of each operator. Once an alias from [`OPERATOR_ALIASES`] is enabled, the lexer reads it as the operator it stands for.
+/// 
+/// Note, that enabling a word alias (e.g. `and`) reserves the word, so that it can no longer be used as an identifier.
+/// Similarly, enabling `=!` makes `x =!y` read as `x != y` instead of an assignment of `!y`.
+/// 
+/// The struct derives the traits `Debug`, `Clone`, `Default`, `PartialEq`, and `Eq`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperatorAliases {
+    enabled: Vec<&'static str>,
+}
+
+impl OperatorAliases {
+    /// Creates a table without any enabled aliases
+    pub fn new() -> Self { Self::default() }
+
+    /// Creates a table with every alias of [`OPERATOR_ALIASES`] enabled
+    pub fn all() -> Self {
+        return Self { enabled: OPERATOR_ALIASES.iter().map(|(spelling, _)| *spelling).collect() };
+    }
+
+    /// Enables an alias by its spelling
+    /// 
+    /// Returns `false` if the spelling is not one of [`OPERATOR_ALIASES`].
+    pub fn enable(&mut self, spelling: &str) -> bool {
+        match OPERATOR_ALIASES.iter().find(|(known, _)| *known == spelling) {
+            Some((known, _)) => {
+                if !self.enabled.contains(known) {
+                    self.enabled.push(known);
+                }
+                return true;
+            },
+            None => return false,
+        }
+    }
+
+    /// Gets the spellings of the enabled aliases, in the order they were enabled
+    pub fn spellings(&self) -> &[&'static str] { &self.enabled }
+
+    /// Checks whether no aliases are enabled
+    pub fn is_empty(&self) -> bool { self.enabled.is_empty() }
+
+    /// Gets the operator an enabled alias stands for
+    pub fn get(&self, spelling: &str) -> Option<Operators> {
+        if !self.enabled.contains(&spelling) {
+            return None;
+        }
+        return OPERATOR_ALIASES.iter()
+            .find(|(known, _)| *known == spelling)
+            .map(|(_, op)| op.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases_must_be_enabled() {
+        let mut aliases = OperatorAliases::new();
+        assert_eq!(aliases.get("and"), None);
+        assert!(aliases.enable("and"));
+        assert!(aliases.enable("and"));
+        assert!(!aliases.enable("xor"));
+        assert_eq!(aliases.spellings(), &["and"]);
+        assert_eq!(aliases.get("and"), Some(Operators::Logical(LogicalOperators::AND)));
+        assert_eq!(aliases.get("=!"), None);
+        assert_eq!(OperatorAliases::all().get("=!"), Some(Operators::Comparison(ComparisonOperators::NEQ)));
+    }
+
+    #[test]
+    fn unit_suffixes_have_factors() {
+        assert_eq!(unit_factor("h"), Some(3_600_000));
+        assert_eq!(unit_factor("gib"), Some(1_073_741_824));
+        assert_eq!(unit_factor("sec"), None);
+        assert!(UNIT_SUFFIXES.iter().all(|(suffix, _)| suffix.chars().all(|c| c.is_ascii_lowercase())));
+    }
+}
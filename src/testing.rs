@@ -0,0 +1,328 @@
+//! Generators for property testing and entry points for fuzzing
+//!
+//! This module is enabled by the `testing` feature. It produces random but valid Envlang programs, so that properties of the front end can be checked on many inputs:
+//! * [`arbitrary_ast`] generates the [`AstNode`] of a program.
+//! * [`arbitrary_source`] renders a generated program with the [`printer`](crate::printer).
+//! * [`arbitrary_tokens`] lexes a generated program into a token stream ending in [`Token::EOF`].
+//!
+//! The generators are driven by the small deterministic [`Rng`], so that a failing case can be reproduced from its seed without any further dependencies.
+//! Adapting them to a property testing framework only takes seeding an [`Rng`] from the framework's own randomness.
+//!
+//! Generated programs stay within the constructs that the parser currently reads back unchanged, so that printing a generated program, parsing it, and printing it again gives the same source.
+//!
+//! [`fuzz_tokenize`] and [`fuzz_parse`] run the front end on arbitrary bytes and return a [`FuzzOutcome`] instead of panicking.
+//! They back the `tokenize` and `parse` targets in the `fuzz` directory of the repository, which are run with, e.g., `cargo fuzz run parse`.
+//!
+//! # Examples
+//! ```
+//! use envlang::printer::print_ast;
+//! use envlang::testing::{arbitrary_source, Rng};
+//!
+//! let mut rng = Rng::new(42);
+//! for _ in 0..100 {
+//!     let source = arbitrary_source(&mut rng);
+//!     let reparsed = envlang::parse_source(&source).unwrap();
+//!     assert_eq!(print_ast(&reparsed), source);
+//! }
+//! ```
+
+use crate::lexer::{Lexer, LexerError, Token};
+use crate::parser::{AstNode, Parser, ParserError};
+use crate::printer::print_ast;
+use crate::symbols::{ArithmeticOperators, ComparisonOperators, LogicalOperators, Operators, OtherOperators};
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::vec;
+
+/// Names used for generated identifiers
+///
+/// The pool is small on purpose, so that generated programs refer to their own bindings and parameters often.
+const NAMES: [&str; 8] = ["a", "b", "c", "foo", "bar", "baz", "value", "env"];
+
+/// Maximum nesting depth of generated environments
+const MAX_DEPTH: usize = 3;
+
+/// A deterministic xorshift pseudo-random number generator
+///
+/// The generator is not suitable for anything but testing.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a new generator from a seed
+    ///
+    /// A seed of zero is replaced by a fixed non-zero seed, as xorshift generators cannot leave the zero state.
+    pub fn new(seed: u64) -> Self {
+        return Rng(if seed == 0 { 0x9e37_79b9_7f4a_7c15 } else { seed });
+    }
+
+    /// Gets the next pseudo-random number
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        return self.0;
+    }
+
+    /// Gets a pseudo-random number below a bound
+    ///
+    /// A bound of zero always gives zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        return (self.next_u64() % bound as u64) as usize;
+    }
+
+    /// Gets a pseudo-random boolean that is `true` with a probability of one in `n`
+    pub fn one_in(&mut self, n: usize) -> bool { self.below(n) == 0 }
+
+    /// Chooses a pseudo-random element of a non-empty slice
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T { &items[self.below(items.len())] }
+}
+
+/// Outcome of running a phase of the front end on arbitrary bytes
+#[derive(Debug)]
+pub enum FuzzOutcome<T> {
+    /// The input was not valid UTF-8
+    InvalidUtf8,
+    /// The lexer rejected the input
+    Lexer(LexerError),
+    /// The parser rejected the input
+    Parser(ParserError),
+    /// Every phase accepted the input
+    Accepted(T),
+}
+
+impl<T> FuzzOutcome<T> {
+    /// Checks whether every phase accepted the input
+    pub fn is_accepted(&self) -> bool { matches!(self, FuzzOutcome::Accepted(_)) }
+}
+
+/// Tokenizes arbitrary bytes
+///
+/// # Examples
+/// ```
+/// use envlang::testing::{fuzz_tokenize, FuzzOutcome};
+///
+/// assert!(fuzz_tokenize(b"let x = 5;").is_accepted());
+/// assert!(matches!(fuzz_tokenize(b"let x = \"abc"), FuzzOutcome::Lexer(_)));
+/// assert!(matches!(fuzz_tokenize(&[0xff, 0xfe]), FuzzOutcome::InvalidUtf8));
+/// ```
+pub fn fuzz_tokenize(data: &[u8]) -> FuzzOutcome<Vec<Token>> {
+    let Ok(source) = core::str::from_utf8(data) else {
+        return FuzzOutcome::InvalidUtf8;
+    };
+    match Lexer::from_text(source).tokenize() {
+        Ok(tokens) => return FuzzOutcome::Accepted(tokens),
+        Err(err) => return FuzzOutcome::Lexer(err),
+    }
+}
+
+/// Tokenizes and parses arbitrary bytes
+///
+/// # Examples
+/// ```
+/// use envlang::testing::{fuzz_parse, FuzzOutcome};
+///
+/// assert!(fuzz_parse(b"let x = 5;").is_accepted());
+/// assert!(matches!(fuzz_parse(b"let = 5;"), FuzzOutcome::Parser(_)));
+/// ```
+pub fn fuzz_parse(data: &[u8]) -> FuzzOutcome<AstNode> {
+    let tokens = match fuzz_tokenize(data) {
+        FuzzOutcome::Accepted(tokens) => tokens,
+        FuzzOutcome::InvalidUtf8 => return FuzzOutcome::InvalidUtf8,
+        FuzzOutcome::Lexer(err) => return FuzzOutcome::Lexer(err),
+        FuzzOutcome::Parser(err) => return FuzzOutcome::Parser(err),
+    };
+    match Parser::new(tokens).parse() {
+        Ok(ast) => return FuzzOutcome::Accepted(ast),
+        Err(err) => return FuzzOutcome::Parser(err),
+    }
+}
+
+/// Generates the global environment of a random program
+pub fn arbitrary_ast(rng: &mut Rng) -> AstNode {
+    let count = 1 + rng.below(5);
+    let bindings = (0..count).map(|_| Rc::new(arbitrary_statement(rng, 0))).collect();
+    return AstNode::Environment { name: None, bindings, parent: None };
+}
+
+/// Generates the source code of a random program
+pub fn arbitrary_source(rng: &mut Rng) -> String {
+    return print_ast(&arbitrary_ast(rng));
+}
+
+/// Generates the token stream of a random program
+pub fn arbitrary_tokens(rng: &mut Rng) -> Vec<Token> {
+    let source = arbitrary_source(rng);
+    // Generated programs only contain valid tokens, so the lexer cannot fail on them
+    return Lexer::from_text(&source).tokenize().unwrap_or_else(|_| vec![Token::EOF]);
+}
+
+/// Generates a random identifier
+fn arbitrary_name(rng: &mut Rng) -> Rc<str> { Rc::from(*rng.choose(&NAMES)) }
+
+/// Generates a random assignment or, in the global environment, function declaration
+///
+/// Nested function declarations are not read back unchanged when further statements follow them, so they are left out.
+fn arbitrary_statement(rng: &mut Rng, depth: usize) -> AstNode {
+    let name = arbitrary_name(rng);
+    let inherit = if depth > 0 && rng.one_in(4) { Some(Rc::new(arbitrary_inherit(rng))) } else { None };
+    let value = if depth == 0 && rng.one_in(4) {
+        arbitrary_function(rng, depth)
+    } else if depth < MAX_DEPTH && rng.one_in(4) {
+        arbitrary_environment(rng, depth + 1)
+    } else {
+        arbitrary_expression(rng)
+    };
+    return AstNode::Let { name, value: Some(Rc::new(value)), inherit, span: None };
+}
+
+/// Generates a random named environment with at least one binding
+fn arbitrary_environment(rng: &mut Rng, depth: usize) -> AstNode {
+    let count = 1 + rng.below(3);
+    let bindings = (0..count).map(|_| Rc::new(arbitrary_statement(rng, depth))).collect();
+    return AstNode::Environment { name: None, bindings, parent: None };
+}
+
+/// Generates a random function with a body and a returned identifier
+fn arbitrary_function(rng: &mut Rng, depth: usize) -> AstNode {
+    let params = (0..rng.below(3)).map(|_| Rc::new(AstNode::Identifier(arbitrary_name(rng)))).collect();
+    let body_count = rng.below(3);
+    let body = (0..body_count).map(|_| Rc::new(arbitrary_statement(rng, depth + 1))).collect();
+    let returned = AstNode::Environment {
+        name: None,
+        bindings: vec![Rc::new(AstNode::Identifier(arbitrary_name(rng)))],
+        parent: None,
+    };
+    return AstNode::Function {
+        params: Rc::new(AstNode::FunctionArgs(params)),
+        body: Rc::new(AstNode::Environment { name: None, bindings: body, parent: None }),
+        r#return: Rc::new(returned),
+        span: None,
+    };
+}
+
+/// Generates a random inheritance clause
+fn arbitrary_inherit(rng: &mut Rng) -> AstNode {
+    if rng.one_in(3) {
+        return AstNode::Inherit { names: None };
+    }
+    let names = (0..1 + rng.below(3)).map(|_| arbitrary_name(rng)).collect();
+    return AstNode::Inherit { names: Some(names) };
+}
+
+/// Generates a random single-line expression
+fn arbitrary_expression(rng: &mut Rng) -> AstNode {
+    match rng.below(9) {
+        0 => return AstNode::Integer(rng.below(1000) as isize),
+        1 => return AstNode::Float(rng.below(1000) as f64 / 4.0),
+        2 => return AstNode::Boolean(rng.one_in(2)),
+        3 => {
+            let words: Vec<&str> = (0..1 + rng.below(3)).map(|_| *rng.choose(&NAMES)).collect();
+            return AstNode::String(Rc::from(words.join(" ")));
+        },
+        4 => return AstNode::Identifier(arbitrary_name(rng)),
+        5 => {
+            // Accessions cannot be chained yet
+            return AstNode::BinaryOp {
+                left: Rc::new(AstNode::Identifier(arbitrary_name(rng))),
+                operator: Operators::Other(OtherOperators::ACCESSOR),
+                right: Rc::new(AstNode::Identifier(arbitrary_name(rng))),
+            };
+        },
+        6 => return arbitrary_operation(rng, 2),
+        7 => {
+            let op = if rng.one_in(2) {
+                Operators::Logical(LogicalOperators::NOT)
+            } else {
+                Operators::Arithmetic(ArithmeticOperators::SUBTRACT)
+            };
+            return AstNode::UnaryOp { op, operand: Rc::new(AstNode::Identifier(arbitrary_name(rng))) };
+        },
+        _ => {
+            let args = (0..rng.below(3)).map(|_| Rc::new(AstNode::Identifier(arbitrary_name(rng)))).collect();
+            return AstNode::FunctionCall {
+                id: Rc::new(AstNode::Identifier(arbitrary_name(rng))),
+                args: Rc::new(AstNode::FunctionArgs(args)),
+                span: None,
+            };
+        },
+    }
+}
+
+/// Generates a random binary operation, whose operands nest up to `depth` further operations
+fn arbitrary_operation(rng: &mut Rng, depth: usize) -> AstNode {
+    let op = rng.choose(&[
+        Operators::Arithmetic(ArithmeticOperators::ADD),
+        Operators::Arithmetic(ArithmeticOperators::SUBTRACT),
+        Operators::Arithmetic(ArithmeticOperators::MULTIPLY),
+        Operators::Arithmetic(ArithmeticOperators::EXPONENTIATION),
+        Operators::Comparison(ComparisonOperators::LEQ),
+        Operators::Logical(LogicalOperators::AND),
+    ]).clone();
+    let mut operand = || {
+        if depth > 0 && rng.one_in(3) {
+            return arbitrary_operation(rng, depth - 1);
+        }
+        return AstNode::Identifier(arbitrary_name(rng));
+    };
+    let left = operand();
+    let right = operand();
+    return AstNode::BinaryOp { left: Rc::new(left), operator: op, right: Rc::new(right) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_programs_round_trip() {
+        let mut rng = Rng::new(0x5eed);
+        for _ in 0..500 {
+            let source = arbitrary_source(&mut rng);
+            let tokens = Lexer::from_text(&source).tokenize()
+                .unwrap_or_else(|err| panic!("{}\n{}", err, source));
+            let ast = Parser::new(tokens).parse()
+                .unwrap_or_else(|err| panic!("{}\n{}", err, source));
+            assert_eq!(print_ast(&ast), source);
+        }
+    }
+
+    #[test]
+    fn generated_tokens_end_in_eof() {
+        let mut rng = Rng::new(7);
+        for _ in 0..50 {
+            assert_eq!(arbitrary_tokens(&mut rng).last(), Some(&Token::EOF));
+        }
+    }
+
+    #[test]
+    fn generated_tokens_render_to_their_source() {
+        let mut rng = Rng::new(0x70c3);
+        for _ in 0..200 {
+            let source = arbitrary_source(&mut rng.clone());
+            assert_eq!(crate::lexer::render_tokens(&arbitrary_tokens(&mut rng)), source);
+        }
+    }
+
+    #[test]
+    fn generators_are_deterministic() {
+        assert_eq!(arbitrary_source(&mut Rng::new(3)), arbitrary_source(&mut Rng::new(3)));
+    }
+
+    #[test]
+    fn fuzz_entry_points_survive_arbitrary_bytes() {
+        let mut rng = Rng::new(0xf022);
+        for _ in 0..2000 {
+            let data: Vec<u8> = (0..rng.below(48)).map(|_| rng.below(256) as u8).collect();
+            let _ = fuzz_parse(&data);
+        }
+        let mut rng = Rng::new(0xf022);
+        for _ in 0..200 {
+            assert!(fuzz_parse(arbitrary_source(&mut rng).as_bytes()).is_accepted());
+        }
+    }
+}
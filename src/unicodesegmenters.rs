@@ -1,12 +1,15 @@
-//! Wrappers for segmenting input strings into Unicode graphemes
+//! Wrappers for segmenting input strings into Unicode graphemes, words, and sentences
 //! 
 //! This module contains functions for converting `String` data into Unicode-segmented `String` vectors.
+//! The rest of the crate segments text through these functions only.
 //! 
 //! It uses the external crate [`unicode_segmentation`].
 //! 
 //! [`unicode_segmentation`]: https://crates.io/crates/unicode-segmentation
 
 use unicode_segmentation::UnicodeSegmentation;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 /// Segment a string slice into Unicode graphemes
 /// 
@@ -26,6 +29,59 @@ pub fn segment_graphemes(input: &str) -> Vec<String> {
         .collect()
 }
 
+/// Iterate lazily over the Unicode graphemes of a string slice
+///
+/// Unlike [`segment_graphemes`], the graphemes are borrowed from the input, so no strings are allocated.
+/// 
+/// # Examples
+/// ```
+/// use envlang::unicodesegmenters::graphemes_iter;
+/// 
+/// let input = "Hello, 世界!";
+/// assert_eq!(graphemes_iter(input).count(), 10);
+/// assert_eq!(graphemes_iter(input).nth(7), Some("世"));
+/// ```
+pub fn graphemes_iter(input: &str) -> impl Iterator<Item = &str> {
+    UnicodeSegmentation::graphemes(input, true)
+}
+
+/// Segment a string slice into Unicode words
+///
+/// Words are split at the word boundaries of [UAX #29](https://www.unicode.org/reports/tr29/), and only segments containing alphanumeric characters are kept, so whitespace and punctuation are dropped.
+/// 
+/// # Examples
+/// ```
+/// use envlang::unicodesegmenters::segment_words;
+/// 
+/// let input = "The quick (\"brown\") fox can't jump 32.3 feet, right?";
+/// let segments = segment_words(input);
+/// assert_eq!(segments, ["The", "quick", "brown", "fox", "can't", "jump", "32.3", "feet", "right"]);
+/// ```
+pub fn segment_words(input: &str) -> Vec<String> {
+    UnicodeSegmentation::unicode_words(input)
+        .map(String::from)
+        .collect()
+}
+
+/// Segment a string slice into Unicode sentences
+///
+/// Sentences are split at the sentence boundaries of [UAX #29](https://www.unicode.org/reports/tr29/), and segments without any alphanumeric characters are dropped.
+/// The sentences keep their trailing punctuation and whitespace.
+/// 
+/// # Examples
+/// ```
+/// use envlang::unicodesegmenters::segment_sentences;
+/// 
+/// let input = "Mr. Fox jumped. The dog was too lazy.";
+/// let segments = segment_sentences(input);
+/// assert_eq!(segments, ["Mr. ", "Fox jumped. ", "The dog was too lazy."]);
+/// ```
+pub fn segment_sentences(input: &str) -> Vec<String> {
+    UnicodeSegmentation::unicode_sentences(input)
+        .map(String::from)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,4 +129,24 @@ mod tests {
         let segments = segment_graphemes(input);
         assert_eq!(segments.join(""), input);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn iterates_like_segment_graphemes() {
+        let input = "line1\r\ne\u{301} 😺";
+        assert_eq!(graphemes_iter(input).collect::<Vec<&str>>(), segment_graphemes(input));
+    }
+
+    #[test]
+    fn segments_words_of_identifiers() {
+        assert_eq!(segment_words("max_depth"), ["max_depth"]);
+        assert_eq!(segment_words("größe wert"), ["größe", "wert"]);
+        assert!(segment_words(" ,; ").is_empty());
+    }
+
+    #[test]
+    fn segments_sentences_without_losing_text() {
+        let input = "First sentence. Second one? Third!";
+        assert_eq!(segment_sentences(input).join(""), input);
+        assert_eq!(segment_sentences(input).len(), 3);
+    }
+}
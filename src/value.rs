@@ -0,0 +1,585 @@
+//! Runtime values of Envlang
+//!
+//! The [`evaluator`](crate::evaluator) computes with [`Value`]s, which are separate from the [`AstNode`]s of the program text.
+//! Values are converted back into AST nodes with [`Value::to_node`], e.g. to print the result of a program with the [`printer`](crate::printer).
+//!
+//! # Operations
+//!
+//! Values are never converted implicitly from one type to another, with one exception: numbers follow the [`numeric`] semantics, so integers are promoted to floats in operations with a float.
+//! * Integers and floats support arithmetic and comparisons, and `-` and `+` as unary operators.
+//! * Booleans support `&`, `|`, `!`, `==`, and `!=`.
+//! * Strings support concatenation with `+`, `==`, and `!=`.
+//!
+//! Any other combination of operator and operands is an error, e.g., `"a" * 2` or `true + 1`.
+//!
+//! # Conversions
+//!
+//! Values convert from and into Rust types with `From` and `TryFrom`, so that embedders can pass data to programs and read their results:
+//! * Integers convert from and into `isize`, and into `i64`.
+//! * Floats convert from and into `f64`. Integers also convert into `f64`, following the promotion of the [`numeric`] semantics.
+//! * Booleans convert from and into `bool`, and strings from and into `String` and `Rc<str>`, and from `&str`.
+//! * Environments convert from and into [`Bindings`], from maps of names to anything that converts into a value, and into maps of names to values (`BTreeMap`, and `HashMap` with the `std` feature).
+//!
+//! Converting a value into a type it does not hold fails with [`EvalError::UnexpectedType`].
+//! [`Value::get`] looks up a binding of an environment and converts it in one step, e.g., to read the results of a program.
+//! Envlang has no lists, so there are no conversions for `Vec`.
+//!
+//! ```
+//! use envlang::value::Value;
+//! use std::collections::BTreeMap;
+//!
+//! let env = Value::from(BTreeMap::from([("port", 8080), ("workers", 4)]));
+//! let bindings: BTreeMap<String, Value> = env.try_into().unwrap();
+//! assert_eq!(isize::try_from(bindings["port"].clone()), Ok(8080));
+//! assert!(bool::try_from(bindings["workers"].clone()).is_err());
+//! ```
+
+use crate::environment::Environment;
+use crate::evaluator::EvalError;
+use crate::numeric::{self, Number};
+use crate::parser::AstNode;
+use crate::span::Span;
+use crate::symbols::{ArithmeticOperators, ComparisonOperators, LogicalOperators, Operators};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// Maximum number of members listed in an [`EvalError::MissingMember`]
+pub const LISTED_MEMBERS: usize = 8;
+
+/// A value computed by the evaluator
+///
+/// The enum derives the traits `Debug`, `Clone`, and `PartialEq`.
+///
+/// # Examples
+/// ```
+/// use envlang::symbols::{ArithmeticOperators, Operators};
+/// use envlang::value::Value;
+///
+/// let sum = Value::Integer(1).binary(&Operators::Arithmetic(ArithmeticOperators::ADD), &Value::Float(0.5));
+/// assert_eq!(sum, Ok(Value::Float(1.5)));
+/// assert!(Value::String("a".into()).binary(&Operators::Arithmetic(ArithmeticOperators::MULTIPLY), &Value::Integer(2)).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(isize),
+    Float(f64),
+    Boolean(bool),
+    String(Rc<str>),
+    /// An environment, with its bindings in the order of the source
+    ///
+    /// The bindings are shared, so that copying an environment does not copy its bindings.
+    Environment {
+        name: Option<Rc<str>>,
+        bindings: Rc<Bindings>,
+    },
+    Function(Rc<Closure>),
+}
+
+/// The bindings of an environment value, in the order of the source
+///
+/// Assigning a name twice replaces the value of the first binding. Bindings are looked up by name through an index.
+///
+/// The struct derives the traits `Debug`, `Clone`, `Default`, and `PartialEq`, and implements `FromIterator` for named values.
+///
+/// # Examples
+/// ```
+/// use envlang::value::{Bindings, Value};
+///
+/// let mut bindings = Bindings::new();
+/// bindings.insert("a".into(), Value::Integer(1));
+/// bindings.insert("b".into(), Value::Integer(2));
+/// bindings.insert("a".into(), Value::Integer(3));
+/// assert_eq!(bindings.get("a"), Some(&Value::Integer(3)));
+/// assert_eq!(bindings.iter().map(|(name, _)| name.as_ref()).collect::<Vec<_>>(), vec!["a", "b"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bindings {
+    entries: Vec<(Rc<str>, Value)>,
+    index: BTreeMap<Rc<str>, usize>,
+}
+
+impl Bindings {
+    /// Creates an empty set of bindings
+    pub fn new() -> Self { Self::default() }
+
+    /// Binds a name to a value, replacing the value of an earlier binding of the name in place
+    pub fn insert(&mut self, name: Rc<str>, value: Value) {
+        match self.index.get(&name) {
+            Some(&pos) => self.entries[pos].1 = value,
+            None => {
+                self.index.insert(name.clone(), self.entries.len());
+                self.entries.push((name, value));
+            },
+        }
+    }
+
+    /// Gets the value bound to a name
+    pub fn get(&self, name: &str) -> Option<&Value> { self.index.get(name).map(|&pos| &self.entries[pos].1) }
+
+    /// Iterates over the bindings in the order of the source
+    pub fn iter(&self) -> impl Iterator<Item = (&Rc<str>, &Value)> { self.entries.iter().map(|(name, value)| (name, value)) }
+
+    /// Gets the number of bindings
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    /// Checks whether there are no bindings
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+}
+
+impl FromIterator<(Rc<str>, Value)> for Bindings {
+    fn from_iter<I: IntoIterator<Item = (Rc<str>, Value)>>(iter: I) -> Self {
+        let mut bindings = Bindings::new();
+        for (name, value) in iter {
+            bindings.insert(name, value);
+        }
+        return bindings;
+    }
+}
+
+/// A function value, together with the bindings it inherits at its definition
+///
+/// The struct derives the traits `Debug`, `Clone`, and `PartialEq`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Closure {
+    pub params: Vec<Rc<str>>,
+    /// The body of the function, as an [`AstNode::Environment`]
+    pub body: Rc<AstNode>,
+    /// The return statement of the function, as an [`AstNode::Environment`]
+    pub r#return: Rc<AstNode>,
+    /// The bindings the function inherits, with their values at the definition of the function
+    pub captured: Rc<Environment>,
+    /// The span of the name of the function in its declaration (see [`AstNode::Function`])
+    pub span: Option<Span>,
+}
+
+impl Value {
+    /// Gets the value of a literal node, or `None` for other nodes
+    pub fn from_literal(node: &AstNode) -> Option<Value> {
+        match node {
+            AstNode::Integer(num) => return Some(Value::Integer(*num)),
+            AstNode::Float(num) => return Some(Value::Float(*num)),
+            AstNode::Boolean(b) => return Some(Value::Boolean(*b)),
+            AstNode::String(s) => return Some(Value::String(s.clone())),
+            _ => return None,
+        }
+    }
+
+    /// Converts the value into an AST node
+    ///
+    /// Environments become [`AstNode::Environment`]s of `let` bindings, and functions become [`AstNode::Function`]s whose body has the inherited bindings as its parent environment.
+    pub fn to_node(&self) -> AstNode {
+        match self {
+            Value::Integer(num) => return AstNode::Integer(*num),
+            Value::Float(num) => return AstNode::Float(*num),
+            Value::Boolean(b) => return AstNode::Boolean(*b),
+            Value::String(s) => return AstNode::String(s.clone()),
+            Value::Environment { name, bindings } => {
+                let bindings = let_bindings(bindings.iter());
+                return AstNode::Environment { name: name.clone(), bindings, parent: None };
+            },
+            Value::Function(closure) => {
+                let parent = AstNode::Environment { name: None, bindings: let_bindings(closure.captured.visible().iter()), parent: None };
+                let body = AstNode::Environment {
+                    name: closure.body.get_name(),
+                    bindings: closure.body.get_bindings().unwrap_or_default(),
+                    parent: Some(Rc::new(parent)),
+                };
+                let params = closure.params.iter().map(|param| Rc::new(AstNode::Identifier(param.clone()))).collect();
+                return AstNode::Function { params: Rc::new(AstNode::FunctionArgs(params)), body: Rc::new(body), r#return: closure.r#return.clone(), span: closure.span };
+            },
+        }
+    }
+
+    /// Gets the number of an integer or a float, or `None` for other values
+    pub fn as_number(&self) -> Option<Number> {
+        match self {
+            Value::Integer(num) => return Some(Number::Integer(*num)),
+            Value::Float(num) => return Some(Number::Float(*num)),
+            _ => return None,
+        }
+    }
+
+    /// Gets the name of the type of the value, as used in error messages, e.g., `an integer`
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Integer(_) => "an integer",
+            Value::Float(_) => "a float",
+            Value::Boolean(_) => "a boolean",
+            Value::String(_) => "a string",
+            Value::Environment { .. } => "an environment",
+            Value::Function(_) => "a function",
+        }
+    }
+
+    /// Looks up a member of an environment, where `path` is the accessed expression as written in the source, e.g., `config.database`
+    ///
+    /// The path is only used in errors, to report which segment of an accessor chain failed.
+    ///
+    /// # Errors
+    /// * [`EvalError::NotAnEnvironment`]: The value is not an environment.
+    /// * [`EvalError::MissingMember`]: The environment has no binding of the name. The error lists the names of the first [`LISTED_MEMBERS`] bindings of the environment.
+    pub fn member(&self, path: &str, name: &str) -> Result<Value, EvalError> {
+        let Value::Environment { bindings, .. } = self else {
+            return Err(EvalError::NotAnEnvironment(path.to_string(), name.to_string(), self.type_name().to_string()));
+        };
+        return bindings.get(name)
+            .cloned()
+            .ok_or_else(|| missing_member(path, name, bindings));
+    }
+
+    /// Gets a binding of an environment, converted into a Rust type (see [Conversions](self#conversions))
+    ///
+    /// The path names a binding of the environment, or a binding of a nested environment in an accessor chain, e.g., `database.port`.
+    /// The errors are those of evaluating the path as an expression in the environment.
+    ///
+    /// # Errors
+    /// * [`EvalError::UndefinedIdentifier`]: The environment has no binding of the first name of the path.
+    /// * [`EvalError::NotAnEnvironment`] and [`EvalError::MissingMember`]: A later name of the path is not found (see [`Value::member`]).
+    /// * [`EvalError::UnexpectedType`]: The binding cannot be converted into the type.
+    ///
+    /// # Examples
+    /// ```
+    /// use envlang::evaluator::Evaluator;
+    /// use envlang::parse_source;
+    ///
+    /// let ast = parse_source("let port = 8000 + 80;\nlet database = { let host = \"db\"; let debug = false; };\n").unwrap();
+    /// let result = Evaluator::new().evaluate(&ast).unwrap();
+    /// assert_eq!(result.get::<i64>("port"), Ok(8080));
+    /// assert_eq!(result.get::<String>("database.host").as_deref(), Ok("db"));
+    /// assert_eq!(result.get_env("database").map(|env| env.len()), Ok(2));
+    /// assert!(result.get::<bool>("database.port").is_err());
+    /// ```
+    pub fn get<T: TryFrom<Value, Error = EvalError>>(&self, path: &str) -> Result<T, EvalError> {
+        return T::try_from(self.lookup(path)?.clone());
+    }
+
+    /// Gets the bindings of an environment bound in an environment (see [`Value::get`])
+    ///
+    /// # Errors
+    /// * Any errors of looking up the path with [`Value::get`].
+    /// * [`EvalError::UnexpectedType`]: The binding is not an environment.
+    pub fn get_env(&self, path: &str) -> Result<&Bindings, EvalError> {
+        match self.lookup(path)? {
+            Value::Environment { bindings, .. } => return Ok(bindings),
+            value => return Err(unexpected("an environment", value)),
+        }
+    }
+
+    /// Looks up the binding an accessor chain names in an environment
+    fn lookup(&self, path: &str) -> Result<&Value, EvalError> {
+        let mut names = path.split('.');
+        let first = names.next().unwrap_or_default();
+        let mut value = match self {
+            Value::Environment { bindings, .. } => bindings.get(first).ok_or_else(|| EvalError::UndefinedIdentifier(first.to_string()))?,
+            _ => return Err(unexpected("an environment", self)),
+        };
+        let mut accessed = first;
+        for name in names {
+            let Value::Environment { bindings, .. } = value else {
+                return Err(EvalError::NotAnEnvironment(accessed.to_string(), name.to_string(), value.type_name().to_string()));
+            };
+            value = bindings.get(name).ok_or_else(|| missing_member(accessed, name, bindings))?;
+            accessed = &path[..accessed.len() + 1 + name.len()];
+        }
+        return Ok(value);
+    }
+
+    /// Applies a binary operator to the value and a right-hand operand (see the [module documentation](self))
+    ///
+    /// # Errors
+    /// * [`EvalError::InvalidOperands`]: The operator is not defined for the types of the operands.
+    /// * [`EvalError::Numeric`]: A numeric operation fails, e.g., by overflowing or dividing by zero.
+    pub fn binary(&self, operator: &Operators, right: &Value) -> Result<Value, EvalError> {
+        let invalid = || EvalError::InvalidOperands(operator.to_string(), self.type_name().to_string(), right.type_name().to_string());
+        if let (Some(a), Some(b)) = (self.as_number(), right.as_number()) {
+            match operator {
+                Operators::Arithmetic(op) => {
+                    return numeric::arithmetic(op, a, b)
+                        .map(Value::from)
+                        .map_err(|err| EvalError::Numeric(operator.to_string(), err));
+                },
+                Operators::Comparison(op) => return Ok(Value::Boolean(numeric::compare(op, a, b))),
+                _ => return Err(invalid()),
+            }
+        }
+        match (self, right) {
+            (Value::Boolean(a), Value::Boolean(b)) => match operator {
+                Operators::Logical(LogicalOperators::AND) => return Ok(Value::Boolean(*a && *b)),
+                Operators::Logical(LogicalOperators::OR) => return Ok(Value::Boolean(*a || *b)),
+                Operators::Comparison(ComparisonOperators::EQ) => return Ok(Value::Boolean(a == b)),
+                Operators::Comparison(ComparisonOperators::NEQ) => return Ok(Value::Boolean(a != b)),
+                _ => return Err(invalid()),
+            },
+            (Value::String(a), Value::String(b)) => match operator {
+                Operators::Arithmetic(ArithmeticOperators::ADD) => {
+                    let mut joined = a.to_string();
+                    joined.push_str(b);
+                    return Ok(Value::String(Rc::from(joined)));
+                },
+                Operators::Comparison(ComparisonOperators::EQ) => return Ok(Value::Boolean(a == b)),
+                Operators::Comparison(ComparisonOperators::NEQ) => return Ok(Value::Boolean(a != b)),
+                _ => return Err(invalid()),
+            },
+            _ => return Err(invalid()),
+        }
+    }
+
+    /// Applies a unary operator to the value (see the [module documentation](self))
+    ///
+    /// # Errors
+    /// * [`EvalError::InvalidOperand`]: The operator is not defined for the type of the operand.
+    /// * [`EvalError::Numeric`]: Negating the number overflows.
+    pub fn unary(&self, op: &Operators) -> Result<Value, EvalError> {
+        match (op, self) {
+            (Operators::Logical(LogicalOperators::NOT), Value::Boolean(b)) => return Ok(Value::Boolean(!b)),
+            (Operators::Arithmetic(ArithmeticOperators::SUBTRACT), Value::Integer(_) | Value::Float(_)) => {
+                let num = self.as_number().ok_or_else(|| EvalError::InvalidOperand(op.to_string(), self.type_name().to_string()))?;
+                return numeric::negate(num).map(Value::from).map_err(|err| EvalError::Numeric(op.to_string(), err));
+            },
+            (Operators::Arithmetic(ArithmeticOperators::ADD), Value::Integer(_) | Value::Float(_)) => return Ok(self.clone()),
+            _ => return Err(EvalError::InvalidOperand(op.to_string(), self.type_name().to_string())),
+        }
+    }
+}
+
+impl From<Number> for Value {
+    fn from(num: Number) -> Self {
+        match num {
+            Number::Integer(num) => return Value::Integer(num),
+            Number::Float(num) => return Value::Float(num),
+        }
+    }
+}
+
+impl From<isize> for Value {
+    fn from(num: isize) -> Self { Value::Integer(num) }
+}
+
+impl From<f64> for Value {
+    fn from(num: f64) -> Self { Value::Float(num) }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self { Value::Boolean(b) }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self { Value::String(Rc::from(s)) }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self { Value::String(Rc::from(s)) }
+}
+
+impl From<Rc<str>> for Value {
+    fn from(s: Rc<str>) -> Self { Value::String(s) }
+}
+
+/// Converts bindings into an anonymous environment
+impl From<Bindings> for Value {
+    fn from(bindings: Bindings) -> Self { Value::Environment { name: None, bindings: Rc::new(bindings) } }
+}
+
+/// Converts a map into an anonymous environment, with its bindings in the order of the keys
+impl<K: Into<Rc<str>>, V: Into<Value>> From<BTreeMap<K, V>> for Value {
+    fn from(map: BTreeMap<K, V>) -> Self {
+        return Value::from(map.into_iter().map(|(name, value)| (name.into(), value.into())).collect::<Bindings>());
+    }
+}
+
+/// Converts a map into an anonymous environment, with its bindings sorted by name, as a `HashMap` has no order
+#[cfg(feature = "std")]
+impl<K: Into<Rc<str>> + Ord, V: Into<Value>> From<HashMap<K, V>> for Value {
+    fn from(map: HashMap<K, V>) -> Self {
+        return Value::from(map.into_iter().collect::<BTreeMap<K, V>>());
+    }
+}
+
+impl TryFrom<Value> for isize {
+    type Error = EvalError;
+
+    fn try_from(value: Value) -> Result<Self, EvalError> {
+        match value {
+            Value::Integer(num) => return Ok(num),
+            _ => return Err(unexpected("an integer", &value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = EvalError;
+
+    fn try_from(value: Value) -> Result<Self, EvalError> {
+        let num = isize::try_from(value)?;
+        return i64::try_from(num).map_err(|_| EvalError::Numeric("i64".to_string(), numeric::NumericError::Overflow));
+    }
+}
+
+/// Converts integers and floats, promoting integers to floats
+impl TryFrom<Value> for f64 {
+    type Error = EvalError;
+
+    fn try_from(value: Value) -> Result<Self, EvalError> {
+        match value.as_number() {
+            Some(num) => return Ok(num.as_float()),
+            None => return Err(unexpected("a float", &value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = EvalError;
+
+    fn try_from(value: Value) -> Result<Self, EvalError> {
+        match value {
+            Value::Boolean(b) => return Ok(b),
+            _ => return Err(unexpected("a boolean", &value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for Rc<str> {
+    type Error = EvalError;
+
+    fn try_from(value: Value) -> Result<Self, EvalError> {
+        match value {
+            Value::String(s) => return Ok(s),
+            _ => return Err(unexpected("a string", &value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = EvalError;
+
+    fn try_from(value: Value) -> Result<Self, EvalError> {
+        return Rc::<str>::try_from(value).map(|s| s.to_string());
+    }
+}
+
+impl TryFrom<Value> for Bindings {
+    type Error = EvalError;
+
+    fn try_from(value: Value) -> Result<Self, EvalError> {
+        match value {
+            Value::Environment { bindings, .. } => return Ok(Rc::unwrap_or_clone(bindings)),
+            _ => return Err(unexpected("an environment", &value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for BTreeMap<String, Value> {
+    type Error = EvalError;
+
+    fn try_from(value: Value) -> Result<Self, EvalError> {
+        let bindings = Bindings::try_from(value)?;
+        return Ok(bindings.iter().map(|(name, value)| (name.to_string(), value.clone())).collect());
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<Value> for HashMap<String, Value> {
+    type Error = EvalError;
+
+    fn try_from(value: Value) -> Result<Self, EvalError> {
+        let bindings = Bindings::try_from(value)?;
+        return Ok(bindings.iter().map(|(name, value)| (name.to_string(), value.clone())).collect());
+    }
+}
+
+/// Creates the error for a member missing from an environment, listing the members the environment has
+fn missing_member(path: &str, name: &str, bindings: &Bindings) -> EvalError {
+    let mut members: Vec<String> = bindings.iter().take(LISTED_MEMBERS).map(|(member, _)| format!("'{}'", member)).collect();
+    if bindings.len() > LISTED_MEMBERS {
+        members.push("…".to_string());
+    }
+    let available = if members.is_empty() { "none".to_string() } else { members.join(", ") };
+    return EvalError::MissingMember(path.to_string(), name.to_string(), available);
+}
+
+/// Creates the error for a value that does not have the expected type
+fn unexpected(expected: &str, value: &Value) -> EvalError {
+    return EvalError::UnexpectedType(expected.to_string(), value.type_name().to_string());
+}
+
+/// Converts named values into `let` bindings of their nodes
+fn let_bindings<'a>(bindings: impl Iterator<Item = (&'a Rc<str>, &'a Value)>) -> Vec<Rc<AstNode>> {
+    return bindings
+        .map(|(name, value)| Rc::new(AstNode::Let { name: name.clone(), value: Some(Rc::new(value.to_node())), inherit: None, span: None }))
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::numeric::NumericError;
+
+    #[test]
+    fn applies_operators_with_promotion() {
+        let add = Operators::Arithmetic(ArithmeticOperators::ADD);
+        let eq = Operators::Comparison(ComparisonOperators::EQ);
+        assert_eq!(Value::Integer(2).binary(&add, &Value::Integer(3)), Ok(Value::Integer(5)));
+        assert_eq!(Value::Integer(2).binary(&add, &Value::Float(0.5)), Ok(Value::Float(2.5)));
+        assert_eq!(Value::Integer(1).binary(&eq, &Value::Float(1.0)), Ok(Value::Boolean(true)));
+        assert_eq!(Value::String("en".into()).binary(&add, &Value::String("v".into())), Ok(Value::String("env".into())));
+        assert_eq!(
+            Value::Integer(isize::MAX).binary(&add, &Value::Integer(1)),
+            Err(EvalError::Numeric("+".into(), NumericError::Overflow)),
+        );
+        assert_eq!(
+            Value::String("a".into()).binary(&add, &Value::Integer(1)),
+            Err(EvalError::InvalidOperands("+".into(), "a string".into(), "an integer".into())),
+        );
+        assert_eq!(
+            Value::Boolean(true).unary(&Operators::Arithmetic(ArithmeticOperators::SUBTRACT)),
+            Err(EvalError::InvalidOperand("-".into(), "a boolean".into())),
+        );
+    }
+
+    #[test]
+    fn converts_from_and_into_rust_types() {
+        assert_eq!(Value::from(8080), Value::Integer(8080));
+        assert_eq!(i64::try_from(Value::Integer(-3)), Ok(-3));
+        assert_eq!(f64::try_from(Value::Integer(2)), Ok(2.0));
+        assert_eq!(bool::try_from(Value::from(true)), Ok(true));
+        assert_eq!(String::try_from(Value::from("host")), Ok("host".to_string()));
+        assert_eq!(isize::try_from(Value::Float(1.5)), Err(EvalError::UnexpectedType("an integer".into(), "a float".into())));
+        assert_eq!(Rc::<str>::try_from(Value::Boolean(false)), Err(EvalError::UnexpectedType("a string".into(), "a boolean".into())));
+
+        let map = std::collections::HashMap::from([("b", Value::from(2)), ("a", Value::from("x"))]);
+        let env = Value::from(map);
+        let bindings = Bindings::try_from(env.clone()).unwrap();
+        assert_eq!(bindings.iter().map(|(name, _)| name.as_ref()).collect::<Vec<_>>(), vec!["a", "b"]);
+        let map: std::collections::HashMap<String, Value> = env.try_into().unwrap();
+        assert_eq!(map["b"], Value::Integer(2));
+        assert!(BTreeMap::<String, Value>::try_from(Value::Integer(1)).is_err());
+    }
+
+    #[test]
+    fn gets_bindings_by_path() {
+        let database = Value::from(BTreeMap::from([("port", 5432)]));
+        let env = Value::from(BTreeMap::from([("database", database), ("name", Value::from("app"))]));
+        assert_eq!(env.get::<isize>("database.port"), Ok(5432));
+        assert_eq!(env.get::<isize>("db"), Err(EvalError::UndefinedIdentifier("db".into())));
+        assert_eq!(env.get::<isize>("database.host"), Err(EvalError::MissingMember("database".into(), "host".into(), "'port'".into())));
+        assert_eq!(env.get::<isize>("database.port.x"), Err(EvalError::NotAnEnvironment("database.port".into(), "x".into(), "an integer".into())));
+        assert_eq!(env.get_env("name"), Err(EvalError::UnexpectedType("an environment".into(), "a string".into())));
+        assert_eq!(Value::Integer(1).get::<isize>("a"), Err(EvalError::UnexpectedType("an environment".into(), "an integer".into())));
+    }
+
+    #[test]
+    fn converts_to_nodes() {
+        let env = Value::Environment { name: Some("e".into()), bindings: Rc::new([("a".into(), Value::Float(1.5))].into_iter().collect()) };
+        assert_eq!(env.member("e", "a"), Ok(Value::Float(1.5)));
+        assert_eq!(env.member("e", "b"), Err(EvalError::MissingMember("e".into(), "b".into(), "'a'".into())));
+        assert_eq!(Value::from(Bindings::new()).member("e", "b"), Err(EvalError::MissingMember("e".into(), "b".into(), "none".into())));
+        assert_eq!(env.to_node().get_bindings().unwrap()[0].as_ref(), &AstNode::Let { name: "a".into(), value: Some(Rc::new(AstNode::Float(1.5))), inherit: None, span: None });
+        assert_eq!(Value::from_literal(&AstNode::String("s".into())), Some(Value::String("s".into())));
+        assert_eq!(Value::from_literal(&AstNode::Identifier("s".into())), None);
+    }
+}
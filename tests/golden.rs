@@ -0,0 +1,88 @@
+//! Golden-file tests of complete Envlang programs
+//!
+//! Every `tests/cases/*.envl` program is run through the pipeline, and the output is compared against the `.expected` file next to it.
+//! The output has two sections:
+//! * `ast`: The parsed program as printed by the pretty-printer, or the rendered error if the program does not parse.
+//! * `diagnostics`: The rendered diagnostics of the default lints.
+//!
+//! Evaluation output will be added as a third section once Envlang has an evaluator.
+//!
+//! Running the tests with `ENVLANG_UPDATE_GOLDEN=1` rewrites the `.expected` files from the current output instead of comparing, so that language changes are reviewed as diffs of those files:
+//!
+//! ```text
+//! ENVLANG_UPDATE_GOLDEN=1 cargo test --test golden
+//! ```
+
+// Explicit `return`s are the house style of this crate.
+#![allow(clippy::needless_return)]
+
+use envlang::config::Config;
+use envlang::diagnostic::Diagnostic;
+use envlang::io::SourceFile;
+use envlang::printer::print_ast;
+use envlang::snippet::render;
+use envlang::{check_source, parse_source};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory of the golden-file cases
+const CASES: &str = "tests/cases";
+
+/// Environment variable enabling the update mode
+const UPDATE: &str = "ENVLANG_UPDATE_GOLDEN";
+
+/// Finds every case in the cases directory, sorted by path
+fn discover() -> Vec<PathBuf> {
+    let mut cases: Vec<PathBuf> = fs::read_dir(CASES)
+        .expect("Cases directory should exist")
+        .map(|entry| entry.expect("Cases directory should be readable").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "envl"))
+        .collect();
+    cases.sort();
+    return cases;
+}
+
+/// Runs a case through the pipeline and renders its output
+fn run(path: &Path) -> String {
+    let name = path.file_name().unwrap().to_string_lossy().into_owned();
+    let text = fs::read_to_string(path).expect("Case should be readable");
+    let source = SourceFile::new(name, text.as_str());
+
+    let mut output = String::from("=== ast\n");
+    match parse_source(&text) {
+        Ok(ast) => output.push_str(&print_ast(&ast)),
+        Err(err) => output.push_str(&render(&Diagnostic::from_error(&err, Some(&source)), &source)),
+    }
+
+    output.push_str("=== diagnostics\n");
+    if let Ok(diagnostics) = check_source(&text, &Config::default()) {
+        for diagnostic in diagnostics {
+            output.push_str(&render(&diagnostic, &source));
+        }
+    }
+    return output;
+}
+
+#[test]
+fn golden_files_match() {
+    let update = std::env::var_os(UPDATE).is_some_and(|value| value != "0");
+    let mut failures = Vec::new();
+    for case in discover() {
+        let actual = run(&case);
+        let expected_path = case.with_extension("expected");
+        if update {
+            fs::write(&expected_path, &actual).expect("Expected file should be writable");
+            continue;
+        }
+        match fs::read_to_string(&expected_path) {
+            Ok(expected) if expected == actual => (),
+            Ok(expected) => failures.push(format!("{}:\n--- expected\n{}--- actual\n{}", case.display(), expected, actual)),
+            Err(_) => failures.push(format!("{}: missing {}", case.display(), expected_path.display())),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} golden file(s) differ, rerun with {}=1 to update them:\n\n{}",
+        failures.len(), UPDATE, failures.join("\n"),
+    );
+}
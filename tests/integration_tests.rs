@@ -9,8 +9,8 @@ fn invalid_file_extension() {
 
     assert_eq!(
         output.status.code(),
-        Some(101), // Note that Rust always sets exit code 101 on panics, so this is not IO-specific
-        "Expected exit code 101, got {:?}", output.status.code()
+        Some(7),
+        "Expected exit code 7, got {:?}", output.status.code()
     );
 }
 
@@ -40,4 +40,384 @@ fn too_many_arguments() {
         Some(2),
         "Expected exit code 2, got {:?}", output.status.code()
     );
-}
\ No newline at end of file
+}
+
+#[test]
+fn unknown_option() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("--frobnicate")
+        .arg("tests/data/io_validextension.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(3),
+        "Expected exit code 3, got {:?}", output.status.code()
+    );
+}
+
+#[test]
+fn unknown_lint() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("--deny")
+        .arg("unused-bindings")
+        .arg("tests/data/io_validextension.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(3),
+        "Expected exit code 3, got {:?}", output.status.code()
+    );
+}
+
+#[test]
+fn invalid_config_file() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("--config")
+        .arg("tests/data/invalid_config.toml")
+        .arg("tests/data/io_validextension.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(4),
+        "Expected exit code 4, got {:?}", output.status.code()
+    );
+}
+
+#[test]
+fn discovered_config_is_honored() {
+    // tests/data/config/envlang.toml sets a maximum file size smaller than the script
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("tests/data/config/nested/script.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(5),
+        "Expected exit code 5, got {:?}", output.status.code()
+    );
+}
+
+#[test]
+fn flags_override_config() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("--max-file-size")
+        .arg("1024")
+        .arg("tests/data/config/nested/script.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "Expected exit code 0, got {:?}", output.status.code()
+    );
+}
+
+#[test]
+fn allowed_extension() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("--ext")
+        .arg("txt")
+        .arg("tests/data/io_validscript.txt")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "Expected exit code 0, got {:?}", output.status.code()
+    );
+}
+
+#[test]
+fn disabled_extension_check() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("--no-ext-check")
+        .arg("tests/data/io_validscript.txt")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "Expected exit code 0, got {:?}", output.status.code()
+    );
+}
+
+#[test]
+fn warnings_are_not_errors() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("tests/data/lints/unused_parameter.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "Expected exit code 0, got {:?}", output.status.code()
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning[W0002]: Unused parameter 'a' in function 'f'\n --> tests/data/lints/unused_parameter.envl:1:9"), "{}", stderr);
+}
+
+#[test]
+fn deny_warnings() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("--deny-warnings")
+        .arg("tests/data/lints/unused_parameter.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(6),
+        "Expected exit code 6, got {:?}", output.status.code()
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("error[W0002]"));
+}
+
+#[test]
+fn unmatched_brace() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("tests/data/parser/unmatched_brace.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(9),
+        "Expected exit code 9, got {:?}", output.status.code()
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("error[E0134]"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("4:1"));
+}
+
+#[test]
+fn literal_size_limits() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("--max-string-length")
+        .arg("5")
+        .arg("tests/data/lexer/long_literals.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(8),
+        "Expected exit code 8, got {:?}", output.status.code()
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("error[E0010]"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1:16"));
+
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("--max-string-length")
+        .arg("13")
+        .arg("--max-number-digits")
+        .arg("9")
+        .arg("tests/data/lexer/long_literals.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "Expected exit code 0, got {:?}", output.status.code()
+    );
+}
+
+#[test]
+fn runs_scripts() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("tests/data/runtime/port.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "Expected exit code 0, got {:?}", output.status.code()
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "let base = 8000;\nlet port = 8080;\n");
+}
+
+#[test]
+fn lexer_error() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("tests/data/lexer/unterminated_string.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(8),
+        "Expected exit code 8, got {:?}", output.status.code()
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("error[E0002]"));
+}
+
+#[test]
+fn runtime_error() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("tests/data/runtime/undefined_identifier.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(10),
+        "Expected exit code 10, got {:?}", output.status.code()
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("error[E0301]: Identifier 'z' is not defined\n --> tests/data/runtime/undefined_identifier.envl:2:5"), "{}", stderr);
+    assert!(stderr.contains("2 | let y = z + x;\n  |     ^"), "{}", stderr);
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn runtime_error_not_inherited() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("tests/data/runtime/not_inherited.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(output.status.code(), Some(10));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("error[E0312]: Identifier 'host' is declared in an enclosing environment, but not inherited"), "{}", stderr);
+    assert!(stderr.contains("`inherit (host)`"), "{}", stderr);
+}
+
+#[test]
+fn runtime_error_call_stack() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("tests/data/runtime/call_stack.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(output.status.code(), Some(10));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let inner = stderr.find("note: In the call to `inner`\n --> tests/data/runtime/call_stack.envl:5:12").expect(&stderr);
+    let outer = stderr.find("note: In the call to `outer`\n --> tests/data/runtime/call_stack.envl:7:14").expect(&stderr);
+    assert!(inner < outer, "{}", stderr);
+    assert!(stderr.contains("7 | let result = outer[1];\n  |              ^^^^^^^^"), "{}", stderr);
+    assert!(stderr.contains("4 | let fun outer[b] inherit (inner) = {\n  |         ----- The function is declared here"), "{}", stderr);
+}
+
+#[test]
+fn lenient_braces() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("--no-strict-braces")
+        .arg("tests/data/parser/unmatched_brace.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "Expected exit code 0, got {:?}", output.status.code()
+    );
+}
+
+#[test]
+fn info_reports_configuration() {
+    // tests/data/config/envlang.toml is found from its nested directory
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("info")
+        .arg("--max-diagnostics")
+        .arg("3")
+        .arg("--module-path")
+        .arg("vendor")
+        .current_dir("tests/data/config/nested")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "Expected exit code 0, got {:?}", output.status.code()
+    );
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(report.starts_with(&format!("# envlang {}\n", env!("CARGO_PKG_VERSION"))), "{}", report);
+    assert!(report.contains("# config file: "), "{}", report);
+    assert!(report.contains("strict = true\n"), "{}", report);
+    // Strict lints are denied in strict mode
+    assert!(report.contains("shadowing = \"deny\"\n"), "{}", report);
+    assert!(report.contains("unused-binding = \"warn\"\n"), "{}", report);
+    // Search paths are resolved against the directory of the configuration file, after those given on the command line
+    assert!(report.contains("search-paths = [\"vendor\", "), "{}", report);
+    assert!(report.contains("config/lib\"]\n"), "{}", report);
+    assert!(report.contains("max-file-size = 8\n"), "{}", report);
+    assert!(report.contains("max-diagnostics = 3\n"), "{}", report);
+
+    // The report is a configuration file with the effective settings
+    let config = envlang::config::Config::parse(&report).unwrap_or_else(|err| panic!("{}\n{}", err, report));
+    assert!(config.strict);
+    assert_eq!(config.strict_braces, Some(true));
+    assert_eq!(config.lints.get("shadowing"), Some(&envlang::config::LintLevel::Deny));
+    assert_eq!(config.module_paths[0], std::path::PathBuf::from("vendor"));
+    assert!(config.module_paths[1].ends_with("config/lib"), "{:?}", config.module_paths);
+    assert_eq!(config.limits.max_file_size, Some(8));
+    assert_eq!(config.limits.max_diagnostics, Some(3));
+    assert_eq!(config.extensions, envlang::io::ExtensionPolicy::Allow(vec!["envl".to_string()]));
+}
+
+#[test]
+fn info_takes_no_script() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("info")
+        .arg("tests/data/io_validextension.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(2),
+        "Expected exit code 2, got {:?}", output.status.code()
+    );
+}
+
+#[test]
+fn explain_parse_prints_steps_per_statement() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("explain-parse")
+        .arg("--no-config")
+        .arg("tests/data/parser/explain.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "Expected exit code 0, got {:?}", output.status.code()
+    );
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(report.starts_with("statement 1 (line 1):\n  | let x = 1 * 2 + 3;\n  token 'let' at line 1, column 1\n  enter assignment\n"), "{}", report);
+    assert!(report.contains("decide at line 1, column 15: '+' has additive precedence, so it ends the right-hand side"), "{}", report);
+    assert!(report.contains("decide at line 1, column 15: '+' has additive precedence, and takes '1 * 2' as its left-hand side"), "{}", report);
+    assert!(report.contains("\nstatement 2 (line 2):\n  | let y = {\n  |     let z = 3;\n  | };\n"), "{}", report);
+}
+
+#[test]
+fn explain_parse_reports_errors() {
+    let output: std::process::Output = Command::new(env!("CARGO_BIN_EXE_envlang"))
+        .arg("explain-parse")
+        .arg("--no-config")
+        .arg("tests/data/parser/missing_identifier.envl")
+        .output()
+        .expect("Failed to run envlang");
+
+    assert_eq!(
+        output.status.code(),
+        Some(9),
+        "Expected exit code 9, got {:?}", output.status.code()
+    );
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(report.contains("statement 2 (line 2):\n  | let =\n"), "{}", report);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("E0110"));
+}
@@ -0,0 +1,166 @@
+//! Fuzz-style tests asserting that the public lexer, parser, and evaluator API never panics
+//!
+//! The inputs are generated from a fixed seed, so that failures are reproducible.
+
+// Explicit `return`s are the house style of this crate.
+#![allow(clippy::needless_return)]
+
+use envlang::config::Config;
+use envlang::error::EnvlangError;
+use envlang::evaluator::{EvalError, Evaluator, DEFAULT_MAX_CALL_DEPTH, DEFAULT_MAX_DEPTH as EVAL_MAX_DEPTH};
+use envlang::lexer::Lexer;
+use envlang::parser::{Parser, ParserError, DEFAULT_MAX_DEPTH, DEFAULT_MAX_OPERATIONS};
+use envlang::unicodesegmenters::segment_graphemes;
+
+/// Fragments of Envlang syntax, so that the generated inputs get past the lexer reasonably often
+const FRAGMENTS: [&str; 36] = [
+    "let", "fun", "inherit", "return", "true", "false", "x", "foo", "5", "1.0",
+    "\"text\"", "'text'", "\"", "=", "==", "!=", "<", "<=", "+", "-", "*", "/",
+    "!", ".", ",", ";", "(", ")", "{", "}", "[", "]", " ", "\n", "#", "ä",
+];
+
+/// A minimal xorshift generator, as no randomness crates are available
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        return self.0;
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        return (self.next() % bound as u64) as usize;
+    }
+}
+
+/// Runs the lexer and parser on an input, with and without error recovery, discarding the result
+fn run(input: &str) {
+    if let Ok(tokens) = Lexer::new(segment_graphemes(input)).tokenize_with_locations() {
+        if let Ok(ast) = Parser::with_locations(tokens.clone()).parse() {
+            let _ = Evaluator::new().evaluate(&ast);
+        }
+        let _ = Parser::with_locations(tokens).parse_recovering();
+    }
+}
+
+#[test]
+fn arbitrary_fragments_never_panic() {
+    let mut rng = XorShift(0x5eed_1e55_c0ff_ee00);
+    for _ in 0..5000 {
+        let length = rng.below(24);
+        let input: String = (0..length)
+            .map(|_| FRAGMENTS[rng.below(FRAGMENTS.len())])
+            .collect::<Vec<&str>>()
+            .join(if rng.below(2) == 0 { "" } else { " " });
+        run(&input);
+    }
+}
+
+#[test]
+fn arbitrary_characters_never_panic() {
+    let mut rng = XorShift(0xdead_beef_cafe_f00d);
+    for _ in 0..5000 {
+        let length = rng.below(32);
+        let input: String = (0..length)
+            .filter_map(|_| char::from_u32(rng.below(0x250) as u32))
+            .collect();
+        run(&input);
+    }
+}
+
+#[test]
+fn truncated_programs_never_panic() {
+    let program = std::fs::read_to_string("tests/data/lints/unused_parameter.envl").unwrap();
+    for (end, _) in program.char_indices() {
+        run(&program[..end]);
+    }
+}
+
+#[test]
+fn deep_nesting_is_rejected() {
+    let environments = format!("let x = {}1{};\n", "{ ".repeat(100_000), " }".repeat(100_000));
+    let nested_operations = format!("let x = {}1{};\n", "1 + (".repeat(10_000), ")".repeat(10_000));
+    let functions = format!("let x = {}1{};\n", "{ let fun f[] = { return ".repeat(10_000), "; } }".repeat(10_000));
+    let accessions = format!("let a = {{}};\nlet b = a{};\n", ".a".repeat(20_000));
+    let calls = format!("let fun f[] = {{ return f; }}\nlet x = f{};\n", "[]".repeat(10_000));
+    for input in [environments, nested_operations, functions, accessions, calls] {
+        let tokens = Lexer::new(segment_graphemes(&input)).tokenize_with_locations().unwrap();
+        let result = Parser::with_locations(tokens).parse();
+        assert!(matches!(result, Err(ParserError::NestingTooDeep(_, DEFAULT_MAX_DEPTH))), "{:?}", result);
+    }
+}
+
+#[test]
+fn deep_input_is_rejected_by_entry_points() {
+    let accessions = format!("let a = {{}};\nlet b = a{};\n", ".a".repeat(20_000));
+    let calls = format!("let fun f[] = {{ return f; }}\nlet x = f{};\n", "[]".repeat(10_000));
+    let operations = format!("let x = 1{};\n", " + 1".repeat(100_000));
+    for input in [accessions, calls, operations] {
+        assert!(envlang::parse_source(&input).is_err());
+        assert!(envlang::check_source(&input, &Config::default()).is_err());
+        assert!(envlang::run_source(&input, &Config::default()).is_err());
+        assert!(envlang::format_source(&input, &Default::default()).is_err());
+    }
+}
+
+#[test]
+fn deep_evaluation_is_rejected() {
+    // Each function is within the parser limits, but calling through all of them nests the operations of every function
+    let chained = |depth: usize| {
+        let mut program = String::from("let fun f0[x] = { return x; }\n");
+        for i in 1..DEFAULT_MAX_CALL_DEPTH {
+            let operations = format!("{}f{}[x]{}", "(".repeat(depth), i - 1, " + 1)".repeat(depth));
+            program.push_str(&format!("let fun f{}[x] inherit (f{}) = {{ return {}; }}\n", i, i - 1, operations));
+        }
+        program.push_str(&format!("let r = f{}[1];\n", DEFAULT_MAX_CALL_DEPTH - 1));
+        return program;
+    };
+    let result = envlang::run_source(&chained(55), &Config::default());
+    assert!(matches!(&result, Err(EnvlangError::Runtime(err)) if err.without_span() == &EvalError::NestingTooDeep(EVAL_MAX_DEPTH)), "{:?}", result);
+    assert!(envlang::run_source(&chained(0), &Config::default()).is_ok());
+}
+
+#[test]
+fn long_chains_are_rejected() {
+    let input = format!("let x = 1{};\n", " + 1".repeat(100_000));
+    let tokens = Lexer::new(segment_graphemes(&input)).tokenize_with_locations().unwrap();
+    let result = Parser::with_locations(tokens).parse();
+    assert!(matches!(result, Err(ParserError::TooManyOperations(_, DEFAULT_MAX_OPERATIONS))), "{:?}", result);
+}
+
+#[test]
+fn deep_programs_are_processed() {
+    // Close to the limit: the global environment is the first level, and each nested assignment adds two, one for the value and one for its braces
+    let depth = DEFAULT_MAX_DEPTH / 2 - 1;
+    let source = format!("let x = {}y{};\nlet z = 1{};\n", "{ let y = ".repeat(depth), "; }".repeat(depth), " + 1".repeat(depth));
+    let ast = envlang::parse_source(&source).unwrap();
+
+    let printed = envlang::printer::print_ast(&ast);
+    assert_eq!(envlang::printer::print_ast(&envlang::parse_source(&printed).unwrap()), printed);
+    let _ = envlang::lint::lint(&ast, &Config::default());
+    let _ = envlang::specialize::specialize(&ast, &Default::default());
+    let _ = envlang::inline::inline(&ast, envlang::inline::DEFAULT_MAX_INLINE_SIZE);
+    let _ = Evaluator::new().evaluate(&ast);
+}
+
+#[test]
+fn long_chains_are_processed() {
+    // The global environment and the value of the assignment are the first two levels, and each call or accession adds one
+    let depth = DEFAULT_MAX_DEPTH - 2;
+    let source = format!(
+        "let a = {{ let a = a; }};\nlet fun f[] = {{ return f; }}\nlet b = a{};\nlet c = f{};\nlet d = 1{};\nlet e = {}1{}{};\n",
+        ".a".repeat(depth), "[]".repeat(depth), " + 1".repeat(DEFAULT_MAX_OPERATIONS),
+        // Each group is two levels, one for its parentheses and one for its chain
+        "(".repeat(depth / 2 - 1), " + 1)".repeat(depth / 2 - 1), " + 1".repeat(DEFAULT_MAX_OPERATIONS - depth / 2 + 1),
+    );
+    let ast = envlang::parse_source(&source).unwrap();
+
+    let printed = envlang::printer::print_ast(&ast);
+    assert_eq!(envlang::printer::print_ast(&envlang::parse_source(&printed).unwrap()), printed);
+    let _ = envlang::lint::lint(&ast, &Config::default());
+    let _ = envlang::specialize::specialize(&ast, &Default::default());
+    let _ = envlang::inline::inline(&ast, envlang::inline::DEFAULT_MAX_INLINE_SIZE);
+    let _ = Evaluator::new().evaluate(&ast);
+}